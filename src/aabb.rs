@@ -67,6 +67,24 @@ impl Aabb {
     /// Tests if the given ray hits the AABB.
     #[must_use]
     pub fn hit(&self, ray: &Ray, t_min: f32, t_max: f32) -> bool {
+        self.intersection(ray, t_min, t_max).is_some()
+    }
+
+    /// Returns the interval of the ray parameter during which the ray is inside the AABB,
+    /// clipped to `[t_min, t_max]`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::aabb::Aabb;
+    /// use crab_rt::ray::Ray;
+    /// use crab_rt::vec::{Point3, Vec3};
+    ///
+    /// let bbox = Aabb::new(Vec3::new(-1., -1., -1.), Vec3::new(1., 1., 1.));
+    /// let ray = Ray::new(Point3::new(-3., 0., 0.), Vec3::new(1., 0., 0.), 0.);
+    /// assert_eq!(bbox.intersection(&ray, 0., f32::INFINITY), Some((2., 4.)));
+    /// ```
+    #[must_use]
+    pub fn intersection(&self, ray: &Ray, mut t_min: f32, mut t_max: f32) -> Option<(f32, f32)> {
         for axis in 0..3 {
             let inv_axis_direction = ray.direction()[axis].recip();
             let mut t0 = (self.min[axis] - ray.origin()[axis]) * inv_axis_direction;
@@ -75,14 +93,14 @@ impl Aabb {
                 mem::swap(&mut t0, &mut t1);
             }
 
-            let t_min = f32::max(t0, t_min);
-            let t_max = f32::min(t1, t_max);
+            t_min = f32::max(t0, t_min);
+            t_max = f32::min(t1, t_max);
             if t_max <= t_min {
-                return false;
+                return None;
             }
         }
 
-        true
+        Some((t_min, t_max))
     }
 
     /// Returns the vertex with minimal coordinates on all axis of the AABB.
@@ -0,0 +1,363 @@
+//! Keyframed animation of the camera and of the objects.
+//!
+//! The time of the animation is the time of the rays, in seconds, so that the shutter interval
+//! of the camera of a frame selects the instant of the animation it shows.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::ops::{Add, Mul};
+
+use crate::camera::{Camera, ShutterCurve};
+use crate::vec::Point3;
+
+/// Interpolation between the keyframes of a [`Track`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    /// Straight lines between the keyframes, with sharp turns at the keyframes.
+    #[default]
+    Linear,
+    /// Catmull-Rom splines through the keyframes, with smooth turns.
+    CatmullRom,
+}
+
+/// Values that can be animated by a [`Track`].
+pub trait Keyframe:
+    Debug + Copy + Send + Sync + Add<Output = Self> + Mul<f32, Output = Self>
+{
+}
+
+impl<T: Debug + Copy + Send + Sync + Add<Output = T> + Mul<f32, Output = T>> Keyframe for T {}
+
+/// A value animated by keyframes, which stays at the value of the first and last keyframes
+/// before and after them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Track<T> {
+    /// Times and values of the keyframes, in increasing time order.
+    keyframes: Vec<(f32, T)>,
+    interpolation: Interpolation,
+}
+
+impl<T: Keyframe> Track<T> {
+    /// Constructs a new `Track` with a first keyframe at `time`, linearly interpolated.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::animation::Track;
+    ///
+    /// let track = Track::new(0., 1.).key(2., 3.);
+    /// assert_eq!(track.value(1.), 2.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(time: f32, value: T) -> Self {
+        Self {
+            keyframes: vec![(time, value)],
+            interpolation: Interpolation::Linear,
+        }
+    }
+
+    /// Consumes the `Track` and returns self with a keyframe added at `time`, replacing the
+    /// keyframe already at that time.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::animation::Track;
+    /// use crab_rt::vec::Point3;
+    ///
+    /// let track = Track::new(0., Point3::zero())
+    ///     .key(1., Point3::new(1., 0., 0.))
+    ///     .key(2., Point3::new(1., 1., 0.));
+    /// ```
+    #[must_use]
+    pub fn key(mut self, time: f32, value: T) -> Self {
+        match self.keyframes.binary_search_by(|(t, _)| t.total_cmp(&time)) {
+            Ok(i) => self.keyframes[i].1 = value,
+            Err(i) => self.keyframes.insert(i, (time, value)),
+        }
+
+        self
+    }
+
+    /// Consumes the `Track` and returns self with the given interpolation.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::animation::{Interpolation, Track};
+    ///
+    /// let track = Track::new(0., 0.)
+    ///     .key(1., 1.)
+    ///     .key(2., 0.)
+    ///     .interpolation(Interpolation::CatmullRom);
+    /// assert!(track.value(0.5) > 0.5);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn interpolation(self, interpolation: Interpolation) -> Self {
+        Self {
+            interpolation,
+            ..self
+        }
+    }
+
+    /// Returns the time interval covered by the keyframes.
+    #[inline]
+    #[must_use]
+    pub fn time_interval(&self) -> (f32, f32) {
+        (
+            self.keyframes[0].0,
+            self.keyframes[self.keyframes.len() - 1].0,
+        )
+    }
+
+    /// Returns the value at `time`.
+    #[must_use]
+    pub fn value(&self, time: f32) -> T {
+        let next = self.keyframes.partition_point(|(t, _)| *t <= time);
+        if next == 0 {
+            return self.keyframes[0].1;
+        }
+        if next == self.keyframes.len() {
+            return self.keyframes[next - 1].1;
+        }
+
+        let (t1, p1) = self.keyframes[next - 1];
+        let (t2, p2) = self.keyframes[next];
+        let s = (time - t1) / (t2 - t1);
+        match self.interpolation {
+            Interpolation::Linear => p1 * (1. - s) + p2 * s,
+            Interpolation::CatmullRom => {
+                let (m1, m2) = self.tangents(next - 1);
+                let (s2, s3) = (s * s, s * s * s);
+                p1 * (2. * s3 - 3. * s2 + 1.)
+                    + m1 * (s3 - 2. * s2 + s)
+                    + p2 * (-2. * s3 + 3. * s2)
+                    + m2 * (s3 - s2)
+            }
+        }
+    }
+
+    /// Returns points whose convex hull contains the values of the track during the time
+    /// interval: the values at its ends, the keyframes in it and, for splines, the control
+    /// points of their Bézier segments overlapping it.
+    #[must_use]
+    pub fn hull(&self, time_interval: (f32, f32)) -> Vec<T> {
+        let (start, end) = time_interval;
+        let mut points = vec![self.value(start), self.value(end)];
+        points.extend(
+            self.keyframes
+                .iter()
+                .filter(|(time, _)| *time > start && *time < end)
+                .map(|(_, value)| *value),
+        );
+
+        if self.interpolation == Interpolation::CatmullRom {
+            for i in 0..self.keyframes.len() - 1 {
+                let ((t1, p1), (t2, p2)) = (self.keyframes[i], self.keyframes[i + 1]);
+                if t2 > start && t1 < end {
+                    let (m1, m2) = self.tangents(i);
+                    points.extend([p1, p1 + m1 * (1. / 3.), p2 + m2 * (-1. / 3.), p2]);
+                }
+            }
+        }
+
+        points
+    }
+
+    /// Returns the tangents of the spline segment starting at keyframe `i`, relatively to the
+    /// duration of the segment.
+    fn tangents(&self, i: usize) -> (T, T) {
+        let last = self.keyframes.len() - 1;
+        let (t0, p0) = self.keyframes[i.saturating_sub(1)];
+        let (t1, p1) = self.keyframes[i];
+        let (t2, p2) = self.keyframes[i + 1];
+        let (t3, p3) = self.keyframes[usize::min(i + 2, last)];
+
+        let duration = t2 - t1;
+        (
+            (p2 + p0 * -1.) * (duration / (t2 - t0)),
+            (p3 + p1 * -1.) * (duration / (t3 - t1)),
+        )
+    }
+}
+
+/// A camera whose position, target, field of view and focus distance are animated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraAnimation {
+    lookfrom: Track<Point3>,
+    lookat: Track<Point3>,
+    vfov: Track<f32>,
+    focus_dist: Option<Track<f32>>,
+    aspect_ratio: f32,
+    aperture: f32,
+    shutter_curve: ShutterCurve,
+}
+
+impl CameraAnimation {
+    /// Constructs a new `CameraAnimation` from the tracks of the parameters of [`Camera::new`],
+    /// focused at the look-at point.
+    ///
+    /// # Panics
+    /// Panics if `aspect_ratio <= 0.`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::animation::{CameraAnimation, Interpolation, Track};
+    /// use crab_rt::vec::Point3;
+    ///
+    /// // Flies towards the origin while zooming out
+    /// let animation = CameraAnimation::new(
+    ///     Track::new(0., Point3::new(0., 1., 10.))
+    ///         .key(1., Point3::new(2., 1., 6.))
+    ///         .key(2., Point3::new(0., 1., 3.))
+    ///         .interpolation(Interpolation::CatmullRom),
+    ///     Track::new(0., Point3::zero()),
+    ///     Track::new(0., 20.).key(2., 40.),
+    ///     16. / 9.,
+    /// );
+    /// let camera = animation.camera(1.5);
+    /// ```
+    #[must_use]
+    pub fn new(
+        lookfrom: Track<Point3>,
+        lookat: Track<Point3>,
+        vfov: Track<f32>,
+        aspect_ratio: f32,
+    ) -> Self {
+        assert!(aspect_ratio > 0., "aspect_ratio should be greater than 0");
+
+        Self {
+            lookfrom,
+            lookat,
+            vfov,
+            focus_dist: None,
+            aspect_ratio,
+            aperture: 0.,
+            shutter_curve: ShutterCurve::Box,
+        }
+    }
+
+    /// Consumes the `CameraAnimation` and returns self with an animated focus distance.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::animation::{CameraAnimation, Track};
+    /// use crab_rt::vec::Point3;
+    ///
+    /// // Shifts the focus from the foreground to the background
+    /// let animation = CameraAnimation::new(
+    ///     Track::new(0., Point3::new(0., 1., 10.)),
+    ///     Track::new(0., Point3::zero()),
+    ///     Track::new(0., 30.),
+    ///     1.5,
+    /// )
+    /// .aperture(0.2)
+    /// .focus_dist(Track::new(0., 2.).key(3., 12.));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn focus_dist(self, focus_dist: Track<f32>) -> Self {
+        Self {
+            focus_dist: Some(focus_dist),
+            ..self
+        }
+    }
+
+    /// Consumes the `CameraAnimation` and returns self with the given aperture.
+    ///
+    /// # Panics
+    /// Panics if `aperture < 0.`.
+    #[inline]
+    #[must_use]
+    pub fn aperture(self, aperture: f32) -> Self {
+        assert!(aperture >= 0., "aperture should be greater or equal to 0");
+
+        Self { aperture, ..self }
+    }
+
+    /// Consumes the `CameraAnimation` and returns self with the given shutter curve.
+    #[inline]
+    #[must_use]
+    pub fn shutter_curve(self, shutter_curve: ShutterCurve) -> Self {
+        Self {
+            shutter_curve,
+            ..self
+        }
+    }
+
+    /// Returns the time interval covered by the keyframes of all the tracks.
+    #[must_use]
+    pub fn time_interval(&self) -> (f32, f32) {
+        [
+            self.lookfrom.time_interval(),
+            self.lookat.time_interval(),
+            self.vfov.time_interval(),
+        ]
+        .into_iter()
+        .chain(self.focus_dist.as_ref().map(Track::time_interval))
+        .fold(
+            (f32::INFINITY, f32::NEG_INFINITY),
+            |(start, end), (t0, t1)| (start.min(t0), end.max(t1)),
+        )
+    }
+
+    /// Returns the camera at `time`.
+    ///
+    /// # Panics
+    /// Panics if the lookfrom and lookat points are equal at `time`.
+    /// Panics if the exposure of a rolling shutter is not in range ]0, 1].
+    #[must_use]
+    pub fn camera(&self, time: f32) -> Camera {
+        let camera = Camera::new(
+            self.lookfrom.value(time),
+            self.lookat.value(time),
+            self.vfov.value(time),
+            self.aspect_ratio,
+        )
+        .aperture(self.aperture)
+        .shutter_curve(self.shutter_curve);
+
+        match &self.focus_dist {
+            Some(focus_dist) => camera.focus_dist(focus_dist.value(time)),
+            None => camera,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec::Vec3;
+
+    #[test]
+    fn track_goes_through_keyframes() {
+        for interpolation in [Interpolation::Linear, Interpolation::CatmullRom] {
+            let testee = Track::new(0., Vec3::zero())
+                .key(2., Vec3::new(2., 1., 0.))
+                .key(1., Vec3::new(1., 3., 0.))
+                .interpolation(interpolation);
+
+            assert_eq!(testee.value(-1.), Vec3::zero());
+            assert_eq!(testee.value(1.), Vec3::new(1., 3., 0.));
+            assert_eq!(testee.value(2.), Vec3::new(2., 1., 0.));
+            assert_eq!(testee.value(5.), Vec3::new(2., 1., 0.));
+        }
+    }
+
+    #[test]
+    fn catmull_rom_stays_in_hull() {
+        let testee = Track::new(0., 0.)
+            .key(1., 1.)
+            .key(1.5, -1.)
+            .key(4., 0.)
+            .interpolation(Interpolation::CatmullRom);
+        let hull = testee.hull((0., 4.));
+        let min = hull.iter().copied().fold(f32::INFINITY, f32::min);
+        let max = hull.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+
+        for i in 0..=400 {
+            let value = testee.value(i as f32 / 100.);
+            assert!(value >= min - 1e-5 && value <= max + 1e-5);
+        }
+    }
+}
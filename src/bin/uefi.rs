@@ -60,9 +60,10 @@ extern "efiapi" fn worker(arg: *mut c_void) {
                 let u = (x as f32 + rng.gen::<f32>()) / width as f32;
                 let v = (y as f32 + rng.gen::<f32>()) / height as f32;
 
-                let ray = raytracer.camera().ray(u, v);
-
-                let color = raytracer.cast(&ray, 0);
+                let color = raytracer
+                    .camera()
+                    .try_ray(u, v)
+                    .map_or_else(Color3::zero, |ray| raytracer.cast(&ray, 0));
                 pixels_row[x] =
                     (pixels_row[x] * (num_samples - 1) as f32 + color) / num_samples as f32;
 
@@ -181,9 +182,10 @@ fn main(_image_handle: Handle, mut system_table: SystemTable<Boot>) -> Status {
                 let u = (x as f32 + rng.gen::<f32>()) / width as f32;
                 let v = (y as f32 + rng.gen::<f32>()) / height as f32;
 
-                let ray = raytracer.camera().ray(u, v);
-
-                let color = raytracer.cast(&ray, 0);
+                let color = raytracer
+                    .camera()
+                    .try_ray(u, v)
+                    .map_or_else(Color3::zero, |ray| raytracer.cast(&ray, 0));
                 pixels_row[x] =
                     (pixels_row[x] * (num_samples - 1) as f32 + color) / num_samples as f32;
 
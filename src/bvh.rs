@@ -8,6 +8,9 @@ use crate::objects::Object;
 use crate::ray::Ray;
 use crate::utils::rng;
 
+/// An object along with its bounding box.
+type Bounded = (Option<Aabb>, Object);
+
 #[derive(Debug, Default)]
 pub struct BvhNode {
     bbox: Option<Aabb>,
@@ -18,57 +21,57 @@ pub struct BvhNode {
 
 impl BvhNode {
     #[must_use]
-    pub fn new(mut objects: Vec<Object>, time_interval: (f32, f32)) -> Self {
+    pub fn new(objects: Vec<Object>, time_interval: (f32, f32)) -> Self {
+        // The bounding box of each object is computed once for all the levels of the tree
+        let objects = objects
+            .into_iter()
+            .map(|object| (object.bounding_box(time_interval), object))
+            .collect();
+
+        Self::from_bounded(objects)
+    }
+
+    /// Constructs a new `BvhNode` from objects along with their bounding boxes.
+    fn from_bounded(mut objects: Vec<Bounded>) -> Self {
         let uniform = Uniform::from(0..3);
         let mut rng = rng();
         let axis = uniform.sample(&mut rng);
-        let comparator = |object1: &Object, object2: &Object| {
-            let bbox_1 = object1.bounding_box((0., 0.));
-            let bbox_2 = object2.bounding_box((0., 0.));
-
-            if bbox_1.is_none() || bbox_2.is_none() {
-                return Ordering::Less;
+        let comparator = |(bbox_1, _): &Bounded, (bbox_2, _): &Bounded| match (bbox_1, bbox_2) {
+            (Some(bbox_1), Some(bbox_2)) => {
+                bbox_1.min()[axis].partial_cmp(&bbox_2.min()[axis]).unwrap()
             }
-
-            bbox_1.unwrap().min()[axis]
-                .partial_cmp(&bbox_2.unwrap().min()[axis])
-                .unwrap()
+            _ => Ordering::Less,
         };
 
+        let bbox = objects
+            .iter()
+            .map(|(bbox, _)| *bbox)
+            .reduce(|lb, rb| lb.zip(rb).map(|(lb, rb)| Aabb::surrounding_box(&lb, &rb)))
+            .flatten();
+
         let (left, right): (Option<Box<dyn Hitable>>, Option<Box<dyn Hitable>>) =
             match objects.len() {
-                1 => (Some(Box::new(objects.remove(0))), None),
+                1 => (Some(Box::new(objects.remove(0).1)), None),
                 2 => {
                     let first = objects.remove(0);
                     let second = objects.remove(0);
 
                     if comparator(&first, &second) == Ordering::Less {
-                        (Some(Box::new(first)), Some(Box::new(second)))
+                        (Some(Box::new(first.1)), Some(Box::new(second.1)))
                     } else {
-                        (Some(Box::new(second)), Some(Box::new(first)))
+                        (Some(Box::new(second.1)), Some(Box::new(first.1)))
                     }
                 }
                 n => {
                     objects.sort_by(comparator);
                     let second_half = objects.split_off(n / 2);
                     (
-                        Some(Box::new(Self::new(objects, time_interval))),
-                        Some(Box::new(Self::new(second_half, time_interval))),
+                        Some(Box::new(Self::from_bounded(objects))),
+                        Some(Box::new(Self::from_bounded(second_half))),
                     )
                 }
             };
 
-        let left_bbox = left.as_ref().and_then(|a| a.bounding_box(time_interval));
-        let bbox = if right.is_none() {
-            left_bbox
-        } else {
-            let right_bbox = right.as_ref().and_then(|a| a.bounding_box(time_interval));
-
-            left_bbox
-                .zip(right_bbox)
-                .map(|(lb, rb)| Aabb::surrounding_box(&lb, &rb))
-        };
-
         Self { bbox, left, right }
     }
 }
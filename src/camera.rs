@@ -1,14 +1,63 @@
-use rand::distributions::{Distribution, Uniform};
+use core::f32::consts::{PI, TAU};
+use rand::Rng;
 
-use crate::ray::Ray;
+use crate::lens_system::LensSystem;
+use crate::ray::{Ray, RayDifferentials};
 use crate::utils::{random_in_unit_disk, rng};
 use crate::vec::{Point3, Vec3};
 
 #[cfg(not(feature = "std"))]
 use core_maths::*;
 
+/// Projection of the scene on the image of a [`Camera`].
+///
+/// The panoramic projections, equirectangular, cubemap and fisheye, ignore the aperture of the
+/// camera.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Projection {
+    /// Rays diverge from the lens through the image, covering the field of view.
+    #[default]
+    Perspective,
+    /// Rays are parallel and start from a plane of the size of the view, keeping the size of
+    /// objects independent of their distance.
+    Orthographic,
+    /// The full sphere of directions mapped to latitude and longitude, the center of the image
+    /// being in front of the camera.
+    Equirectangular,
+    /// The six faces of a cube around the camera in a 3 × 2 grid, +X, -X and +Y on the top row
+    /// and -Y, +Z and -Z on the bottom row, the axes being the right, up and back of the camera.
+    Cubemap,
+    /// A fisheye lens covering `fov` degrees across the height of the image.
+    Fisheye { mapping: FisheyeMapping, fov: f32 },
+}
+
+/// Opening of the shutter of a [`Camera`] over its time interval, which weights the instants
+/// blurred together in the image.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ShutterCurve {
+    /// The shutter is fully open during the whole interval.
+    #[default]
+    Box,
+    /// The shutter opens and closes linearly, fully open at the middle of the interval, which
+    /// gives motion trails fading at their ends.
+    Triangle,
+    /// The rows of the image are exposed one after the other from the top to the bottom, each
+    /// during the fraction `exposure` of the interval, which skews fast moving objects.
+    Rolling { exposure: f32 },
+}
+
+/// Mapping of the angle to the axis of a fisheye lens to the distance to the center of the image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FisheyeMapping {
+    /// The distance is proportional to the angle.
+    Equidistant,
+    /// The area of the image is proportional to the solid angle, like most fisheye lenses.
+    Equisolid,
+}
+
 #[derive(Debug, Clone)]
 pub struct Camera {
+    projection: Projection,
     origin: Point3,
     lower_left_corner: Point3,
     horizontal: Vec3,
@@ -17,8 +66,15 @@ pub struct Camera {
     v: Vec3,
     w: Vec3,
     lens_radius: f32,
+    /// Number of blades and rotation in degrees of a polygonal aperture.
+    aperture_blades: Option<(u32, f32)>,
+    lens_system: Option<LensSystem>,
     focus_dist: f32,
-    time_distribution: Option<Uniform<f32>>,
+    /// Signed distance of the eye from the center of panoramic cameras, towards their right.
+    stereo_offset: f32,
+    /// Interval during which the shutter is open.
+    time_interval: (f32, f32),
+    shutter_curve: ShutterCurve,
 }
 
 impl Camera {
@@ -56,6 +112,7 @@ impl Camera {
         let vertical = viewport_height * focus_dist * v;
 
         Self {
+            projection: Projection::Perspective,
             origin,
             horizontal,
             vertical,
@@ -64,11 +121,151 @@ impl Camera {
             v,
             w,
             lens_radius: 0.,
+            aperture_blades: None,
+            lens_system: None,
             focus_dist,
-            time_distribution: None,
+            stereo_offset: 0.,
+            time_interval: (0., 0.),
+            shutter_curve: ShutterCurve::Box,
         }
     }
 
+    /// Constructs a new orthographic `Camera` with the given lookfrom and lookat points, whose
+    /// view is `view_height` high and `aspect_ratio * view_height` wide.
+    ///
+    /// # Panics
+    /// Panics if `lookfrom == lookat`.
+    /// Panics if `view_height <= 0.`.
+    /// Panics if `aspect_ratio <= 0.`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::camera::{Camera, Projection};
+    /// use crab_rt::vec::Point3;
+    ///
+    /// // Isometric view of the origin
+    /// let camera = Camera::orthographic(Point3::new(10., 10., 10.), Point3::zero(), 4., 1.5);
+    /// assert_eq!(camera.projection(), Projection::Orthographic);
+    /// ```
+    #[must_use]
+    pub fn orthographic(
+        lookfrom: Point3,
+        lookat: Point3,
+        view_height: f32,
+        aspect_ratio: f32,
+    ) -> Self {
+        assert!(view_height > 0., "view_height should be greater than 0");
+
+        let camera = Self::new(lookfrom, lookat, 90., aspect_ratio);
+        let horizontal = aspect_ratio * view_height * camera.u;
+        let vertical = view_height * camera.v;
+
+        Self {
+            projection: Projection::Orthographic,
+            horizontal,
+            vertical,
+            lower_left_corner: camera.origin - horizontal / 2. - vertical / 2.,
+            ..camera
+        }
+    }
+
+    /// Constructs a new equirectangular `Camera` at lookfrom, looking at lookat from the center of
+    /// the image, which should be twice as wide as high.
+    ///
+    /// # Panics
+    /// Panics if `lookfrom == lookat`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::camera::Camera;
+    /// use crab_rt::vec::Point3;
+    ///
+    /// let camera = Camera::equirectangular(Point3::new(0., 1.7, 0.), Point3::new(0., 1.7, -1.));
+    /// ```
+    #[must_use]
+    pub fn equirectangular(lookfrom: Point3, lookat: Point3) -> Self {
+        Self {
+            projection: Projection::Equirectangular,
+            ..Self::new(lookfrom, lookat, 90., 2.)
+        }
+    }
+
+    /// Constructs a new cubemap `Camera` at lookfrom, whose -Z face looks at lookat, for images
+    /// whose aspect ratio is 3:2.
+    ///
+    /// # Panics
+    /// Panics if `lookfrom == lookat`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::camera::Camera;
+    /// use crab_rt::vec::Point3;
+    ///
+    /// // Faces along the axes of the world, to bake an environment map
+    /// let camera = Camera::cubemap(Point3::new(0., 1., 0.), Point3::new(0., 1., -1.));
+    /// ```
+    #[must_use]
+    pub fn cubemap(lookfrom: Point3, lookat: Point3) -> Self {
+        Self {
+            projection: Projection::Cubemap,
+            ..Self::new(lookfrom, lookat, 90., 1.5)
+        }
+    }
+
+    /// Constructs a new fisheye `Camera` with the given lookfrom and lookat points, covering
+    /// `fov` degrees across the height of the image.
+    ///
+    /// Outside of the field of view, the mapping goes on up to the back of the camera.
+    ///
+    /// # Panics
+    /// Panics if `lookfrom == lookat`.
+    /// Panics if `fov` is not in range ]0, 360].
+    /// Panics if `aspect_ratio <= 0.`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::camera::{Camera, FisheyeMapping};
+    /// use crab_rt::vec::Point3;
+    ///
+    /// // A circular fisheye looking at the sky
+    /// let camera = Camera::fisheye(
+    ///     Point3::zero(),
+    ///     Point3::new(0., 1., 0.),
+    ///     180.,
+    ///     1.,
+    ///     FisheyeMapping::Equisolid,
+    /// );
+    /// ```
+    #[must_use]
+    pub fn fisheye(
+        lookfrom: Point3,
+        lookat: Point3,
+        fov: f32,
+        aspect_ratio: f32,
+        mapping: FisheyeMapping,
+    ) -> Self {
+        assert!(fov > 0. && fov <= 360., "fov should be in range ]0, 360]");
+
+        Self {
+            projection: Projection::Fisheye { mapping, fov },
+            ..Self::new(lookfrom, lookat, 90., aspect_ratio)
+        }
+    }
+
+    /// Returns the projection of the camera.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::camera::{Camera, Projection};
+    ///
+    /// assert_eq!(Camera::default().projection(), Projection::Perspective);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn projection(&self) -> Projection {
+        self.projection
+    }
+
     /// Consumes the `Camera` and returns self after setting the vup.
     ///
     /// # Panics
@@ -98,7 +295,7 @@ impl Camera {
             lower_left_corner: self.origin
                 - horizontal / 2.
                 - vertical / 2.
-                - self.focus_dist * self.w,
+                - self.image_plane_dist() * self.w,
             u,
             v,
             ..self
@@ -127,6 +324,64 @@ impl Camera {
         }
     }
 
+    /// Consumes the `Camera` and returns self with an aperture shaped as a regular polygon with
+    /// the given number of blades, turned by `rotation` degrees, which gives polygonal bokeh.
+    ///
+    /// # Panics
+    /// Panics if `blades < 3`.
+    ///
+    /// # Example
+    /// ```
+    /// use crab_rt::camera::Camera;
+    /// use crab_rt::vec::{Point3, Vec3};
+    ///
+    /// let camera = Camera::new(Point3::zero(), Point3::new(1., 0., 0.), 20., 2.)
+    ///     .aperture(0.5)
+    ///     .aperture_blades(6, 15.);
+    /// ```
+    #[must_use]
+    pub fn aperture_blades(self, blades: u32, rotation: f32) -> Self {
+        assert!(blades >= 3, "blades should be at least 3");
+
+        Self {
+            aperture_blades: Some((blades, rotation.to_radians())),
+            ..self
+        }
+    }
+
+    /// Consumes the `Camera` and returns self tracing its rays through the given lens system,
+    /// focused at the focus distance of the camera, instead of the thin lens.
+    ///
+    /// The field of view is given by the lens and its film, the vfov and the aperture of the
+    /// camera being ignored.
+    ///
+    /// # Panics
+    /// Panics if the projection is not [`Projection::Perspective`].
+    /// Panics if the lens cannot focus at the focus distance.
+    ///
+    /// # Example
+    /// ```
+    /// use crab_rt::camera::Camera;
+    /// use crab_rt::lens_system::LensSystem;
+    /// use crab_rt::vec::Point3;
+    ///
+    /// let lens = LensSystem::parse("50 5 1.5 20\n-50 5 0 20\n0 45 0 10").unwrap();
+    /// let camera = Camera::new(Point3::zero(), Point3::new(0., 0., -3.), 40., 1.5).lens_system(lens);
+    /// ```
+    #[must_use]
+    pub fn lens_system(self, lens_system: LensSystem) -> Self {
+        assert_eq!(
+            self.projection,
+            Projection::Perspective,
+            "lens systems need a perspective projection"
+        );
+
+        Self {
+            lens_system: Some(lens_system.focus(self.focus_dist)),
+            ..self
+        }
+    }
+
     /// Consumes the `Camera` and returns self after setting the focus distance.
     ///
     /// # Panics
@@ -143,6 +398,11 @@ impl Camera {
     pub fn focus_dist(self, focus_dist: f32) -> Self {
         assert!(focus_dist > 0., "focus_dist should be greater than 0");
 
+        // The view of an orthographic camera does not depend on the focus
+        if self.projection == Projection::Orthographic {
+            return Self { focus_dist, ..self };
+        }
+
         let horizontal = self.horizontal / self.focus_dist * focus_dist;
         let vertical = self.vertical / self.focus_dist * focus_dist;
 
@@ -150,12 +410,89 @@ impl Camera {
             horizontal,
             vertical,
             lower_left_corner: self.origin - horizontal / 2. - vertical / 2. - focus_dist * self.w,
+            lens_system: self
+                .lens_system
+                .map(|lens_system| lens_system.focus(focus_dist)),
             focus_dist,
             ..self
         }
     }
 
-    /// Consumes the `Camera` and returns self after setting the time interval.
+    /// Consumes the `Camera` and returns self with the eye moved by `stereo_offset` towards the
+    /// right of the camera, negative offsets moving it to the left.
+    ///
+    /// Panoramic cameras turn the offset with the direction of each ray, so that a pair of
+    /// cameras with opposite offsets renders an omnidirectional stereo panorama. Other cameras
+    /// ignore the offset, see [`Camera::eye`] instead.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::camera::Camera;
+    /// use crab_rt::vec::Point3;
+    ///
+    /// // Half of a usual interocular distance
+    /// let ipd = 0.064;
+    /// let lookfrom = Point3::new(0., 1.7, 0.);
+    /// let lookat = Point3::new(0., 1.7, -1.);
+    /// let left = Camera::equirectangular(lookfrom, lookat).stereo_offset(-ipd / 2.);
+    /// let right = Camera::equirectangular(lookfrom, lookat).stereo_offset(ipd / 2.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn stereo_offset(self, stereo_offset: f32) -> Self {
+        Self {
+            stereo_offset,
+            ..self
+        }
+    }
+
+    /// Consumes the `Camera` and returns self as one eye of a stereo pair, moved by `offset`
+    /// towards the right of the camera, negative offsets giving the left eye, whose images
+    /// match at the `convergence` distance.
+    ///
+    /// The eyes look in the same direction and their images are shifted, an off-axis
+    /// projection which avoids the vertical parallax of toed-in cameras. Panoramic cameras use
+    /// [`Camera::stereo_offset`] and converge at infinity. Orthographic cameras have no
+    /// parallax.
+    ///
+    /// # Panics
+    /// Panics if `convergence <= 0.`.
+    ///
+    /// # Example
+    /// ```
+    /// use crab_rt::camera::Camera;
+    /// use crab_rt::vec::Point3;
+    ///
+    /// let camera = Camera::new(Point3::new(0., 1.7, 5.), Point3::new(0., 1., 0.), 40., 1.5);
+    /// let left = camera.clone().eye(-0.032, 5.);
+    /// let right = camera.eye(0.032, 5.);
+    /// ```
+    #[must_use]
+    pub fn eye(self, offset: f32, convergence: f32) -> Self {
+        assert!(convergence > 0., "convergence should be greater than 0");
+
+        if !matches!(
+            self.projection,
+            Projection::Perspective | Projection::Orthographic
+        ) {
+            return self.stereo_offset(offset);
+        }
+
+        // The center of the image of the eye looks at the point of convergence
+        let shift = offset * self.u;
+        Self {
+            origin: self.origin + shift,
+            lower_left_corner: self.lower_left_corner
+                + (1. - self.image_plane_dist() / convergence) * shift,
+            ..self
+        }
+    }
+
+    /// Consumes the `Camera` and returns self after setting the time interval during which the
+    /// shutter is open.
+    ///
+    /// # Panics
+    /// Panics if `time_interval.0 > time_interval.1`.
     ///
     /// # Example
     /// ```
@@ -167,23 +504,229 @@ impl Camera {
     /// ```
     #[must_use]
     pub fn time_interval(self, time_interval: (f32, f32)) -> Self {
+        assert!(
+            time_interval.0 <= time_interval.1,
+            "the time interval should not end before its start"
+        );
+
         Self {
-            time_distribution: Some(Uniform::from(time_interval.0..time_interval.1)),
+            time_interval,
             ..self
         }
     }
 
+    /// Consumes the `Camera` and returns self with the given shutter curve.
+    ///
+    /// # Panics
+    /// Panics if the exposure of a rolling shutter is not in range ]0, 1].
+    ///
+    /// # Example
+    /// ```
+    /// use crab_rt::camera::{Camera, ShutterCurve};
+    /// use crab_rt::vec::Point3;
+    ///
+    /// let camera = Camera::new(Point3::zero(), Point3::new(1., 0., 0.), 20., 2.)
+    ///     .time_interval((0., 1.))
+    ///     .shutter_curve(ShutterCurve::Rolling { exposure: 0.1 });
+    /// ```
+    #[must_use]
+    pub fn shutter_curve(self, shutter_curve: ShutterCurve) -> Self {
+        if let ShutterCurve::Rolling { exposure } = shutter_curve {
+            assert!(
+                exposure > 0. && exposure <= 1.,
+                "exposure should be in range ]0, 1]"
+            );
+        }
+
+        Self {
+            shutter_curve,
+            ..self
+        }
+    }
+
+    /// Returns the time interval during which the shutter is open, which the scene should be
+    /// built for.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::camera::Camera;
+    /// use crab_rt::scene::{Background, SceneBuilder};
+    ///
+    /// let camera = Camera::default().time_interval((0., 0.5));
+    /// let scene = SceneBuilder::new(Background::default())
+    ///     .time_interval(camera.shutter_interval())
+    ///     .build();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn shutter_interval(&self) -> (f32, f32) {
+        self.time_interval
+    }
+
+    /// Returns a ray for the point `(s, t)` of the image.
+    ///
+    /// # Panics
+    /// Panics if the camera has a lens system which blocks the ray, see [`Camera::try_ray`].
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::camera::Camera;
+    /// use crab_rt::vec::Point3;
+    ///
+    /// let camera = Camera::new(Point3::zero(), Point3::new(0., 0., -1.), 90., 1.);
+    /// assert_eq!(
+    ///     camera.ray(0.5, 0.5).direction().unit(),
+    ///     Point3::new(0., 0., -1.)
+    /// );
+    /// ```
     #[must_use]
     pub fn ray(&self, s: f32, t: f32) -> Ray {
+        self.try_ray(s, t)
+            .expect("the lens system of the camera should not block the ray")
+    }
+
+    /// Returns a ray for the point `(s, t)` of the image, or `None` if the lens system of the
+    /// camera blocks it. Cameras without a lens system never block rays.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::camera::Camera;
+    /// use crab_rt::vec::Point3;
+    ///
+    /// let camera = Camera::new(Point3::zero(), Point3::new(0., 0., -1.), 90., 1.);
+    /// assert!(camera.try_ray(0.5, 0.5).is_some());
+    /// ```
+    #[must_use]
+    pub fn try_ray(&self, s: f32, t: f32) -> Option<Ray> {
+        let sample = self.lens_sample();
+        self.ray_through_lens(s, t, &sample, self.shutter_time(t))
+    }
+
+    /// Returns a ray for the point `(s, t)` of the image with the differentials of the rays for
+    /// the points `(s + ds, t)` and `(s, t + dt)`, which usually are the size of a pixel, or
+    /// `None` if the lens system of the camera blocks it.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::camera::Camera;
+    /// use crab_rt::vec::Point3;
+    ///
+    /// let camera = Camera::new(Point3::zero(), Point3::new(0., 0., -1.), 90., 1.);
+    /// let ray = camera.ray_with_differentials(0.5, 0.5, 0.01, 0.01).unwrap();
+    /// assert!(ray.differentials().is_some());
+    /// ```
+    #[must_use]
+    pub fn ray_with_differentials(&self, s: f32, t: f32, ds: f32, dt: f32) -> Option<Ray> {
+        // The offset rays go through the same point of the lens at the same time
+        let sample = self.lens_sample();
+        let time = self.shutter_time(t);
+        let ray = self.ray_through_lens(s, t, &sample, time)?;
+        let (Some(rx), Some(ry)) = (
+            self.ray_through_lens(s + ds, t, &sample, time),
+            self.ray_through_lens(s, t + dt, &sample, time),
+        ) else {
+            return Some(ray);
+        };
+
+        Some(ray.with_differentials(RayDifferentials {
+            rx_origin: *rx.origin(),
+            rx_direction: *rx.direction(),
+            ry_origin: *ry.origin(),
+            ry_direction: *ry.direction(),
+        }))
+    }
+
+    /// Returns the distance from the origin to the plane of the image, the focus plane of
+    /// perspective cameras and the plane of the origin of orthographic ones.
+    #[inline]
+    const fn image_plane_dist(&self) -> f32 {
+        match self.projection {
+            Projection::Orthographic => 0.,
+            _ => self.focus_dist,
+        }
+    }
+
+    /// Samples a point of the aperture shape, in the unit disk.
+    fn lens_sample(&self) -> Vec3 {
+        self.aperture_blades
+            .map_or_else(random_in_unit_disk, |(blades, rotation)| {
+                random_in_polygon(blades, rotation)
+            })
+    }
+
+    /// Samples the time of a ray for the row `t` of the image, following the shutter curve.
+    fn shutter_time(&self, t: f32) -> f32 {
         let mut rng = rng();
-        let rd = self.lens_radius * random_in_unit_disk();
-        let offset = self.u * rd.x + self.v * rd.y;
+        let (start, end) = self.time_interval;
+
+        let fraction = match self.shutter_curve {
+            ShutterCurve::Box => rng.gen::<f32>(),
+            ShutterCurve::Triangle => rng.gen::<f32>().mul_add(0.5, 0.5 * rng.gen::<f32>()),
+            // The top row is exposed first
+            ShutterCurve::Rolling { exposure } => {
+                (1. - t.clamp(0., 1.)).mul_add(1. - exposure, exposure * rng.gen::<f32>())
+            }
+        };
+        fraction.mul_add(end - start, start)
+    }
+
+    /// Returns the ray at `time` for the point `(s, t)` of the image through the point of the
+    /// lens given by `sample` in the unit disk.
+    fn ray_through_lens(&self, s: f32, t: f32, sample: &Vec3, time: f32) -> Option<Ray> {
+        if let Some(lens_system) = &self.lens_system {
+            let aspect_ratio = self.horizontal.length() / self.vertical.length();
+            let (origin, direction) = lens_system.ray(s, t, aspect_ratio, sample)?;
+            return Some(Ray::new(
+                self.origin + origin.x * self.u + origin.y * self.v - origin.z * self.w,
+                direction.x * self.u + direction.y * self.v - direction.z * self.w,
+                time,
+            ));
+        }
 
-        Ray::new(
-            self.origin + offset,
-            self.lower_left_corner + s * self.horizontal + t * self.vertical - self.origin - offset,
-            self.time_distribution.map_or(0., |d| d.sample(&mut rng)),
-        )
+        let offset = self.lens_radius * (sample.x * self.u + sample.y * self.v);
+        let point = self.lower_left_corner + s * self.horizontal + t * self.vertical;
+        let direction = match self.projection {
+            Projection::Perspective => {
+                return Some(Ray::new(
+                    self.origin + offset,
+                    point - self.origin - offset,
+                    time,
+                ));
+            }
+            // Rays through the lens converge at the focus distance in front of the point
+            Projection::Orthographic => {
+                return Some(Ray::new(
+                    point + offset,
+                    -self.focus_dist * self.w - offset,
+                    time,
+                ));
+            }
+            Projection::Equirectangular => equirectangular_direction(s, t),
+            Projection::Cubemap => cubemap_direction(s, t),
+            Projection::Fisheye { mapping, fov } => {
+                let aspect_ratio = self.horizontal.length() / self.vertical.length();
+                fisheye_direction((2. * s - 1.) * aspect_ratio, 2. * t - 1., mapping, fov)
+            }
+        };
+
+        let direction = direction.x * self.u + direction.y * self.v + direction.z * self.w;
+        Some(Ray::new(
+            self.origin + self.eye_offset(&direction),
+            direction,
+            time,
+        ))
+    }
+
+    /// Returns the position of the eye of a panoramic camera relatively to its center, for a ray
+    /// in the given direction.
+    fn eye_offset(&self, direction: &Vec3) -> Vec3 {
+        // The eyes turn around the up axis with the horizontal direction of the ray
+        let right = direction.cross(&self.v);
+        if self.stereo_offset == 0. || right.length() < 1e-6 {
+            return Vec3::zero();
+        }
+
+        self.stereo_offset * right.unit()
     }
 }
 
@@ -196,7 +739,8 @@ impl Default for Camera {
 
 impl PartialEq for Camera {
     fn eq(&self, other: &Self) -> bool {
-        self.origin == other.origin
+        self.projection == other.projection
+            && self.origin == other.origin
             && self.lower_left_corner == other.lower_left_corner
             && self.horizontal == other.horizontal
             && self.vertical == other.vertical
@@ -204,8 +748,234 @@ impl PartialEq for Camera {
             && self.v == other.v
             && self.w == other.w
             && self.lens_radius == other.lens_radius
+            && self.aperture_blades == other.aperture_blades
+            && self.lens_system == other.lens_system
             && self.focus_dist == other.focus_dist
+            && self.stereo_offset == other.stereo_offset
+            && self.time_interval == other.time_interval
+            && self.shutter_curve == other.shutter_curve
+    }
+}
+
+/// Returns a random point in the regular polygon with the given number of blades inscribed in
+/// the unit circle, turned by `rotation` radians.
+// Apertures have a handful of blades, which a f32 represents exactly
+#[allow(clippy::cast_precision_loss)]
+fn random_in_polygon(blades: u32, rotation: f32) -> Vec3 {
+    let mut rng = rng();
+
+    // Picks one of the triangles between the center and the sides, then a point in it
+    let step = TAU / blades as f32;
+    let angle = (rng.gen_range(0..blades) as f32).mul_add(step, rotation);
+    let (mut a, mut b) = (rng.gen::<f32>(), rng.gen::<f32>());
+    if a + b > 1. {
+        (a, b) = (1. - a, 1. - b);
+    }
+
+    a * Vec3::new(angle.cos(), angle.sin(), 0.)
+        + b * Vec3::new((angle + step).cos(), (angle + step).sin(), 0.)
+}
+
+/// Returns the direction, relatively to the right, up and back axes of the camera, of the point
+/// `(s, t)` of an equirectangular image.
+fn equirectangular_direction(s: f32, t: f32) -> Vec3 {
+    let longitude = (s - 0.5) * TAU;
+    let latitude = (t - 0.5) * PI;
+
+    Vec3::new(
+        latitude.cos() * longitude.sin(),
+        latitude.sin(),
+        -latitude.cos() * longitude.cos(),
+    )
+}
+
+/// Returns the direction, relatively to the right, up and back axes of the camera, of the point
+/// `(s, t)` of a cubemap image.
+fn cubemap_direction(s: f32, t: f32) -> Vec3 {
+    let column = f32::min((3. * s).floor(), 2.);
+    let row = f32::min((2. * t).floor(), 1.);
+    // Coordinates in range [-1, 1] on the face, from left to right and bottom to top
+    let a = 2. * (3. * s - column) - 1.;
+    let b = 2. * (2. * t - row) - 1.;
+
+    // The row and column are whole numbers of at most 2
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_sign_loss)]
+    let face = (row as u8, column as u8);
+    match face {
+        (1, 0) => Vec3::new(1., b, -a),
+        (1, 1) => Vec3::new(-1., b, a),
+        (1, _) => Vec3::new(a, 1., -b),
+        (_, 0) => Vec3::new(a, -1., b),
+        (_, 1) => Vec3::new(a, b, 1.),
+        _ => Vec3::new(-a, b, -1.),
+    }
+}
+
+/// Returns the direction, relatively to the right, up and back axes of the camera, of the point
+/// `(x, y)` of a fisheye image, the edges of its height being at `y = ±1`.
+fn fisheye_direction(x: f32, y: f32, mapping: FisheyeMapping, fov: f32) -> Vec3 {
+    let radius = x.hypot(y);
+    if radius == 0. {
+        return Vec3::new(0., 0., -1.);
+    }
+
+    let half_fov = fov.to_radians() / 2.;
+    let theta = match mapping {
+        FisheyeMapping::Equidistant => radius * half_fov,
+        FisheyeMapping::Equisolid => 2. * f32::asin(f32::min(radius * f32::sin(half_fov / 2.), 1.)),
+    }
+    .min(PI);
+
+    let sin_theta = theta.sin();
+    Vec3::new(sin_theta * x / radius, sin_theta * y / radius, -theta.cos())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orthographic_rays_are_parallel() {
+        let testee = Camera::orthographic(Point3::new(0., 0., 5.), Point3::zero(), 2., 2.);
+
+        let corner = testee.ray(0., 0.);
+        let center = testee.ray(0.5, 0.5);
+        assert_eq!(corner.direction().unit(), center.direction().unit());
+        assert_eq!(*center.origin(), Point3::new(0., 0., 5.));
+        assert_eq!(*corner.origin(), Point3::new(-2., -1., 5.));
+    }
+
+    #[test]
+    fn equirectangular_wraps_around_the_camera() {
+        let testee = Camera::equirectangular(Point3::zero(), Point3::new(0., 0., -1.));
+
+        let direction = |s, t| testee.ray(s, t).direction().unit();
+        assert!((direction(0.5, 0.5) - Vec3::new(0., 0., -1.)).length() < 1e-5);
+        assert!((direction(0.75, 0.5) - Vec3::new(1., 0., 0.)).length() < 1e-5);
+        assert!((direction(0.3, 1.) - Vec3::new(0., 1., 0.)).length() < 1e-5);
+    }
 
-        // TODO: Find a way to compare uniforms
+    #[test]
+    fn cubemap_faces_follow_the_axes() {
+        let testee = Camera::cubemap(Point3::zero(), Point3::new(0., 0., -1.));
+
+        let centers = [
+            ((1. / 6., 0.75), Vec3::new(1., 0., 0.)),
+            ((0.5, 0.75), Vec3::new(-1., 0., 0.)),
+            ((5. / 6., 0.75), Vec3::new(0., 1., 0.)),
+            ((1. / 6., 0.25), Vec3::new(0., -1., 0.)),
+            ((0.5, 0.25), Vec3::new(0., 0., 1.)),
+            ((5. / 6., 0.25), Vec3::new(0., 0., -1.)),
+        ];
+        for ((s, t), expected) in centers {
+            assert!((testee.ray(s, t).direction().unit() - expected).length() < 1e-5);
+        }
+
+        // The top of the -Z face is up
+        assert!(testee.ray(5. / 6., 0.49).direction().y > 0.);
+    }
+
+    #[test]
+    fn fisheye_edge_is_at_half_fov() {
+        for mapping in [FisheyeMapping::Equidistant, FisheyeMapping::Equisolid] {
+            let testee =
+                Camera::fisheye(Point3::zero(), Point3::new(0., 0., -1.), 180., 1., mapping);
+
+            let edge = testee.ray(0.5, 1.).direction().unit();
+            assert!((edge - Vec3::new(0., 1., 0.)).length() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn panoramic_eyes_turn_with_the_rays() {
+        let testee =
+            Camera::equirectangular(Point3::zero(), Point3::new(0., 0., -1.)).stereo_offset(0.5);
+
+        assert!((*testee.ray(0.5, 0.5).origin() - Point3::new(0.5, 0., 0.)).length() < 1e-5);
+        assert!((*testee.ray(0.75, 0.5).origin() - Point3::new(0., 0., 0.5)).length() < 1e-5);
+    }
+
+    #[test]
+    fn bladed_aperture_stays_in_polygon() {
+        let testee = Camera::new(Point3::zero(), Point3::new(0., 0., -1.), 40., 1.)
+            .aperture(2.)
+            .aperture_blades(4, 0.);
+
+        // A square with vertices on the axes
+        for _ in 0..100 {
+            let origin = *testee.ray(0.5, 0.5).origin();
+            assert!(origin.x.abs() + origin.y.abs() <= 1. + 1e-5);
+        }
+    }
+
+    #[test]
+    fn lens_system_turns_image_upright() {
+        let lens = LensSystem::parse("0 5 0 10\n50 5 1.5 20\n-50 45 0 20").unwrap();
+        let testee =
+            Camera::new(Point3::zero(), Point3::new(0., 0., -3.), 40., 1.).lens_system(lens);
+
+        // Some rays are blocked by the elements
+        let top = (0..100)
+            .find_map(|_| testee.ray_with_differentials(0.5, 0.7, 0.01, 0.01))
+            .unwrap();
+        assert!(top.direction().y > 0.);
+        assert!(top.direction().z < 0.);
+    }
+
+    #[test]
+    fn shutter_curves_stay_in_time_interval() {
+        let camera = Camera::default().time_interval((1., 3.));
+
+        for curve in [
+            ShutterCurve::Box,
+            ShutterCurve::Triangle,
+            ShutterCurve::Rolling { exposure: 0.25 },
+        ] {
+            let testee = camera.clone().shutter_curve(curve);
+            for _ in 0..100 {
+                let time = testee.ray(0.5, 0.5).time();
+                assert!((1. ..=3.).contains(&time));
+            }
+        }
+    }
+
+    #[test]
+    fn rolling_shutter_exposes_top_rows_first() {
+        let testee = Camera::default()
+            .time_interval((0., 1.))
+            .shutter_curve(ShutterCurve::Rolling { exposure: 0.1 });
+
+        for _ in 0..100 {
+            assert!(testee.ray(0.5, 1.).time() <= 0.1);
+            assert!(testee.ray(0.5, 0.).time() >= 0.9);
+        }
+    }
+
+    #[test]
+    fn eyes_converge_at_convergence_distance() {
+        let camera = Camera::new(Point3::zero(), Point3::new(0., 0., -2.), 40., 1.5);
+
+        for offset in [-0.1, 0.1] {
+            let testee = camera.clone().eye(offset, 5.);
+            let ray = testee.ray(0.5, 0.5);
+
+            assert_eq!(*ray.origin(), Point3::new(offset, 0., 0.));
+            let convergence = ray.point(5. / -ray.direction().z);
+            assert!(convergence.length() - 5. < 1e-5 && convergence.x.abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn orthographic_lens_focuses_at_focus_dist() {
+        let testee = Camera::orthographic(Point3::new(0., 0., 5.), Point3::zero(), 2., 1.)
+            .aperture(1.)
+            .focus_dist(3.);
+
+        for _ in 0..10 {
+            let ray = testee.ray(0.75, 0.5);
+            let focus = ray.point(3. / -ray.direction().z);
+            assert!((focus - Point3::new(0.5, 0., 2.)).length() < 1e-5);
+        }
     }
 }
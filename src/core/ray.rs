@@ -1,5 +1,92 @@
+use crate::hitable::HitRecord;
+use crate::utils::{reflect, refract};
 use crate::vec::{Point3, Vec3};
 
+/// The offset rays of a ray for neighbouring pixels along the x and y axes of the image, which
+/// give the footprint of the ray on the surfaces it hits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayDifferentials {
+    pub rx_origin: Point3,
+    pub rx_direction: Vec3,
+    pub ry_origin: Point3,
+    pub ry_direction: Vec3,
+}
+
+impl RayDifferentials {
+    /// Returns the points where the offset rays hit the plane tangent to the surface at `p` with
+    /// the normal `normal`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::ray::RayDifferentials;
+    /// use crab_rt::vec::{Point3, Vec3};
+    ///
+    /// let differentials = RayDifferentials {
+    ///     rx_origin: Point3::new(1., 0., 0.),
+    ///     rx_direction: Vec3::new(0., 0., -1.),
+    ///     ry_origin: Point3::new(0., 1., 0.),
+    ///     ry_direction: Vec3::new(0., 0., -1.),
+    /// };
+    /// let (px, py) = differentials
+    ///     .plane_hit_points(&Point3::new(0., 0., -2.), &Vec3::new(0., 0., 1.))
+    ///     .unwrap();
+    /// assert_eq!(px, Point3::new(1., 0., -2.));
+    /// assert_eq!(py, Point3::new(0., 1., -2.));
+    /// ```
+    #[must_use]
+    pub fn plane_hit_points(&self, p: &Point3, normal: &Vec3) -> Option<(Point3, Point3)> {
+        let hit_point = |origin: &Point3, direction: &Vec3| {
+            let cosine = normal.dot(direction);
+            if cosine.abs() < 1e-8 {
+                return None;
+            }
+            let t = normal.dot(&(p - origin)) / cosine;
+            Some(origin + t * direction)
+        };
+
+        Some((
+            hit_point(&self.rx_origin, &self.rx_direction)?,
+            hit_point(&self.ry_origin, &self.ry_direction)?,
+        ))
+    }
+
+    /// Returns the differentials of the ray `scattered` specularly by the surface of `record`
+    /// from `ray`, whose differentials are `self`.
+    ///
+    /// The offset rays are reflected or refracted like the main ray, ignoring the curvature of
+    /// the surface.
+    #[must_use]
+    pub fn scatter(&self, ray: &Ray, record: &HitRecord<'_>, scattered: &Ray) -> Option<Self> {
+        let normal = record.normal();
+        let (px, py) = self.plane_hit_points(record.hit_point(), normal)?;
+
+        let incoming = ray.direction().unit();
+        let outgoing = scattered.direction().unit();
+        let transfer = |direction: &Vec3| {
+            let direction = direction.unit();
+            if outgoing.dot(normal) > 0. {
+                reflect(&direction, normal)
+            } else {
+                // The refraction ratio is the ratio of the sines of the angles with the normal
+                let sin_incoming = incoming.cross(normal).length();
+                let sin_outgoing = outgoing.cross(normal).length();
+                if sin_incoming < 1e-4 {
+                    direction - incoming + outgoing
+                } else {
+                    refract(&direction, normal, sin_outgoing / sin_incoming)
+                }
+            }
+        };
+
+        Some(Self {
+            rx_origin: px,
+            rx_direction: transfer(&self.rx_direction),
+            ry_origin: py,
+            ry_direction: transfer(&self.ry_direction),
+        })
+    }
+}
+
 /// A mathematical ray.
 #[derive(Debug)]
 pub struct Ray {
@@ -9,6 +96,10 @@ pub struct Ray {
     direction: Vec3,
     /// The time when the ray was casted.
     time: f32,
+    /// The wavelength in nanometers carried by the ray in spectral rendering mode.
+    wavelength: Option<f32>,
+    /// The offset rays for neighbouring pixels, used to filter textures.
+    differentials: Option<RayDifferentials>,
 }
 
 impl Ray {
@@ -34,6 +125,53 @@ impl Ray {
             origin,
             direction,
             time,
+            wavelength: None,
+            differentials: None,
+        }
+    }
+
+    /// Consumes the `Ray` and returns self after setting the wavelength it carries.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::ray::Ray;
+    /// use crab_rt::vec::{Point3, Vec3};
+    ///
+    /// let ray = Ray::new(Point3::new(0., 0., 0.), Vec3::new(1., 2., 3.), 0.).with_wavelength(550.);
+    /// assert_eq!(ray.wavelength(), Some(550.));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn with_wavelength(self, wavelength: f32) -> Self {
+        Self {
+            wavelength: Some(wavelength),
+            ..self
+        }
+    }
+
+    /// Consumes the `Ray` and returns self after setting its differentials.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::ray::{Ray, RayDifferentials};
+    /// use crab_rt::vec::{Point3, Vec3};
+    ///
+    /// let differentials = RayDifferentials {
+    ///     rx_origin: Point3::zero(),
+    ///     rx_direction: Vec3::new(0.01, 0., -1.),
+    ///     ry_origin: Point3::zero(),
+    ///     ry_direction: Vec3::new(0., 0.01, -1.),
+    /// };
+    /// let ray =
+    ///     Ray::new(Point3::zero(), Vec3::new(0., 0., -1.), 0.).with_differentials(differentials);
+    /// assert_eq!(ray.differentials(), Some(&differentials));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn with_differentials(self, differentials: RayDifferentials) -> Self {
+        Self {
+            differentials: Some(differentials),
+            ..self
         }
     }
 
@@ -85,6 +223,38 @@ impl Ray {
         self.time
     }
 
+    /// Returns the wavelength in nanometers carried by the `Ray` if any.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::ray::Ray;
+    /// use crab_rt::vec::{Point3, Vec3};
+    ///
+    /// let ray = Ray::new(Point3::new(0., 0., 0.), Vec3::new(1., 2., 3.), 0.);
+    /// assert_eq!(ray.wavelength(), None);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn wavelength(&self) -> Option<f32> {
+        self.wavelength
+    }
+
+    /// Returns the differentials of the `Ray` if any.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::ray::Ray;
+    /// use crab_rt::vec::{Point3, Vec3};
+    ///
+    /// let ray = Ray::new(Point3::new(0., 0., 0.), Vec3::new(1., 2., 3.), 0.);
+    /// assert_eq!(ray.differentials(), None);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn differentials(&self) -> Option<&RayDifferentials> {
+        self.differentials.as_ref()
+    }
+
     /// Returns the point on the `Ray` at distance t.
     ///
     /// # Examples
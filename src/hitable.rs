@@ -4,8 +4,12 @@ use core::fmt::Debug;
 use crate::aabb::Aabb;
 use crate::materials::Material;
 use crate::ray::Ray;
+use crate::utils::orthonormal_basis;
 use crate::vec::{Point3, Vec3};
 
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
 pub trait Hitable: Debug + Send + Sync {
     #[must_use]
     fn hit(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>>;
@@ -45,8 +49,18 @@ impl<H: Hitable> Hitable for Vec<H> {
     }
 }
 
+/// The partial derivatives of the texture coordinates of a hit point along the x and y axes of
+/// the image, which give the footprint of a pixel in texture space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UvDerivatives {
+    pub dudx: f32,
+    pub dvdx: f32,
+    pub dudy: f32,
+    pub dvdy: f32,
+}
+
 /// A record for a surface hit.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct HitRecord<'material> {
     /// The distance of the hit point to the origin.
     t: f32,
@@ -56,6 +70,13 @@ pub struct HitRecord<'material> {
     normal: Vec3,
     /// The texture coordinates.
     texture_coordinates: (f32, f32),
+    /// The partial derivatives of the hit point with respect to the texture coordinates, zero
+    /// when the surface does not provide them.
+    dpdu: Vec3,
+    dpdv: Vec3,
+    /// The partial derivatives of the texture coordinates along the image axes, when the ray
+    /// carries differentials.
+    uv_derivatives: Option<UvDerivatives>,
     /// Whether the ray hitted the front face.
     front_face: bool,
     /// The material of the surface.
@@ -94,6 +115,9 @@ impl<'material> HitRecord<'material> {
             hit_point,
             normal,
             texture_coordinates,
+            dpdu: Vec3::zero(),
+            dpdv: Vec3::zero(),
+            uv_derivatives: None,
             front_face: true,
             material,
         }
@@ -220,6 +244,202 @@ impl<'material> HitRecord<'material> {
         self.texture_coordinates
     }
 
+    /// Returns the partial derivative of the hit point with respect to the first texture
+    /// coordinate.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::hitable::HitRecord;
+    /// use crab_rt::materials::Lambertian;
+    /// use crab_rt::vec::{Point3, Vec3};
+    ///
+    /// let material = Lambertian::default();
+    /// let mut record = HitRecord::new(
+    ///     1.,
+    ///     Point3::new(1., 1., 1.),
+    ///     Vec3::new(0., 1., 0.),
+    ///     (0., 0.5),
+    ///     &material,
+    /// );
+    /// record.set_partial_derivatives(Vec3::new(2., 0., 0.), Vec3::new(0., 0., 2.));
+    /// assert_eq!(record.dpdu(), &Vec3::new(2., 0., 0.));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn dpdu(&self) -> &Vec3 {
+        &self.dpdu
+    }
+
+    /// Returns the partial derivative of the hit point with respect to the second texture
+    /// coordinate.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::hitable::HitRecord;
+    /// use crab_rt::materials::Lambertian;
+    /// use crab_rt::vec::{Point3, Vec3};
+    ///
+    /// let material = Lambertian::default();
+    /// let mut record = HitRecord::new(
+    ///     1.,
+    ///     Point3::new(1., 1., 1.),
+    ///     Vec3::new(0., 1., 0.),
+    ///     (0., 0.5),
+    ///     &material,
+    /// );
+    /// record.set_partial_derivatives(Vec3::new(2., 0., 0.), Vec3::new(0., 0., 2.));
+    /// assert_eq!(record.dpdv(), &Vec3::new(0., 0., 2.));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn dpdv(&self) -> &Vec3 {
+        &self.dpdv
+    }
+
+    #[inline]
+    #[allow(clippy::similar_names)]
+    pub const fn set_partial_derivatives(&mut self, dpdu: Vec3, dpdv: Vec3) {
+        self.dpdu = dpdu;
+        self.dpdv = dpdv;
+    }
+
+    /// Returns the partial derivatives of the texture coordinates along the image axes, if they
+    /// were computed from the differentials of the ray.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::hitable::HitRecord;
+    /// use crab_rt::materials::Lambertian;
+    /// use crab_rt::vec::{Point3, Vec3};
+    ///
+    /// let material = Lambertian::default();
+    /// let record = HitRecord::new(
+    ///     1.,
+    ///     Point3::new(1., 1., 1.),
+    ///     Vec3::new(0., 1., 0.),
+    ///     (0., 0.5),
+    ///     &material,
+    /// );
+    /// assert_eq!(record.uv_derivatives(), None);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn uv_derivatives(&self) -> Option<&UvDerivatives> {
+        self.uv_derivatives.as_ref()
+    }
+
+    /// Computes the partial derivatives of the texture coordinates along the image axes from
+    /// the differentials of the ray, if it has some and the surface provides its partial
+    /// derivatives.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::hitable::{HitRecord, UvDerivatives};
+    /// use crab_rt::materials::Lambertian;
+    /// use crab_rt::ray::{Ray, RayDifferentials};
+    /// use crab_rt::vec::{Point3, Vec3};
+    ///
+    /// let ray = Ray::new(Point3::new(0., 1., 0.), Vec3::new(0., -1., 0.), 0.).with_differentials(
+    ///     RayDifferentials {
+    ///         rx_origin: Point3::new(0.1, 1., 0.),
+    ///         rx_direction: Vec3::new(0., -1., 0.),
+    ///         ry_origin: Point3::new(0., 1., 0.2),
+    ///         ry_direction: Vec3::new(0., -1., 0.),
+    ///     },
+    /// );
+    ///
+    /// let material = Lambertian::default();
+    /// let mut record = HitRecord::new(
+    ///     1.,
+    ///     Point3::zero(),
+    ///     Vec3::new(0., 1., 0.),
+    ///     (0., 0.),
+    ///     &material,
+    /// );
+    /// record.set_partial_derivatives(Vec3::new(2., 0., 0.), Vec3::new(0., 0., 2.));
+    /// record.compute_uv_derivatives(&ray);
+    ///
+    /// let derivatives = record.uv_derivatives().unwrap();
+    /// assert!((derivatives.dudx - 0.05).abs() < 1e-6 && (derivatives.dvdy - 0.1).abs() < 1e-6);
+    /// ```
+    #[allow(clippy::similar_names)]
+    pub fn compute_uv_derivatives(&mut self, ray: &Ray) {
+        let Some(differentials) = ray.differentials() else {
+            return;
+        };
+        let Some((px, py)) = differentials.plane_hit_points(&self.hit_point, &self.normal) else {
+            return;
+        };
+
+        // Least squares solution of dpdx = dudx * dpdu + dvdx * dpdv
+        let (uu, uv, vv) = (
+            self.dpdu.dot(&self.dpdu),
+            self.dpdu.dot(&self.dpdv),
+            self.dpdv.dot(&self.dpdv),
+        );
+        let determinant = uu.mul_add(vv, -uv * uv);
+        if determinant.abs() < 1e-12 {
+            return;
+        }
+
+        let solve = |dp: Vec3| {
+            let (u, v) = (self.dpdu.dot(&dp), self.dpdv.dot(&dp));
+            (
+                vv.mul_add(u, -uv * v) / determinant,
+                uu.mul_add(v, -uv * u) / determinant,
+            )
+        };
+        let (dudx, dvdx) = solve(px - self.hit_point);
+        let (dudy, dvdy) = solve(py - self.hit_point);
+
+        self.uv_derivatives = Some(UvDerivatives {
+            dudx,
+            dvdx,
+            dudy,
+            dvdy,
+        });
+    }
+
+    /// Returns the unit tangent and bitangent forming with the normal the shading frame of the
+    /// surface, oriented along the texture coordinates when the surface provides them.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::hitable::HitRecord;
+    /// use crab_rt::materials::Lambertian;
+    /// use crab_rt::vec::{Point3, Vec3};
+    ///
+    /// let material = Lambertian::default();
+    /// let mut record = HitRecord::new(
+    ///     1.,
+    ///     Point3::new(1., 1., 1.),
+    ///     Vec3::new(0., 1., 0.),
+    ///     (0., 0.5),
+    ///     &material,
+    /// );
+    /// record.set_partial_derivatives(Vec3::new(2., 0., 0.), Vec3::new(0., 0., 2.));
+    /// assert_eq!(
+    ///     record.shading_frame(),
+    ///     (Vec3::new(1., 0., 0.), Vec3::new(0., 0., 1.))
+    /// );
+    /// ```
+    #[must_use]
+    pub fn shading_frame(&self) -> (Vec3, Vec3) {
+        // Gram-Schmidt orthogonalization of dpdu against the normal
+        let tangent = self.dpdu - self.normal.dot(&self.dpdu) * self.normal;
+        if tangent.is_near_zero() {
+            return orthonormal_basis(&self.normal);
+        }
+
+        let tangent = tangent.unit();
+        let bitangent = self.normal.cross(&tangent);
+        if bitangent.dot(&self.dpdv) < 0. {
+            (tangent, -bitangent)
+        } else {
+            (tangent, bitangent)
+        }
+    }
+
     /// Returns whether the ray hitted the front face.
     ///
     /// # Examples
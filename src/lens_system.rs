@@ -0,0 +1,427 @@
+use alloc::vec::Vec;
+
+use crate::vec::{Point3, Vec3};
+
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
+#[cfg(feature = "std")]
+use {
+    anyhow::{bail, Context, Result},
+    std::{format, fs},
+};
+
+/// Diagonal of a full frame film of 36 × 24 mm, in meters.
+const FULL_FRAME_DIAGONAL: f32 = 0.043_267;
+
+/// A surface of a [`LensSystem`], with the medium behind it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LensElement {
+    /// Signed radius of curvature of the surface, positive when its center is towards the film,
+    /// and 0 for the aperture stop.
+    pub curvature_radius: f32,
+    /// Distance along the axis to the next surface, or to the film for the last one.
+    pub thickness: f32,
+    /// Refractive index of the medium between the surface and the next one, 1 for air.
+    pub refractive_index: f32,
+    /// Diameter of the surface.
+    pub aperture: f32,
+}
+
+/// A photographic lens made of spherical elements, through which a [`Camera`] traces its rays.
+///
+/// Unlike the thin lens, it reproduces the vignetting, the cat's eye bokeh and the distortion of
+/// real lenses.
+///
+/// [`Camera`]: crate::camera::Camera
+#[derive(Debug, Clone, PartialEq)]
+pub struct LensSystem {
+    /// Surfaces from the front of the lens to the film.
+    elements: Vec<LensElement>,
+    film_diagonal: f32,
+}
+
+impl LensSystem {
+    /// Constructs a new `LensSystem` from its elements, from the front of the lens to the film,
+    /// in scene units, with a full frame film.
+    ///
+    /// # Panics
+    /// Panics if `elements` is empty.
+    /// Panics if the thickness or the aperture of an element is not positive.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::lens_system::{LensElement, LensSystem};
+    ///
+    /// // A biconvex lens of 5 cm focal length in front of a stop
+    /// let lens = LensSystem::new(vec![
+    ///     LensElement {
+    ///         curvature_radius: 0.05,
+    ///         thickness: 0.005,
+    ///         refractive_index: 1.5,
+    ///         aperture: 0.02,
+    ///     },
+    ///     LensElement {
+    ///         curvature_radius: -0.05,
+    ///         thickness: 0.005,
+    ///         refractive_index: 1.,
+    ///         aperture: 0.02,
+    ///     },
+    ///     LensElement {
+    ///         curvature_radius: 0.,
+    ///         thickness: 0.045,
+    ///         refractive_index: 1.,
+    ///         aperture: 0.01,
+    ///     },
+    /// ]);
+    /// ```
+    #[must_use]
+    pub fn new(elements: Vec<LensElement>) -> Self {
+        assert!(!elements.is_empty(), "elements should not be empty");
+        assert!(
+            elements
+                .iter()
+                .all(|element| element.thickness > 0. && element.aperture > 0.),
+            "the thickness and the aperture of the elements should be greater than 0"
+        );
+
+        Self {
+            elements,
+            film_diagonal: FULL_FRAME_DIAGONAL,
+        }
+    }
+
+    /// Parses a lens prescription, whose lines give the curvature radius, the thickness, the
+    /// refractive index and the aperture of each element in millimeters, from the front of the
+    /// lens to the film.
+    ///
+    /// The aperture stop has a curvature radius of 0 and the refractive index of air is either 0
+    /// or 1. Empty lines and lines starting with `#` are ignored. The lens is scaled to meters.
+    ///
+    /// # Errors
+    /// Returns an error if a line does not have 4 numbers or if an element is invalid.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::lens_system::LensSystem;
+    ///
+    /// let lens = LensSystem::parse(
+    ///     "# radius thickness ior aperture
+    ///     50 5 1.5 20
+    ///     -50 5 0 20
+    ///     0 45 0 10",
+    /// )
+    /// .unwrap();
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn parse(content: &str) -> Result<Self> {
+        let elements = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                let values = line
+                    .split_whitespace()
+                    .map(|value| {
+                        value
+                            .parse::<f32>()
+                            .with_context(|| format!("invalid number {value}"))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let [curvature_radius, thickness, refractive_index, aperture] = values[..] else {
+                    bail!("expected 4 numbers per element, got {line}");
+                };
+                if thickness <= 0. || aperture <= 0. {
+                    bail!("invalid element {line}");
+                }
+
+                Ok(LensElement {
+                    curvature_radius: curvature_radius / 1000.,
+                    thickness: thickness / 1000.,
+                    refractive_index: if refractive_index == 0. {
+                        1.
+                    } else {
+                        refractive_index
+                    },
+                    aperture: aperture / 1000.,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if elements.is_empty() {
+            bail!("missing elements");
+        }
+        Ok(Self::new(elements))
+    }
+
+    /// Loads a lens prescription file, see [`LensSystem::parse`].
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or is invalid.
+    #[cfg(feature = "std")]
+    pub fn load(filename: &str) -> Result<Self> {
+        let content = fs::read_to_string(filename)?;
+        Self::parse(&content).with_context(|| format!("invalid lens file {filename}"))
+    }
+
+    /// Consumes the `LensSystem` and returns self with the given diameter of the aperture stop,
+    /// closing or opening the diaphragm.
+    ///
+    /// # Panics
+    /// Panics if the lens has no aperture stop.
+    /// Panics if `diameter <= 0.`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::lens_system::LensSystem;
+    ///
+    /// let lens = LensSystem::parse("50 5 1.5 20\n-50 5 0 20\n0 45 0 10")
+    ///     .unwrap()
+    ///     .stop_diameter(0.005);
+    /// ```
+    #[must_use]
+    pub fn stop_diameter(mut self, diameter: f32) -> Self {
+        assert!(diameter > 0., "diameter should be greater than 0");
+
+        let stop = self
+            .elements
+            .iter_mut()
+            .find(|element| element.curvature_radius == 0.)
+            .expect("the lens should have an aperture stop");
+        stop.aperture = diameter;
+        self
+    }
+
+    /// Consumes the `LensSystem` and returns self with the given diagonal of the film, which
+    /// sets the field of view of the lens.
+    ///
+    /// # Panics
+    /// Panics if `film_diagonal <= 0.`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::lens_system::LensSystem;
+    ///
+    /// // An APS-C film
+    /// let lens = LensSystem::parse("50 5 1.5 20\n-50 5 0 20\n0 45 0 10")
+    ///     .unwrap()
+    ///     .film_diagonal(0.0283);
+    /// ```
+    #[must_use]
+    pub fn film_diagonal(self, film_diagonal: f32) -> Self {
+        assert!(film_diagonal > 0., "film_diagonal should be greater than 0");
+
+        Self {
+            film_diagonal,
+            ..self
+        }
+    }
+
+    /// Consumes the `LensSystem` and returns self with the film moved so that objects at
+    /// `distance` in front of the lens are in focus.
+    ///
+    /// # Panics
+    /// Panics if the lens cannot focus at `distance`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::lens_system::LensSystem;
+    ///
+    /// let lens = LensSystem::parse("50 5 1.5 20\n-50 5 0 20\n0 45 0 10")
+    ///     .unwrap()
+    ///     .focus(2.);
+    /// ```
+    #[must_use]
+    pub fn focus(mut self, distance: f32) -> Self {
+        assert!(distance > 0., "distance should be greater than 0");
+
+        // Traces a paraxial ray from the point of the axis in focus to the film side
+        let front = self.front_z();
+        let height = 0.01
+            * self.elements.iter().fold(f32::INFINITY, |min, element| {
+                f32::min(min, element.aperture / 2.)
+            });
+        let origin = Point3::new(0., 0., front + distance);
+        let (p, direction) = self
+            .trace(origin, Vec3::new(height, 0., -distance).unit(), true)
+            .expect("the paraxial ray should go through the lens");
+
+        // The film moves to where the ray crosses the axis again
+        let image = p.z - p.x * direction.z / direction.x;
+        let rear = self.elements.last_mut().unwrap();
+        assert!(
+            direction.x < 0. && image < rear.thickness,
+            "the lens cannot focus at distance {distance}"
+        );
+        rear.thickness -= image;
+        self
+    }
+
+    /// Returns a ray leaving the front of the lens, from the point `(s, t)` of the film through
+    /// the point of the rear element given by `sample` in the unit disk, or `None` if the lens
+    /// blocks it.
+    ///
+    /// The ray is given relatively to the front of the lens, the z axis pointing towards the
+    /// scene and the y axis up.
+    pub(crate) fn ray(
+        &self,
+        s: f32,
+        t: f32,
+        aspect_ratio: f32,
+        sample: &Vec3,
+    ) -> Option<(Point3, Vec3)> {
+        let film_height = self.film_diagonal / aspect_ratio.hypot(1.);
+        // The lens turns the image upside down
+        let film_point = Point3::new(
+            -(s - 0.5) * aspect_ratio * film_height,
+            -(t - 0.5) * film_height,
+            0.,
+        );
+
+        let rear = self.elements.last().unwrap();
+        let rear_point = Point3::new(
+            sample.x * rear.aperture / 2.,
+            sample.y * rear.aperture / 2.,
+            rear.thickness,
+        );
+
+        let (origin, direction) =
+            self.trace(film_point, (rear_point - film_point).unit(), false)?;
+        Some((origin - Vec3::new(0., 0., self.front_z()), direction))
+    }
+
+    /// Returns the position of the front of the lens on the axis, the film being at 0.
+    fn front_z(&self) -> f32 {
+        self.elements.iter().map(|element| element.thickness).sum()
+    }
+
+    /// Traces a ray through the elements, either from the scene towards the film or from the
+    /// film towards the scene, returning the ray after the last surface or `None` if an element
+    /// blocks it.
+    fn trace(
+        &self,
+        mut origin: Point3,
+        mut direction: Vec3,
+        towards_film: bool,
+    ) -> Option<(Point3, Vec3)> {
+        let mut trace_surface = |i: usize, z: f32| {
+            let element = &self.elements[i];
+            // Refractive index in front of the surface
+            let front_index = if i == 0 {
+                1.
+            } else {
+                self.elements[i - 1].refractive_index
+            };
+
+            let (t, normal) = if element.curvature_radius == 0. {
+                ((z - origin.z) / direction.z, None)
+            } else {
+                let center = Point3::new(0., 0., z - element.curvature_radius);
+                let oc = origin - center;
+                let b = oc.dot(&direction);
+                let discriminant = b * b - oc.squared_length()
+                    + element.curvature_radius * element.curvature_radius;
+                if discriminant < 0. {
+                    return None;
+                }
+
+                // The surface is the cap of the sphere around its vertex
+                let closer = (direction.z > 0.) ^ (element.curvature_radius > 0.);
+                let t = if closer {
+                    -b - discriminant.sqrt()
+                } else {
+                    -b + discriminant.sqrt()
+                };
+                (t, Some(center))
+            };
+            if t <= 0. {
+                return None;
+            }
+
+            let p = origin + t * direction;
+            if p.x * p.x + p.y * p.y > element.aperture * element.aperture / 4. {
+                return None;
+            }
+
+            if let Some(center) = normal {
+                let (from, to) = if towards_film {
+                    (front_index, element.refractive_index)
+                } else {
+                    (element.refractive_index, front_index)
+                };
+                let normal = (p - center).unit();
+                let normal = if normal.dot(&direction) > 0. {
+                    -normal
+                } else {
+                    normal
+                };
+                direction = refract(&direction, &normal, from / to)?;
+            }
+            origin = p;
+            Some(())
+        };
+
+        // Positions of the surfaces on the axis, from the front of the lens or from the film
+        if towards_film {
+            let mut z = self.front_z();
+            for (i, element) in self.elements.iter().enumerate() {
+                trace_surface(i, z)?;
+                z -= element.thickness;
+            }
+        } else {
+            let mut z = 0.;
+            for (i, element) in self.elements.iter().enumerate().rev() {
+                z += element.thickness;
+                trace_surface(i, z)?;
+            }
+        }
+        Some((origin, direction))
+    }
+}
+
+/// Refracts the unit `direction` through a surface of unit `normal` facing it, or returns
+/// `None` for a total internal reflection.
+fn refract(direction: &Vec3, normal: &Vec3, eta: f32) -> Option<Vec3> {
+    let cos_in = -direction.dot(normal);
+    let sin_out_squared = eta * eta * cos_in.mul_add(-cos_in, 1.);
+    if sin_out_squared > 1. {
+        return None;
+    }
+
+    let cos_out = f32::sqrt(1. - sin_out_squared);
+    Some(eta * direction + eta.mul_add(cos_in, -cos_out) * normal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn singlet() -> LensSystem {
+        LensSystem::parse("0 5 0 10\n50 5 1.5 20\n-50 45 0 20").unwrap()
+    }
+
+    #[test]
+    fn focused_rays_converge_at_focus_distance() {
+        let testee = singlet().focus(2.);
+
+        for sample in [Vec3::new(0.3, 0., 0.), Vec3::new(-0.2, 0.2, 0.)] {
+            let (origin, direction) = testee.ray(0.5, 0.5, 1.5, &sample).unwrap();
+            let focus = origin + (2. - origin.z) / direction.z * direction;
+            assert!(focus.x.hypot(focus.y) < 1e-3);
+        }
+    }
+
+    #[test]
+    fn stop_blocks_rays() {
+        let testee = singlet().stop_diameter(0.001);
+
+        assert!(testee.ray(0.5, 0.5, 1.5, &Vec3::zero()).is_some());
+        assert!(testee.ray(0.5, 0.5, 1.5, &Vec3::new(0.9, 0., 0.)).is_none());
+    }
+
+    #[test]
+    fn parse_rejects_incomplete_elements() {
+        assert!(LensSystem::parse("50 5 1.5").is_err());
+        assert!(LensSystem::parse("# no elements").is_err());
+    }
+}
@@ -19,16 +19,24 @@ extern crate std;
 extern crate quickcheck_macros;
 
 pub mod aabb;
+pub mod animation;
 pub mod bvh;
 pub mod camera;
 mod core;
 pub mod hitable;
+pub mod lens_system;
+pub mod lights;
 pub mod materials;
 pub mod objects;
 pub mod perlin;
 pub mod raytracer;
 pub mod scene;
+pub mod sdf;
+pub mod sky;
+pub mod spectrum;
+pub mod stereo;
 pub mod textures;
 pub mod utils;
+pub mod worley;
 
 pub use crate::core::*;
@@ -0,0 +1,134 @@
+use rand::Rng;
+
+use super::{LightSample, LightSource};
+use crate::utils::{orthonormal_basis, rng};
+use crate::vec::{Color3, Point3, Vec3};
+
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
+/// Angular diameter of the sun seen from the earth, in degrees.
+const SUN_ANGULAR_DIAMETER: f32 = 0.53;
+
+/// A light infinitely far away lighting the whole scene from a direction, such as the sun.
+///
+/// A light with an angular diameter lights from a small disk of the sky, which softens the
+/// shadows.
+#[derive(Debug)]
+pub struct DirectionalLight {
+    /// Unit direction in which the light travels.
+    direction: Vec3,
+    /// Irradiance on a surface facing the light.
+    irradiance: Color3,
+    /// Cosine of the angular radius of the light.
+    cos_radius: f32,
+}
+
+impl DirectionalLight {
+    /// Constructs a new `DirectionalLight` travelling in `direction` with the given irradiance
+    /// on a surface facing it.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::lights::DirectionalLight;
+    /// use crab_rt::vec::{Color3, Vec3};
+    ///
+    /// let light = DirectionalLight::new(Vec3::new(1., -1., 0.), Color3::new(2., 2., 2.));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(direction: Vec3, irradiance: Color3) -> Self {
+        Self {
+            direction: direction.unit(),
+            irradiance,
+            cos_radius: 1.,
+        }
+    }
+
+    /// Constructs a new `DirectionalLight` with the angular diameter of the sun, 0.53 degrees.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::lights::DirectionalLight;
+    /// use crab_rt::vec::{Color3, Vec3};
+    ///
+    /// let sun = DirectionalLight::sun(Vec3::new(1., -2., 0.5), Color3::new(3., 2.9, 2.7));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn sun(direction: Vec3, irradiance: Color3) -> Self {
+        Self::new(direction, irradiance).angular_diameter(SUN_ANGULAR_DIAMETER)
+    }
+
+    /// Consumes the `DirectionalLight` and returns self lighting from a disk of the given
+    /// angular diameter in degrees.
+    ///
+    /// # Panics
+    /// Panics if `angular_diameter` is not in range [0, 180].
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::lights::DirectionalLight;
+    /// use crab_rt::vec::{Color3, Vec3};
+    ///
+    /// let light =
+    ///     DirectionalLight::new(Vec3::new(0., -1., 0.), Color3::new(1., 1., 1.)).angular_diameter(5.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn angular_diameter(self, angular_diameter: f32) -> Self {
+        assert!(
+            (0. ..=180.).contains(&angular_diameter),
+            "angular_diameter should be in range [0, 180]"
+        );
+
+        Self {
+            cos_radius: (0.5 * angular_diameter).to_radians().cos(),
+            ..self
+        }
+    }
+}
+
+impl LightSource for DirectionalLight {
+    fn sample(&self, _p: &Point3) -> Option<LightSample> {
+        let axis = -self.direction;
+        let direction = if self.cos_radius < 1. {
+            // Uniform sampling of the cone of directions towards the disk of the light
+            let mut rng = rng();
+            let cos_theta = rng.gen::<f32>().mul_add(self.cos_radius - 1., 1.);
+            let sin_theta = cos_theta.mul_add(-cos_theta, 1.).max(0.).sqrt();
+            let phi = 2. * core::f32::consts::PI * rng.gen::<f32>();
+            let (tangent, bitangent) = orthonormal_basis(&axis);
+
+            (sin_theta * phi.cos()) * tangent
+                + (sin_theta * phi.sin()) * bitangent
+                + cos_theta * axis
+        } else {
+            axis
+        };
+
+        Some(LightSample {
+            direction,
+            distance: f32::INFINITY,
+            irradiance: self.irradiance,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directional_light_samples_its_disk() {
+        let testee = DirectionalLight::new(Vec3::new(0., -1., 0.), Color3::new(1., 1., 1.))
+            .angular_diameter(10.);
+
+        for _ in 0..100 {
+            let sample = testee.sample(&Point3::zero()).unwrap();
+            assert!((sample.direction.length() - 1.).abs() < 1e-5);
+            assert!(sample.direction.y >= 5_f32.to_radians().cos() - 1e-5);
+            assert_eq!(sample.distance, f32::INFINITY);
+        }
+    }
+}
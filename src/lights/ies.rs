@@ -0,0 +1,320 @@
+use alloc::vec::Vec;
+
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
+#[cfg(feature = "std")]
+use {
+    anyhow::{anyhow, bail, Context, Result},
+    std::{format, fs},
+};
+
+/// Maximum count of angles or tilt pairs of a file, far above the ones of real measures.
+#[cfg(feature = "std")]
+const MAX_COUNT: f32 = 100_000.;
+
+/// A measured light distribution of a luminaire, as given by the IES LM-63 photometric files of
+/// manufacturers.
+///
+/// The distribution follows the type C photometry: the vertical angle goes from 0 degrees at
+/// the nadir below the luminaire to 180 degrees at the zenith, and the horizontal angle turns
+/// around the vertical axis.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IesProfile {
+    /// Vertical angles in degrees, in increasing order.
+    vertical_angles: Vec<f32>,
+    /// Horizontal angles in degrees, in increasing order.
+    horizontal_angles: Vec<f32>,
+    /// Intensities in candelas, for each horizontal angle and each vertical angle.
+    candelas: Vec<f32>,
+    max_candela: f32,
+}
+
+impl IesProfile {
+    /// Constructs a new `IesProfile` from the intensities in candelas measured at the given
+    /// vertical and horizontal angles, in degrees. `candelas` holds the intensities at every
+    /// vertical angle for the first horizontal angle, then for the second one, and so on.
+    ///
+    /// A single horizontal angle gives an axially symmetric distribution. Horizontal angles
+    /// ending at 90 or 180 degrees give a distribution symmetric in each quadrant or across a
+    /// plane.
+    ///
+    /// # Panics
+    /// Panics if there are no angles, if the angles are not strictly increasing or if the number
+    /// of intensities does not match the number of angles.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::lights::IesProfile;
+    ///
+    /// // A downlight whose intensity halves at 45 degrees from its axis
+    /// let profile = IesProfile::new(vec![0., 45., 90.], vec![0.], vec![1000., 500., 0.]);
+    /// assert_eq!(profile.intensity(22.5, 0.), 750.);
+    /// ```
+    #[must_use]
+    pub fn new(vertical_angles: Vec<f32>, horizontal_angles: Vec<f32>, candelas: Vec<f32>) -> Self {
+        assert!(
+            !vertical_angles.is_empty() && !horizontal_angles.is_empty(),
+            "a profile needs vertical and horizontal angles"
+        );
+        assert!(
+            Self::is_increasing(&vertical_angles) && Self::is_increasing(&horizontal_angles),
+            "the angles of a profile must be strictly increasing"
+        );
+        assert_eq!(
+            candelas.len(),
+            vertical_angles.len() * horizontal_angles.len(),
+            "a profile needs an intensity for each pair of angles"
+        );
+
+        let max_candela = candelas.iter().copied().fold(0., f32::max);
+        Self {
+            vertical_angles,
+            horizontal_angles,
+            candelas,
+            max_candela,
+        }
+    }
+
+    /// Parses the content of an IES LM-63 photometric file.
+    ///
+    /// # Errors
+    /// Returns an error if the content is not a valid IES file or if its photometry is not of
+    /// type C.
+    #[cfg(feature = "std")]
+    pub fn parse(content: &str) -> Result<Self> {
+        // The keywords end at the tilt line, after which the values are separated by spaces or
+        // commas regardless of the lines
+        let mut lines = content.lines();
+        let tilt = lines
+            .by_ref()
+            .find_map(|line| line.trim().strip_prefix("TILT="))
+            .ok_or_else(|| anyhow!("missing TILT line"))?;
+
+        let mut values = lines
+            .flat_map(|line| line.split(|c: char| c.is_whitespace() || c == ','))
+            .filter(|value| !value.is_empty())
+            .map(|value| {
+                value
+                    .parse::<f32>()
+                    .with_context(|| format!("invalid number {value}"))
+            });
+        let mut next = || {
+            values
+                .next()
+                .unwrap_or_else(|| bail!("unexpected end of file"))
+        };
+
+        if tilt.trim() == "INCLUDE" {
+            // The tilt of the lamps does not change the distribution of a fixed luminaire
+            let _geometry = next()?;
+            let pairs = Self::count(next()?)?;
+            let values = pairs
+                .checked_mul(2)
+                .ok_or_else(|| anyhow!("too many tilt angles"))?;
+            for _ in 0..values {
+                next()?;
+            }
+        }
+
+        let _lamps = next()?;
+        let _lumens_per_lamp = next()?;
+        let multiplier = next()?;
+        let vertical_count = Self::count(next()?)?;
+        let horizontal_count = Self::count(next()?)?;
+        // The photometric types are whole numbers, which are parsed exactly
+        #[allow(clippy::float_cmp)]
+        let is_type_c = next()? == 1.;
+        if !is_type_c {
+            bail!("only type C photometry is supported");
+        }
+        // Units and dimensions of the luminaire, ballast factors and input watts
+        for _ in 0..7 {
+            next()?;
+        }
+
+        let vertical_angles = (0..vertical_count)
+            .map(|_| next())
+            .collect::<Result<Vec<_>>>()?;
+        let horizontal_angles = (0..horizontal_count)
+            .map(|_| next())
+            .collect::<Result<Vec<_>>>()?;
+        let candela_count = vertical_count
+            .checked_mul(horizontal_count)
+            .ok_or_else(|| anyhow!("too many angles"))?;
+        let candelas = (0..candela_count)
+            .map(|_| next().map(|candela| multiplier * candela))
+            .collect::<Result<Vec<_>>>()?;
+
+        if vertical_angles.is_empty() || horizontal_angles.is_empty() {
+            bail!("missing angles");
+        }
+        if !Self::is_increasing(&vertical_angles) || !Self::is_increasing(&horizontal_angles) {
+            bail!("angles are not strictly increasing");
+        }
+
+        Ok(Self::new(vertical_angles, horizontal_angles, candelas))
+    }
+
+    /// Converts a count of a file to an integer, rejecting the ones that are not whole numbers
+    /// in range [0, `MAX_COUNT`].
+    #[cfg(feature = "std")]
+    fn count(value: f32) -> Result<usize> {
+        if !(0. ..=MAX_COUNT).contains(&value) || value.fract() != 0. {
+            bail!("invalid count {value}");
+        }
+        // The count is a whole number within the range of usize
+        #[allow(clippy::cast_possible_truncation)]
+        #[allow(clippy::cast_sign_loss)]
+        Ok(value as usize)
+    }
+
+    /// Returns whether the angles are strictly increasing.
+    fn is_increasing(angles: &[f32]) -> bool {
+        angles.windows(2).all(|pair| pair[0] < pair[1])
+    }
+
+    /// Loads an IES LM-63 photometric file.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or is not a valid IES file.
+    #[cfg(feature = "std")]
+    pub fn load(filename: &str) -> Result<Self> {
+        let content = fs::read_to_string(filename)?;
+        Self::parse(&content).with_context(|| format!("invalid IES file {filename}"))
+    }
+
+    /// Returns the highest intensity of the distribution in candelas.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::lights::IesProfile;
+    ///
+    /// let profile = IesProfile::new(vec![0., 45., 90.], vec![0.], vec![1000., 500., 0.]);
+    /// assert_eq!(profile.max_intensity(), 1000.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn max_intensity(&self) -> f32 {
+        self.max_candela
+    }
+
+    /// Returns the intensity in candelas at the given vertical and horizontal angles in degrees,
+    /// interpolating the measures. Directions outside of the measured vertical angles receive no
+    /// light.
+    #[must_use]
+    pub fn intensity(&self, vertical: f32, horizontal: f32) -> f32 {
+        let Some((v, tv)) = Self::locate(&self.vertical_angles, vertical) else {
+            return 0.;
+        };
+        let (h, th, h_next) = self.locate_horizontal(horizontal);
+
+        let candela = |h: usize, v: usize| self.candelas[h * self.vertical_angles.len() + v];
+        let v_next = (v + 1).min(self.vertical_angles.len() - 1);
+        let at = |h| (1. - tv) * candela(h, v) + tv * candela(h, v_next);
+        (1. - th) * at(h) + th * at(h_next)
+    }
+
+    /// Returns the index of the last angle not greater than `angle` and the position of `angle`
+    /// towards the next one, or `None` if `angle` is out of the range of the angles.
+    fn locate(angles: &[f32], angle: f32) -> Option<(usize, f32)> {
+        let (first, last) = (angles[0], angles[angles.len() - 1]);
+        if angle < first || angle > last {
+            return None;
+        }
+        if angles.len() == 1 {
+            return Some((0, 0.));
+        }
+
+        let i = (angles.partition_point(|&a| a <= angle) - 1).min(angles.len() - 2);
+        Some((i, (angle - angles[i]) / (angles[i + 1] - angles[i])))
+    }
+
+    /// Returns the indices of the horizontal angles around `angle` and the position of `angle`
+    /// between them, following the symmetries of the distribution.
+    fn locate_horizontal(&self, angle: f32) -> (usize, f32, usize) {
+        let angles = &self.horizontal_angles;
+        let last = angles[angles.len() - 1];
+        if angles.len() == 1 {
+            return (0, 0., 0);
+        }
+
+        let mut angle = angle.rem_euclid(360.);
+        if last <= 180. && angle > 180. {
+            angle = 360. - angle;
+        }
+        if last <= 90. && angle > 90. {
+            angle = 180. - angle;
+        }
+
+        // Full distributions not measured up to 360 degrees wrap to their first angle
+        if angle > last {
+            let t = (angle - last) / (360. + angles[0] - last);
+            return (angles.len() - 1, t, 0);
+        }
+
+        let (i, t) = Self::locate(angles, angle.max(angles[0])).unwrap_or((0, 0.));
+        (i, t, i + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec;
+
+    #[test]
+    fn ies_profile_parses_lm_63_files() {
+        let content = "IESNA:LM-63-2002\n\
+            [MANUFAC] Crab lighting\n\
+            TILT=INCLUDE\n\
+            1\n\
+            2\n\
+            0 90\n\
+            1 1\n\
+            1 -1 2 3 2 1 2 0.1 0.1 0.1\n\
+            1.0 1.0 10\n\
+            0 45 90\n\
+            0,90\n\
+            100 50 0\n\
+            200 100 0\n";
+        let testee = IesProfile::parse(content).unwrap();
+
+        assert_eq!(testee.max_intensity(), 400.);
+        assert_eq!(testee.intensity(0., 0.), 200.);
+        assert_eq!(testee.intensity(45., 90.), 200.);
+        // Quadrant symmetry
+        assert_eq!(testee.intensity(45., 270.), 200.);
+        assert_eq!(testee.intensity(45., 45.), 150.);
+        // Above the measured angles
+        assert_eq!(testee.intensity(120., 0.), 0.);
+    }
+
+    #[test]
+    fn ies_profile_rejects_invalid_files() {
+        assert!(IesProfile::parse("IESNA:LM-63-2002\n").is_err());
+        assert!(IesProfile::parse("TILT=NONE\n1 1000 1 3 1 1 2").is_err());
+        // Counts out of range
+        assert!(IesProfile::parse("TILT=NONE\n1 1000 1 1e30 1e30 1 2").is_err());
+        assert!(IesProfile::parse("TILT=NONE\n1 1000 1 -1 1 1 2").is_err());
+        assert!(IesProfile::parse("TILT=INCLUDE\n1 1e30\n1 1000 1 1 1 1 2").is_err());
+        // Repeated angles
+        assert!(
+            IesProfile::parse("TILT=NONE\n1 1000 1 2 1 1 2 0 0 0 1 1 10\n0 0\n0\n1 1").is_err()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "strictly increasing")]
+    fn ies_profile_rejects_unsorted_angles() {
+        let _ = IesProfile::new(vec![45., 0.], vec![0.], vec![500., 1000.]);
+    }
+
+    #[test]
+    fn ies_profile_wraps_full_distributions() {
+        let testee = IesProfile::new(vec![0.], vec![0., 90., 180., 270.], vec![0., 1., 2., 3.]);
+
+        assert_eq!(testee.intensity(0., 315.), 1.5);
+        assert_eq!(testee.intensity(0., -45.), 1.5);
+    }
+}
@@ -0,0 +1,25 @@
+use core::fmt::Debug;
+
+use crate::vec::{Color3, Point3, Vec3};
+
+/// The light arriving at a point from a light source.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightSample {
+    /// Unit direction from the point towards the light.
+    pub direction: Vec3,
+    /// Distance from the point to the light, infinite for lights at infinity.
+    pub distance: f32,
+    /// Irradiance at the point on a surface facing the light.
+    pub irradiance: Color3,
+}
+
+/// A light source which is not part of the geometry of the scene, such as a point light or the
+/// sun, so rays never hit it by chance.
+///
+/// Light sources are sampled with shadow rays from every diffuse hit instead.
+pub trait LightSource: Debug + Send + Sync {
+    /// Returns a sample of the light arriving at point `p`, or `None` if the light does not
+    /// reach it.
+    #[must_use]
+    fn sample(&self, p: &Point3) -> Option<LightSample>;
+}
@@ -0,0 +1,13 @@
+pub mod directional;
+pub mod ies;
+pub mod light_source;
+pub mod photometric;
+pub mod point;
+pub mod spot;
+
+pub use directional::DirectionalLight;
+pub use ies::IesProfile;
+pub use light_source::{LightSample, LightSource};
+pub use photometric::PhotometricLight;
+pub use point::PointLight;
+pub use spot::SpotLight;
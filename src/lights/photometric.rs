@@ -0,0 +1,151 @@
+use super::{IesProfile, LightSample, LightSource};
+use crate::vec::{Color3, Point3, Vec3};
+
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
+/// A point light whose intensity follows the measured distribution of a luminaire.
+#[derive(Debug)]
+pub struct PhotometricLight {
+    position: Point3,
+    profile: IesProfile,
+    /// Power per unit solid angle in the brightest direction of the distribution.
+    intensity: Color3,
+    /// Unit direction of the nadir of the distribution.
+    nadir: Vec3,
+    /// Unit direction of the horizontal angle 0 of the distribution, orthogonal to the nadir.
+    zero: Vec3,
+}
+
+impl PhotometricLight {
+    /// Constructs a new `PhotometricLight` at `position` following the distribution of the
+    /// profile, with the given intensity in the brightest direction of the distribution.
+    ///
+    /// The nadir of the distribution points down the y axis and its horizontal angle 0 along
+    /// the x axis.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::lights::{IesProfile, PhotometricLight};
+    /// use crab_rt::vec::{Color3, Point3};
+    ///
+    /// let profile = IesProfile::new(vec![0., 45., 90.], vec![0.], vec![1000., 500., 0.]);
+    /// let downlight =
+    ///     PhotometricLight::new(Point3::new(0., 3., 0.), profile, Color3::new(5., 5., 5.));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(position: Point3, profile: IesProfile, intensity: Color3) -> Self {
+        Self {
+            position,
+            profile,
+            intensity,
+            nadir: Vec3::new(0., -1., 0.),
+            zero: Vec3::new(1., 0., 0.),
+        }
+    }
+
+    /// Constructs a new `PhotometricLight` emitting the intensities of the profile, in
+    /// candelas, multiplied by `scale`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::lights::{IesProfile, PhotometricLight};
+    /// use crab_rt::vec::Point3;
+    ///
+    /// let profile = IesProfile::new(vec![0., 45., 90.], vec![0.], vec![1000., 500., 0.]);
+    /// let downlight = PhotometricLight::from_candelas(Point3::new(0., 3., 0.), profile, 0.01);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn from_candelas(position: Point3, profile: IesProfile, scale: f32) -> Self {
+        let intensity = scale * profile.max_intensity();
+        Self::new(
+            position,
+            profile,
+            Color3::new(intensity, intensity, intensity),
+        )
+    }
+
+    /// Consumes the `PhotometricLight` and returns self with the nadir of the distribution
+    /// pointing in `nadir` and its horizontal angle 0 towards `zero`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::lights::{IesProfile, PhotometricLight};
+    /// use crab_rt::vec::{Color3, Point3, Vec3};
+    ///
+    /// let profile = IesProfile::new(vec![0., 45., 90.], vec![0.], vec![1000., 500., 0.]);
+    /// let wall_washer =
+    ///     PhotometricLight::new(Point3::new(0., 3., 0.), profile, Color3::new(5., 5., 5.))
+    ///         .orientation(Vec3::new(1., -1., 0.), Vec3::new(0., 0., 1.));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn orientation(self, nadir: Vec3, zero: Vec3) -> Self {
+        let nadir = nadir.unit();
+        Self {
+            nadir,
+            zero: (zero - zero.dot(&nadir) * nadir).unit(),
+            ..self
+        }
+    }
+
+    /// Returns the fraction of the intensity emitted in the unit direction `direction`.
+    fn distribution(&self, direction: &Vec3) -> f32 {
+        if self.profile.max_intensity() <= 0. {
+            return 0.;
+        }
+
+        let vertical = direction
+            .dot(&self.nadir)
+            .clamp(-1., 1.)
+            .acos()
+            .to_degrees();
+        let ninety = self.nadir.cross(&self.zero);
+        let horizontal = f32::atan2(direction.dot(&ninety), direction.dot(&self.zero)).to_degrees();
+
+        self.profile.intensity(vertical, horizontal) / self.profile.max_intensity()
+    }
+}
+
+impl LightSource for PhotometricLight {
+    fn sample(&self, p: &Point3) -> Option<LightSample> {
+        let to_light = self.position - p;
+        let distance_squared = to_light.squared_length();
+        if distance_squared == 0. {
+            return None;
+        }
+
+        let distance = distance_squared.sqrt();
+        let direction = to_light / distance;
+        let distribution = self.distribution(&-direction);
+        if distribution == 0. {
+            return None;
+        }
+
+        Some(LightSample {
+            direction,
+            distance,
+            irradiance: distribution * self.intensity / distance_squared,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec;
+
+    #[test]
+    fn photometric_light_follows_profile() {
+        let profile = IesProfile::new(vec![0., 90.], vec![0.], vec![2., 1.]);
+        let testee = PhotometricLight::new(Point3::zero(), profile, Color3::new(1., 1., 1.));
+
+        let below = testee.sample(&Point3::new(0., -1., 0.)).unwrap();
+        let side = testee.sample(&Point3::new(1., 0., 0.)).unwrap();
+        assert_eq!(below.irradiance, Color3::new(1., 1., 1.));
+        assert!((side.irradiance.x - 0.5).abs() < 1e-5);
+        assert!(testee.sample(&Point3::new(0., 1., 0.)).is_none());
+    }
+}
@@ -0,0 +1,67 @@
+use super::{LightSample, LightSource};
+use crate::vec::{Color3, Point3};
+
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
+/// A light emitting evenly in all directions from a single point.
+#[derive(Debug)]
+pub struct PointLight {
+    position: Point3,
+    /// Power per unit solid angle.
+    intensity: Color3,
+}
+
+impl PointLight {
+    /// Constructs a new `PointLight` at `position` with the given intensity, the power per
+    /// unit solid angle.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::lights::PointLight;
+    /// use crab_rt::vec::{Color3, Point3};
+    ///
+    /// let bulb = PointLight::new(Point3::new(0., 3., 0.), Color3::new(10., 9., 8.));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new(position: Point3, intensity: Color3) -> Self {
+        Self {
+            position,
+            intensity,
+        }
+    }
+}
+
+impl LightSource for PointLight {
+    fn sample(&self, p: &Point3) -> Option<LightSample> {
+        let to_light = self.position - p;
+        let distance_squared = to_light.squared_length();
+        if distance_squared == 0. {
+            return None;
+        }
+
+        let distance = distance_squared.sqrt();
+        Some(LightSample {
+            direction: to_light / distance,
+            distance,
+            irradiance: self.intensity / distance_squared,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec::Vec3;
+
+    #[test]
+    fn point_light_follows_inverse_square_law() {
+        let testee = PointLight::new(Point3::new(0., 2., 0.), Color3::new(8., 8., 8.));
+        let sample = testee.sample(&Point3::zero()).unwrap();
+
+        assert_eq!(sample.direction, Vec3::new(0., 1., 0.));
+        assert_eq!(sample.distance, 2.);
+        assert_eq!(sample.irradiance, Color3::new(2., 2., 2.));
+    }
+}
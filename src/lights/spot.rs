@@ -0,0 +1,152 @@
+use super::{LightSample, LightSource};
+use crate::vec::{Color3, Point3, Vec3};
+
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
+/// A point light emitting in a cone, whose intensity falls off smoothly towards the border of
+/// the cone.
+#[derive(Debug)]
+pub struct SpotLight {
+    position: Point3,
+    /// Unit direction of the axis of the cone.
+    direction: Vec3,
+    /// Power per unit solid angle along the axis of the cone.
+    intensity: Color3,
+    /// Cosine of the angle from the axis where the intensity starts falling off.
+    cos_falloff_start: f32,
+    /// Cosine of the half angle of the cone.
+    cos_cone: f32,
+}
+
+impl SpotLight {
+    /// Constructs a new `SpotLight` at `position` pointing in `direction` with the given
+    /// intensity, the power per unit solid angle along its axis.
+    ///
+    /// The default cone has a half angle of 30 degrees and falls off from 25 degrees.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::lights::SpotLight;
+    /// use crab_rt::vec::{Color3, Point3, Vec3};
+    ///
+    /// let spot = SpotLight::new(
+    ///     Point3::new(0., 5., 0.),
+    ///     Vec3::new(0., -1., 0.),
+    ///     Color3::new(50., 50., 50.),
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(position: Point3, direction: Vec3, intensity: Color3) -> Self {
+        Self {
+            position,
+            direction: direction.unit(),
+            intensity,
+            cos_falloff_start: 25_f32.to_radians().cos(),
+            cos_cone: 30_f32.to_radians().cos(),
+        }
+    }
+
+    /// Consumes the `SpotLight` and returns self with a cone of half angle `angle` whose
+    /// intensity falls off from `falloff_start`, both in degrees.
+    ///
+    /// # Panics
+    /// Panics if `falloff_start` is greater than `angle` or if `angle` is not in range [0, 180].
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::lights::SpotLight;
+    /// use crab_rt::vec::{Color3, Point3, Vec3};
+    ///
+    /// let spot = SpotLight::new(
+    ///     Point3::new(0., 5., 0.),
+    ///     Vec3::new(0., -1., 0.),
+    ///     Color3::new(50., 50., 50.),
+    /// )
+    /// .cone(10., 15.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn cone(self, falloff_start: f32, angle: f32) -> Self {
+        assert!(
+            (0. ..=180.).contains(&angle),
+            "angle should be in range [0, 180]"
+        );
+        assert!(
+            falloff_start <= angle,
+            "falloff_start should not be greater than angle"
+        );
+
+        Self {
+            cos_falloff_start: falloff_start.to_radians().cos(),
+            cos_cone: angle.to_radians().cos(),
+            ..self
+        }
+    }
+
+    /// Returns the fraction of the intensity emitted at an angle of cosine `cos_theta` from the
+    /// axis.
+    #[inline]
+    fn falloff(&self, cos_theta: f32) -> f32 {
+        if cos_theta >= self.cos_falloff_start {
+            return 1.;
+        }
+        if cos_theta <= self.cos_cone {
+            return 0.;
+        }
+
+        // Smoothstep between the border of the cone and the start of the falloff
+        let t = (cos_theta - self.cos_cone) / (self.cos_falloff_start - self.cos_cone);
+        t * t * 2f32.mul_add(-t, 3.)
+    }
+}
+
+impl LightSource for SpotLight {
+    fn sample(&self, p: &Point3) -> Option<LightSample> {
+        let to_light = self.position - p;
+        let distance_squared = to_light.squared_length();
+        if distance_squared == 0. {
+            return None;
+        }
+
+        let distance = distance_squared.sqrt();
+        let direction = to_light / distance;
+        let falloff = self.falloff(-direction.dot(&self.direction));
+        if falloff == 0. {
+            return None;
+        }
+
+        Some(LightSample {
+            direction,
+            distance,
+            irradiance: falloff * self.intensity / distance_squared,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spot_light_falls_off_towards_cone_border() {
+        let testee = SpotLight::new(
+            Point3::new(0., 1., 0.),
+            Vec3::new(0., -1., 0.),
+            Color3::new(1., 1., 1.),
+        )
+        .cone(30., 60.);
+
+        let irradiance = |x: f32| {
+            testee
+                .sample(&Point3::new(x, 0., 0.))
+                .map_or(0., |sample| sample.irradiance.x * (1. + x * x))
+        };
+
+        // Inside the falloff start, at 45 degrees and outside of the cone
+        assert!((irradiance(0.5) - 1.).abs() < 1e-5);
+        assert!(irradiance(1.) > 0. && irradiance(1.) < 1.);
+        assert_eq!(irradiance(2.), 0.);
+    }
+}
@@ -1,5 +1,7 @@
+use std::f32::consts::TAU;
 use std::sync::Arc;
 
+use crab_rt::animation::{CameraAnimation, Interpolation, Track};
 use crab_rt::camera::Camera;
 use crab_rt::materials::{Dielectric, Lambertian, Metal};
 use crab_rt::objects::Sphere;
@@ -10,19 +12,34 @@ use crab_rt::vec::{Color3, Point3, Vec3};
 
 const WIDTH: u32 = 600;
 const HEIGHT: u32 = 300;
+const FPS: f32 = 24.;
 
 fn main() {
     // TODO: Remove that
     let start = std::time::Instant::now();
 
-    let raytracer = raytracer1();
-
-    raytracer.raytrace().save("out.jpg").unwrap();
+    // `--sequence <frames>` renders a turntable instead of a still
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() == Some("--sequence") {
+        let frames = args.next().and_then(|f| f.parse().ok()).unwrap_or(48);
+        raytracer1((0., frames as f32 / FPS))
+            .render_sequence(
+                &turntable(frames as f32 / FPS),
+                0..frames,
+                FPS,
+                0.5,
+                "out_####.jpg",
+            )
+            .unwrap();
+    } else {
+        raytracer1((0., 0.)).raytrace().save("out.jpg").unwrap();
+    }
 
     println!("Done in {:?}", start.elapsed());
 }
 
-fn raytracer1() -> RayTracer {
+/// The scene of three spheres, whose moving objects are rendered during `time_interval`.
+fn raytracer1(time_interval: (f32, f32)) -> RayTracer {
     let camera = Camera::new(
         Point3::new(4., 2., 4.),
         Point3::new(0., 0., -1.),
@@ -63,7 +80,30 @@ fn raytracer1() -> RayTracer {
     //     -0.45,
     //     Dielectric::new(1.5),
     // ))
+    .time_interval(time_interval)
     .build();
 
     RayTracer::new(WIDTH, HEIGHT, 200, 50, camera, scene)
 }
+
+/// A camera turning once around the scene of `raytracer1` in `duration` seconds.
+fn turntable(duration: f32) -> CameraAnimation {
+    let center = Point3::new(0., 0., -1.);
+    let orbit = (0..=8).fold(
+        Track::new(0., center + Vec3::new(0., 2., 6.)),
+        |track, i| {
+            let angle = i as f32 / 8. * TAU;
+            track.key(
+                i as f32 / 8. * duration,
+                center + Vec3::new(6. * angle.sin(), 2., 6. * angle.cos()),
+            )
+        },
+    );
+
+    CameraAnimation::new(
+        orbit.interpolation(Interpolation::CatmullRom),
+        Track::new(0., center),
+        Track::new(0., 20.),
+        WIDTH as f32 / HEIGHT as f32,
+    )
+}
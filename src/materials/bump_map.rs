@@ -0,0 +1,171 @@
+use alloc::boxed::Box;
+
+use super::{Material, Scatter};
+use crate::hitable::HitRecord;
+use crate::ray::Ray;
+use crate::textures::Texture;
+use crate::vec::{Point3, Vec3};
+
+/// Offset of the texture coordinates used to compute the slopes of the height map.
+const DELTA: f32 = 1e-3;
+
+/// A material wrapper perturbing the shading normal of a material as if its surface was displaced
+/// along the normal by a height map, the mean of the color channels being used as height.
+#[derive(Debug)]
+pub struct BumpMap {
+    material: Box<dyn Material>,
+    heights: Box<dyn Texture>,
+    /// Displacement of the surface for a height of 1.
+    strength: f32,
+}
+
+impl BumpMap {
+    /// Constructs a new `BumpMap` applying the heights of the texture to the material.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::{BumpMap, Lambertian};
+    /// use crab_rt::textures::Noise;
+    ///
+    /// let material = BumpMap::new(Lambertian::from_rgb(0.5, 0.5, 0.5), Noise::new(4.));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new<M: 'static + Material, T: 'static + Texture>(material: M, heights: T) -> Self {
+        Self {
+            material: Box::new(material),
+            heights: Box::new(heights),
+            strength: 1.,
+        }
+    }
+
+    /// Consumes the `BumpMap` and returns self with the heights scaled by `strength`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::{BumpMap, Lambertian};
+    /// use crab_rt::textures::Noise;
+    ///
+    /// let material = BumpMap::new(Lambertian::from_rgb(0.5, 0.5, 0.5), Noise::new(4.)).strength(0.05);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn strength(self, strength: f32) -> Self {
+        Self { strength, ..self }
+    }
+
+    /// Returns the height of the surface at the given texture coordinates and point.
+    #[inline]
+    fn height(&self, texture_coordinates: (f32, f32), p: &Point3) -> f32 {
+        let color = self.heights.value(texture_coordinates, p);
+        self.strength * (color.x + color.y + color.z) / 3.
+    }
+
+    /// Returns a copy of the record with the shading normal of the displaced surface.
+    #[allow(clippy::similar_names)]
+    fn perturb<'material>(&self, record: &HitRecord<'material>) -> HitRecord<'material> {
+        let (u, v) = record.texture_coordinates();
+        let p = record.hit_point();
+        let normal = record.normal();
+
+        // Uses the shading frame when the surface has no partial derivatives
+        let (dpdu, dpdv) = if record.dpdu().is_near_zero() || record.dpdv().is_near_zero() {
+            record.shading_frame()
+        } else {
+            (*record.dpdu(), *record.dpdv())
+        };
+
+        // Finite differences of the height along the texture coordinates
+        let height = self.height((u, v), p);
+        let dhdu = (self.height((u + DELTA, v), &(p + DELTA * dpdu)) - height) / DELTA;
+        let dhdv = (self.height((u, v + DELTA), &(p + DELTA * dpdv)) - height) / DELTA;
+
+        let displaced = (dpdu + dhdu * normal).cross(&(dpdv + dhdv * normal));
+        let mut record = record.clone();
+        if !displaced.is_near_zero() {
+            // Keeps the normal on the side of the surface the ray comes from
+            let displaced = displaced.unit();
+            record.set_normal(if displaced.dot(normal) < 0. {
+                -displaced
+            } else {
+                displaced
+            });
+        }
+        record
+    }
+}
+
+impl Material for BumpMap {
+    fn scatter(&self, ray: &Ray, record: &HitRecord<'_>) -> Option<Scatter> {
+        self.material.scatter(ray, &self.perturb(record))
+    }
+
+    fn scattering_pdf(&self, ray: &Ray, record: &HitRecord<'_>, scattered: &Ray) -> f32 {
+        self.material
+            .scattering_pdf(ray, &self.perturb(record), scattered)
+    }
+
+    fn evaluate(&self, ray: &Ray, record: &HitRecord<'_>, scattered: &Ray) -> Vec3 {
+        self.material
+            .evaluate(ray, &self.perturb(record), scattered)
+    }
+
+    fn emitted(&self, ray: &Ray, record: &HitRecord<'_>) -> Vec3 {
+        self.material.emitted(ray, record)
+    }
+
+    fn opacity(&self, record: &HitRecord<'_>) -> f32 {
+        self.material.opacity(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::materials::Lambertian;
+    use crate::textures::Monochrome;
+
+    /// A height map increasing along the first texture coordinate.
+    #[derive(Debug)]
+    struct Slope;
+
+    impl Texture for Slope {
+        fn value(&self, texture_coordinates: (f32, f32), _p: &Point3) -> Vec3 {
+            Vec3::new(
+                texture_coordinates.0,
+                texture_coordinates.0,
+                texture_coordinates.0,
+            )
+        }
+    }
+
+    fn record(material: &dyn Material) -> HitRecord<'_> {
+        let mut record = HitRecord::new(
+            1.,
+            Point3::zero(),
+            Vec3::new(0., 1., 0.),
+            (0.5, 0.5),
+            material,
+        );
+        record.set_partial_derivatives(Vec3::new(1., 0., 0.), Vec3::new(0., 0., -1.));
+        record
+    }
+
+    #[test]
+    fn bump_map_tilts_normal_against_slope() {
+        let testee = BumpMap::new(Lambertian::default(), Slope);
+        let perturbed = testee.perturb(&record(&testee));
+
+        // The surface y = u rises along x so its normal is (-1, 1, 0) / sqrt(2)
+        let expected = Vec3::new(-1., 1., 0.).unit();
+        assert!((*perturbed.normal() - expected).length() < 1e-3);
+    }
+
+    #[test]
+    fn bump_map_with_constant_heights_keeps_normal() {
+        let testee = BumpMap::new(Lambertian::default(), Monochrome::from_rgb(0.3, 0.3, 0.3));
+        let perturbed = testee.perturb(&record(&testee));
+
+        assert!((*perturbed.normal() - Vec3::new(0., 1., 0.)).length() < 1e-5);
+    }
+}
@@ -0,0 +1,213 @@
+use alloc::boxed::Box;
+use rand::Rng;
+
+use super::{Material, Scatter};
+use crate::hitable::HitRecord;
+use crate::ray::Ray;
+use crate::utils::{reflect, rng, schlick};
+use crate::vec::{Color3, Vec3};
+
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
+const VARNISH_REFRACTIVE_INDEX: f32 = 1.5;
+
+/// A material covered by a thin and smooth dielectric clear coat, like car paint, varnished wood
+/// or lacquered plastic.
+///
+/// Light is either reflected specularly by the coat, following the Fresnel equations, or goes
+/// through the coat and is scattered by the base material.
+#[derive(Debug)]
+pub struct Coated {
+    base: Box<dyn Material>,
+    /// Refractive index of the coat.
+    refractive_index: f32,
+    /// Color of white light after going through the coat perpendicularly.
+    tint: Color3,
+}
+
+impl Coated {
+    /// Constructs a new `Coated` material with a clear coat of the given refractive index over
+    /// the base material.
+    ///
+    /// # Panics
+    /// Panics if `refractive_index < 1.`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::{Coated, Lambertian};
+    ///
+    /// // Creates a red lacquered plastic
+    /// let material = Coated::new(Lambertian::from_rgb(0.8, 0.1, 0.1), 1.5);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new<M: 'static + Material>(base: M, refractive_index: f32) -> Self {
+        assert!(refractive_index >= 1.);
+
+        Self {
+            base: Box::new(base),
+            refractive_index,
+            tint: Color3::new(1., 1., 1.),
+        }
+    }
+
+    /// Constructs a new `Coated` material with a coat of varnish over the base material.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::{Coated, Lambertian};
+    ///
+    /// let material = Coated::varnish(Lambertian::from_rgb(0.4, 0.2, 0.1));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn varnish<M: 'static + Material>(base: M) -> Self {
+        Self::new(base, VARNISH_REFRACTIVE_INDEX)
+    }
+
+    /// Consumes the `Coated` and returns self with a coat absorbing light, whose color is the
+    /// one of white light after going through the coat perpendicularly.
+    ///
+    /// Light going through the coat at grazing angles travels longer inside it and gets darker.
+    ///
+    /// # Panics
+    /// Panics if a component of `tint` is not in range ]0, 1].
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::{Coated, Lambertian};
+    /// use crab_rt::vec::Color3;
+    ///
+    /// // Creates a wood with an amber varnish
+    /// let material =
+    ///     Coated::varnish(Lambertian::from_rgb(0.6, 0.4, 0.3)).tint(Color3::new(0.9, 0.7, 0.4));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn tint(self, tint: Color3) -> Self {
+        assert!(
+            [tint.x, tint.y, tint.z]
+                .iter()
+                .all(|component| *component > 0. && *component <= 1.),
+            "tint should be in range ]0, 1]"
+        );
+
+        Self { tint, ..self }
+    }
+
+    /// Returns the cosine of the angle between the normal and a ray refracted inside the coat
+    /// from outside with an angle whose cosine is `cos_theta`.
+    #[inline]
+    fn refracted_cosine(&self, cos_theta: f32) -> f32 {
+        let sin_theta_squared = 1. - cos_theta * cos_theta;
+        f32::sqrt(1. - sin_theta_squared / (self.refractive_index * self.refractive_index))
+    }
+
+    /// Returns the fraction of light going into and out of the coat with the given angles.
+    fn transmittance(&self, cos_in: f32, cos_out: f32) -> Vec3 {
+        let fresnel_out = schlick(cos_out, 1. / self.refractive_index);
+
+        // Path length inside the coat relatively to its thickness
+        let distance = 1. / self.refracted_cosine(cos_in) + 1. / self.refracted_cosine(cos_out);
+        (1. - fresnel_out)
+            * Vec3::new(
+                self.tint.x.powf(distance),
+                self.tint.y.powf(distance),
+                self.tint.z.powf(distance),
+            )
+    }
+}
+
+impl Material for Coated {
+    fn scatter(&self, ray: &Ray, record: &HitRecord<'_>) -> Option<Scatter> {
+        let unit_direction = ray.direction().unit();
+        let cos_in = f32::clamp(-unit_direction.dot(record.normal()), 0., 1.);
+
+        if schlick(cos_in, 1. / self.refractive_index) > rng().gen::<f32>() {
+            let reflected = reflect(&unit_direction, record.normal());
+            return Some(Scatter::specular(
+                Ray::new(*record.hit_point(), reflected, ray.time()),
+                Vec3::new(1., 1., 1.),
+            ));
+        }
+
+        // Light scattered by the base goes straight through the coat
+        let scatter = self.base.scatter(ray, record)?;
+        let cos_out = f32::clamp(scatter.ray.direction().unit().dot(record.normal()), 0., 1.);
+        Some(Scatter {
+            attenuation: scatter.attenuation * self.transmittance(cos_in, cos_out),
+            ..scatter
+        })
+    }
+
+    fn scattering_pdf(&self, ray: &Ray, record: &HitRecord<'_>, scattered: &Ray) -> f32 {
+        let cos_in = f32::clamp(-ray.direction().unit().dot(record.normal()), 0., 1.);
+        (1. - schlick(cos_in, 1. / self.refractive_index))
+            * self.base.scattering_pdf(ray, record, scattered)
+    }
+
+    fn evaluate(&self, ray: &Ray, record: &HitRecord<'_>, scattered: &Ray) -> Vec3 {
+        // The light goes through the coat when it is not reflected by it
+        let cos_in = f32::clamp(-ray.direction().unit().dot(record.normal()), 0., 1.);
+        let cos_out = f32::clamp(scattered.direction().unit().dot(record.normal()), 0., 1.);
+        (1. - schlick(cos_in, 1. / self.refractive_index))
+            * self.transmittance(cos_in, cos_out)
+            * self.base.evaluate(ray, record, scattered)
+    }
+
+    fn opacity(&self, record: &HitRecord<'_>) -> f32 {
+        self.base.opacity(record)
+    }
+
+    fn emitted(&self, ray: &Ray, record: &HitRecord<'_>) -> Vec3 {
+        self.base.emitted(ray, record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::materials::Lambertian;
+    use crate::vec::Point3;
+
+    #[test]
+    fn coated_reflects_more_at_grazing_angles() {
+        let testee = Coated::varnish(Lambertian::from_rgb(0., 0., 0.));
+
+        // Only the coat reflects light over a black base
+        let reflectance = |direction: Vec3| {
+            let ray = Ray::new(Point3::new(0., 1., 0.), direction, 0.);
+            let mut record =
+                HitRecord::new(1., Point3::zero(), Vec3::new(0., 1., 0.), (0., 0.), &testee);
+            record.set_face_normal(&ray);
+
+            let n = 10_000;
+            (0..n)
+                .filter_map(|_| testee.scatter(&ray, &record))
+                .map(|scatter| scatter.attenuation.x)
+                .sum::<f32>()
+                / n as f32
+        };
+
+        let normal = reflectance(Vec3::new(0., -1., 0.));
+        let grazing = reflectance(Vec3::new(1., -0.05, 0.));
+        assert!((normal - 0.04).abs() < 0.01);
+        assert!(grazing > 0.5);
+    }
+
+    #[test]
+    fn only_coat_reflection_is_specular() {
+        let testee = Coated::varnish(Lambertian::from_rgb(0.5, 0.5, 0.5));
+        let ray = Ray::new(Point3::new(-1., 1., 0.), Vec3::new(1., -1., 0.), 0.);
+        let mut record =
+            HitRecord::new(1., Point3::zero(), Vec3::new(0., 1., 0.), (0., 0.), &testee);
+        record.set_face_normal(&ray);
+
+        let mirror = Vec3::new(1., 1., 0.).unit();
+        for scatter in (0..1000).filter_map(|_| testee.scatter(&ray, &record)) {
+            let is_mirror = (scatter.ray.direction().unit() - mirror).length() < 1e-5;
+            assert_eq!(scatter.is_specular, is_mirror);
+        }
+    }
+}
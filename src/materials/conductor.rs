@@ -0,0 +1,514 @@
+use alloc::vec::Vec;
+use core::f32::consts::PI;
+use rand::Rng;
+
+use super::{Material, Scatter};
+use crate::hitable::HitRecord;
+use crate::ray::Ray;
+use crate::utils::{reflect, rng};
+use crate::vec::Vec3;
+
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
+/// Wavelengths in nanometers at which a spectral index of refraction is evaluated for the red,
+/// green and blue channels outside of spectral mode.
+const RGB_WAVELENGTHS: [f32; 3] = [610., 540., 450.];
+
+/// Smallest GGX roughness used to evaluate the distribution, which is a Dirac along the
+/// polished directions of a surface.
+const MIN_ALPHA: f32 = 1e-4;
+
+/// A metallic material whose reflectance follows the Fresnel equations of a conductor, with
+/// optional microfacet roughness.
+#[derive(Debug, Clone)]
+pub struct Conductor {
+    /// Real part of the index of refraction for the red, green and blue channels.
+    eta: Vec3,
+    /// Imaginary part (extinction coefficient) of the index of refraction for the red, green and
+    /// blue channels.
+    k: Vec3,
+    /// Tabulated `(wavelength, eta, k)` samples used in spectral mode, sorted by wavelength.
+    spectrum: Option<Vec<(f32, f32, f32)>>,
+    /// GGX roughness along the two tangent directions.
+    alpha: (f32, f32),
+}
+
+impl Conductor {
+    /// Constructs a new polished `Conductor` material with the given complex index of refraction
+    /// `eta + i k` for the red, green and blue channels.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::Conductor;
+    /// use crab_rt::vec::Vec3;
+    ///
+    /// let material = Conductor::new(Vec3::new(0.2, 0.92, 1.1), Vec3::new(3.91, 2.45, 2.14));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new(eta: Vec3, k: Vec3) -> Self {
+        Self {
+            eta,
+            k,
+            spectrum: None,
+            alpha: (0., 0.),
+        }
+    }
+
+    /// Constructs a new polished `Conductor` material from `(wavelength, eta, k)` samples of its
+    /// complex index of refraction, with wavelengths in nanometers.
+    ///
+    /// The samples are interpolated at the wavelength of the rays in spectral mode.
+    ///
+    /// # Panics
+    /// Panics if `samples` is empty or not sorted by wavelength.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::Conductor;
+    ///
+    /// let material = Conductor::from_spectrum(vec![
+    ///     (450., 1.38, 1.91),
+    ///     (550., 0.33, 2.33),
+    ///     (650., 0.16, 3.52),
+    /// ]);
+    /// ```
+    #[must_use]
+    pub fn from_spectrum(samples: Vec<(f32, f32, f32)>) -> Self {
+        assert!(!samples.is_empty(), "samples should not be empty");
+        assert!(
+            samples.windows(2).all(|pair| pair[0].0 <= pair[1].0),
+            "samples should be sorted by wavelength"
+        );
+
+        let [red, green, blue] =
+            RGB_WAVELENGTHS.map(|wavelength| interpolate(&samples, wavelength));
+
+        Self {
+            eta: Vec3::new(red.0, green.0, blue.0),
+            k: Vec3::new(red.1, green.1, blue.1),
+            spectrum: Some(samples),
+            alpha: (0., 0.),
+        }
+    }
+
+    /// Constructs a new `Conductor` material with the index of refraction of gold.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::Conductor;
+    ///
+    /// let gold_material = Conductor::gold();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn gold() -> Self {
+        Self::new(
+            Vec3::new(0.143, 0.374, 1.442),
+            Vec3::new(3.983, 2.385, 1.603),
+        )
+    }
+
+    /// Constructs a new `Conductor` material with the index of refraction of silver.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::Conductor;
+    ///
+    /// let silver_material = Conductor::silver();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn silver() -> Self {
+        Self::new(
+            Vec3::new(0.155, 0.117, 0.138),
+            Vec3::new(4.828, 3.122, 2.147),
+        )
+    }
+
+    /// Constructs a new `Conductor` material with the index of refraction of copper.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::Conductor;
+    ///
+    /// let copper_material = Conductor::copper();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn copper() -> Self {
+        Self::new(Vec3::new(0.2, 0.924, 1.102), Vec3::new(3.912, 2.452, 2.142))
+    }
+
+    /// Constructs a new `Conductor` material with the index of refraction of aluminium.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::Conductor;
+    ///
+    /// let aluminium_material = Conductor::aluminium();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn aluminium() -> Self {
+        Self::new(Vec3::new(1.657, 0.88, 0.521), Vec3::new(9.224, 6.27, 4.837))
+    }
+
+    /// Constructs a new `Conductor` material with the index of refraction of iron.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::Conductor;
+    ///
+    /// let iron_material = Conductor::iron();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn iron() -> Self {
+        Self::new(Vec3::new(2.912, 2.95, 2.585), Vec3::new(3.077, 2.93, 2.609))
+    }
+
+    /// Consumes the `Conductor` and returns self with the given roughness, following the GGX
+    /// microfacet distribution.
+    ///
+    /// A roughness of 0 is a perfect mirror and a roughness of 1 is close to a diffuse surface.
+    ///
+    /// # Panics
+    /// Panics if `roughness` is not in range [0, 1].
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::Conductor;
+    ///
+    /// let material = Conductor::aluminium().roughness(0.3);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn roughness(self, roughness: f32) -> Self {
+        self.anisotropic_roughness(roughness, roughness)
+    }
+
+    /// Consumes the `Conductor` and returns self with different roughnesses along the two tangent
+    /// directions of the surface, which gives a brushed aspect.
+    ///
+    /// # Panics
+    /// Panics if `roughness_u` or `roughness_v` is not in range [0, 1].
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::Conductor;
+    ///
+    /// let brushed_material = Conductor::iron().anisotropic_roughness(0.05, 0.4);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn anisotropic_roughness(self, roughness_u: f32, roughness_v: f32) -> Self {
+        assert!(
+            (0. ..=1.).contains(&roughness_u) && (0. ..=1.).contains(&roughness_v),
+            "roughness should be in range [0, 1]"
+        );
+
+        // Squaring the roughness makes it perceptually linear
+        Self {
+            alpha: (roughness_u * roughness_u, roughness_v * roughness_v),
+            ..self
+        }
+    }
+
+    /// Returns the Fresnel reflectance of the conductor for light arriving with an angle whose
+    /// cosine is `cos_theta`, for a ray of the given wavelength.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::Conductor;
+    ///
+    /// let gold = Conductor::gold();
+    /// // Gold reflects red light better than blue light
+    /// let reflectance = gold.reflectance(1., None);
+    /// assert!(reflectance.x > reflectance.z);
+    /// ```
+    #[must_use]
+    pub fn reflectance(&self, cos_theta: f32, wavelength: Option<f32>) -> Vec3 {
+        if let (Some(spectrum), Some(wavelength)) = (&self.spectrum, wavelength) {
+            let (eta, k) = interpolate(spectrum, wavelength);
+            let reflectance = fresnel_conductor(cos_theta, eta, k);
+            return Vec3::new(reflectance, reflectance, reflectance);
+        }
+
+        Vec3::new(
+            fresnel_conductor(cos_theta, self.eta.x, self.k.x),
+            fresnel_conductor(cos_theta, self.eta.y, self.k.y),
+            fresnel_conductor(cos_theta, self.eta.z, self.k.z),
+        )
+    }
+
+    /// Samples a microfacet normal in the local frame of the surface, proportionally to its
+    /// projected area.
+    fn sample_microfacet_normal(&self) -> Vec3 {
+        let mut rng = rng();
+        let xi = rng.gen::<f32>();
+        let phi = 2. * PI * rng.gen::<f32>();
+
+        // Stretches a slope sampled from the GGX distribution of roughness 1
+        let slope = f32::sqrt(xi / (1. - xi));
+        Vec3::new(
+            self.alpha.0 * slope * phi.cos(),
+            self.alpha.1 * slope * phi.sin(),
+            1.,
+        )
+        .unit()
+    }
+
+    /// Returns the GGX density of the microfacet normal `h`, in the local frame of the surface.
+    fn distribution(&self, h: &Vec3) -> f32 {
+        let alpha_x = self.alpha.0.max(MIN_ALPHA);
+        let alpha_y = self.alpha.1.max(MIN_ALPHA);
+        let d = (h.x / alpha_x).powi(2) + (h.y / alpha_y).powi(2) + h.z * h.z;
+        1. / (PI * alpha_x * alpha_y * d * d)
+    }
+
+    /// Returns the directions of the ray leaving the hit towards its origin and of `scattered`,
+    /// and their microfacet normal, in the local frame of the surface, or `None` if `scattered`
+    /// goes below the surface.
+    fn local_directions(
+        ray: &Ray,
+        record: &HitRecord<'_>,
+        scattered: &Ray,
+    ) -> Option<(Vec3, Vec3, Vec3)> {
+        let normal = record.normal();
+        let (tangent, bitangent) = record.shading_frame();
+        let to_local = |v: &Vec3| Vec3::new(v.dot(&tangent), v.dot(&bitangent), v.dot(normal));
+
+        let outgoing = to_local(&-ray.direction().unit());
+        let incoming = to_local(&scattered.direction().unit());
+        if outgoing.z <= 0. || incoming.z <= 0. {
+            return None;
+        }
+
+        Some((outgoing, incoming, (outgoing + incoming).unit()))
+    }
+
+    /// Returns the Smith height-correlated masking-shadowing term of the GGX distribution for
+    /// the given directions in the local frame of the surface.
+    fn masking_shadowing(&self, incoming: &Vec3, outgoing: &Vec3) -> f32 {
+        let lambda = |w: &Vec3| {
+            let (alpha_x, alpha_y) = self.alpha;
+            let tan_theta_squared =
+                (alpha_x * alpha_x * w.x * w.x + alpha_y * alpha_y * w.y * w.y) / (w.z * w.z);
+            0.5 * (f32::sqrt(1. + tan_theta_squared) - 1.)
+        };
+
+        1. / (1. + lambda(incoming) + lambda(outgoing))
+    }
+}
+
+impl Material for Conductor {
+    fn scatter(&self, ray: &Ray, record: &HitRecord<'_>) -> Option<Scatter> {
+        // The roughness follows the texture directions of the surface
+        let normal = record.normal();
+        let (tangent, bitangent) = record.shading_frame();
+        let to_local = |v: &Vec3| Vec3::new(v.dot(&tangent), v.dot(&bitangent), v.dot(normal));
+
+        let outgoing = -ray.direction().unit();
+        let microfacet_normal = self.sample_microfacet_normal();
+        let microfacet_normal = microfacet_normal.x * tangent
+            + microfacet_normal.y * bitangent
+            + microfacet_normal.z * *normal;
+
+        let direction = reflect(&-outgoing, &microfacet_normal);
+        if direction.dot(normal) <= 0. {
+            return None;
+        }
+
+        // Weight of the microfacet BRDF divided by the probability density of the sampled direction
+        let cos_outgoing = outgoing.dot(normal).max(1e-6);
+        let cos_microfacet = outgoing.dot(&microfacet_normal).max(0.);
+        let weight = self.masking_shadowing(&to_local(&direction), &to_local(&outgoing))
+            * cos_microfacet
+            / (cos_outgoing * microfacet_normal.dot(normal));
+
+        Some(Scatter {
+            ray: Ray::new(*record.hit_point(), direction, ray.time()),
+            attenuation: weight * self.reflectance(cos_microfacet, ray.wavelength()),
+            is_specular: self.alpha == (0., 0.),
+        })
+    }
+
+    fn scattering_pdf(&self, ray: &Ray, record: &HitRecord<'_>, scattered: &Ray) -> f32 {
+        if self.alpha == (0., 0.) {
+            return 0.;
+        }
+        let Some((outgoing, _, microfacet_normal)) = Self::local_directions(ray, record, scattered)
+        else {
+            return 0.;
+        };
+
+        // Reflecting the microfacet normals stretches their density by 4 cos
+        self.distribution(&microfacet_normal) * microfacet_normal.z
+            / (4. * outgoing.dot(&microfacet_normal).max(1e-6))
+    }
+
+    fn evaluate(&self, ray: &Ray, record: &HitRecord<'_>, scattered: &Ray) -> Vec3 {
+        if self.alpha == (0., 0.) {
+            return Vec3::zero();
+        }
+        let Some((outgoing, incoming, microfacet_normal)) =
+            Self::local_directions(ray, record, scattered)
+        else {
+            return Vec3::zero();
+        };
+
+        // Microfacet BRDF times the cosine of the incoming direction
+        self.distribution(&microfacet_normal) * self.masking_shadowing(&incoming, &outgoing)
+            / (4. * outgoing.z)
+            * self.reflectance(outgoing.dot(&microfacet_normal), ray.wavelength())
+    }
+}
+
+/// Returns the Fresnel reflectance of unpolarized light on a conductor of complex index of
+/// refraction `eta + i k`.
+fn fresnel_conductor(cos_theta: f32, eta: f32, k: f32) -> f32 {
+    let cos_squared = cos_theta.clamp(0., 1.).powi(2);
+    let sin_squared = 1. - cos_squared;
+    let eta_squared = eta * eta;
+    let k_squared = k * k;
+
+    let t0 = eta_squared - k_squared - sin_squared;
+    let a_squared_plus_b_squared = f32::sqrt(t0.mul_add(t0, 4. * eta_squared * k_squared));
+    let a = f32::sqrt(f32::max(f32::midpoint(a_squared_plus_b_squared, t0), 0.));
+
+    let t1 = a_squared_plus_b_squared + cos_squared;
+    let t2 = 2. * cos_theta * a;
+    let reflectance_s = (t1 - t2) / (t1 + t2);
+
+    let t3 = cos_squared.mul_add(a_squared_plus_b_squared, sin_squared * sin_squared);
+    let t4 = t2 * sin_squared;
+    let reflectance_p = reflectance_s * (t3 - t4) / (t3 + t4);
+
+    f32::midpoint(reflectance_s, reflectance_p)
+}
+
+/// Linearly interpolates `(eta, k)` at the given wavelength from sorted samples.
+fn interpolate(samples: &[(f32, f32, f32)], wavelength: f32) -> (f32, f32) {
+    let i = samples.partition_point(|sample| sample.0 < wavelength);
+    if i == 0 {
+        return (samples[0].1, samples[0].2);
+    }
+    if i == samples.len() {
+        return (samples[i - 1].1, samples[i - 1].2);
+    }
+
+    let (previous, next) = (samples[i - 1], samples[i]);
+    let t = (wavelength - previous.0) / (next.0 - previous.0);
+    (
+        t.mul_add(next.1 - previous.1, previous.1),
+        t.mul_add(next.2 - previous.2, previous.2),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec::Point3;
+    use std::vec;
+
+    #[test]
+    fn fresnel_conductor_at_normal_incidence() {
+        let (eta, k) = (0.2, 3.9);
+        let expected = ((eta - 1.) * (eta - 1.) + k * k) / ((eta + 1.) * (eta + 1.) + k * k);
+
+        assert!((fresnel_conductor(1., eta, k) - expected).abs() < 1e-5);
+        assert!((fresnel_conductor(0., eta, k) - 1.).abs() < 1e-5);
+    }
+
+    #[test]
+    fn spectral_conductor_interpolates_samples() {
+        let testee = Conductor::from_spectrum(vec![(400., 1., 2.), (600., 2., 4.)]);
+
+        assert_eq!(
+            interpolate(testee.spectrum.as_ref().unwrap(), 500.),
+            (1.5, 3.)
+        );
+        assert_eq!(
+            interpolate(testee.spectrum.as_ref().unwrap(), 300.),
+            (1., 2.)
+        );
+        assert_eq!(
+            interpolate(testee.spectrum.as_ref().unwrap(), 700.),
+            (2., 4.)
+        );
+    }
+
+    #[test]
+    fn rough_conductor_conserves_energy() {
+        // A perfect reflector only loses energy through masking and shadowing
+        let testee = Conductor::new(Vec3::new(0., 0., 0.), Vec3::new(1e4, 1e4, 1e4)).roughness(0.5);
+        let ray = Ray::new(Point3::new(0., 0., 1.), Vec3::new(0.3, 0., -1.), 0.);
+        let mut record =
+            HitRecord::new(1., Point3::zero(), Vec3::new(0., 0., 1.), (0., 0.), &testee);
+        record.set_face_normal(&ray);
+
+        let n = 100_000;
+        let albedo = (0..n)
+            .filter_map(|_| testee.scatter(&ray, &record))
+            .map(|scatter| scatter.attenuation.x)
+            .sum::<f32>()
+            / n as f32;
+
+        assert!(albedo > 0.8 && albedo <= 1.01);
+    }
+
+    #[test]
+    fn only_polished_conductor_is_specular() {
+        let ray = Ray::new(Point3::new(0., 0., 1.), Vec3::new(0.3, 0., -1.), 0.);
+        let is_specular = |testee: &Conductor| {
+            let mut record =
+                HitRecord::new(1., Point3::zero(), Vec3::new(0., 0., 1.), (0., 0.), testee);
+            record.set_face_normal(&ray);
+            testee.scatter(&ray, &record).unwrap().is_specular
+        };
+
+        assert!(is_specular(&Conductor::gold()));
+        assert!(!is_specular(&Conductor::gold().roughness(0.3)));
+    }
+
+    #[test]
+    fn rough_conductor_weight_is_brdf_over_pdf() {
+        let testee = Conductor::gold().anisotropic_roughness(0.3, 0.6);
+        let ray = Ray::new(Point3::new(0., 0., 1.), Vec3::new(0.3, 0., -1.), 0.);
+        let mut record =
+            HitRecord::new(1., Point3::zero(), Vec3::new(0., 0., 1.), (0., 0.), &testee);
+        record.set_face_normal(&ray);
+        record.set_partial_derivatives(Vec3::new(1., 0., 0.), Vec3::new(0., 1., 0.));
+
+        // The weight of a sample is the evaluated BRDF divided by its pdf
+        for scatter in (0..100).filter_map(|_| testee.scatter(&ray, &record)) {
+            let expected = testee.evaluate(&ray, &record, &scatter.ray)
+                / testee.scattering_pdf(&ray, &record, &scatter.ray);
+            assert!((scatter.attenuation - expected).length() < 1e-3 * expected.length().max(1.));
+        }
+    }
+
+    #[test]
+    fn anisotropic_roughness_follows_dpdu() {
+        let testee = Conductor::iron().anisotropic_roughness(0.05, 0.6);
+        let ray = Ray::new(Point3::new(0., 0., 1.), Vec3::new(0., 0., -1.), 0.);
+
+        // Mean spread of the reflections along the x axis
+        let spread = |dpdu: Vec3, dpdv: Vec3| {
+            let mut record =
+                HitRecord::new(1., Point3::zero(), Vec3::new(0., 0., 1.), (0., 0.), &testee);
+            record.set_face_normal(&ray);
+            record.set_partial_derivatives(dpdu, dpdv);
+            (0..1000)
+                .filter_map(|_| testee.scatter(&ray, &record))
+                .map(|scatter| scatter.ray.direction().unit().x.abs())
+                .sum::<f32>()
+        };
+
+        let along_x = spread(Vec3::new(1., 0., 0.), Vec3::new(0., 1., 0.));
+        let along_y = spread(Vec3::new(0., 1., 0.), Vec3::new(-1., 0., 0.));
+        assert!(along_x * 10. < along_y);
+    }
+}
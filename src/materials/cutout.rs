@@ -0,0 +1,77 @@
+use alloc::boxed::Box;
+
+use super::{Material, Scatter};
+use crate::hitable::HitRecord;
+use crate::ray::Ray;
+use crate::textures::OpacityMask;
+use crate::vec::Vec3;
+
+/// A material wrapper letting rays go straight through the transparent points of a material,
+/// according to an opacity mask.
+///
+/// It works with any hitable but each transparent point costs a reflection, see
+/// [`objects::Cutout`](crate::objects::Cutout) to skip them during the traversal instead.
+#[derive(Debug)]
+pub struct Cutout {
+    material: Box<dyn Material>,
+    mask: OpacityMask,
+}
+
+impl Cutout {
+    /// Constructs a new `Cutout` of the material with the given opacity mask.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::{Cutout, Lambertian};
+    /// use crab_rt::textures::{Image, OpacityMask};
+    ///
+    /// let material = Cutout::new(
+    ///     Lambertian::from_rgb(0.1, 0.6, 0.1),
+    ///     OpacityMask::alpha(Image::from_rgba(1, 1, vec![0, 255, 0, 255])),
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new<M: 'static + Material>(material: M, mask: OpacityMask) -> Self {
+        Self {
+            material: Box::new(material),
+            mask,
+        }
+    }
+}
+
+impl Material for Cutout {
+    fn scatter(&self, ray: &Ray, record: &HitRecord<'_>) -> Option<Scatter> {
+        if self
+            .mask
+            .is_opaque(record.texture_coordinates(), record.hit_point())
+        {
+            return self.material.scatter(ray, record);
+        }
+
+        // Rays going through transparent points keep their direction
+        Some(Scatter::specular(
+            Ray::new(*record.hit_point(), *ray.direction(), ray.time()),
+            Vec3::new(1., 1., 1.),
+        ))
+    }
+
+    fn scattering_pdf(&self, ray: &Ray, record: &HitRecord<'_>, scattered: &Ray) -> f32 {
+        self.opacity(record) * self.material.scattering_pdf(ray, record, scattered)
+    }
+
+    fn evaluate(&self, ray: &Ray, record: &HitRecord<'_>, scattered: &Ray) -> Vec3 {
+        // Transparent points only let the light through
+        self.opacity(record) * self.material.evaluate(ray, record, scattered)
+    }
+
+    fn opacity(&self, record: &HitRecord<'_>) -> f32 {
+        self.mask
+            .coverage(record.texture_coordinates(), record.hit_point())
+            * self.material.opacity(record)
+    }
+
+    fn emitted(&self, ray: &Ray, record: &HitRecord<'_>) -> Vec3 {
+        self.opacity(record) * self.material.emitted(ray, record)
+    }
+}
@@ -1,22 +1,65 @@
 use rand::Rng;
 
-use super::material::Material;
+use super::material::{Material, Scatter};
 use crate::hitable::HitRecord;
 use crate::ray::Ray;
+use crate::spectrum::SODIUM_D_LINE;
 use crate::utils::{reflect, refract, rng, schlick};
-use crate::vec::Vec3;
+use crate::vec::{Color3, Vec3};
 
 #[cfg(not(feature = "std"))]
 use core_maths::*;
 
 const WATER_REFRACTIVE_INDEX: f32 = 1.333;
-const DIAMOND_REFRACTIVE_INDEX: f32 = 2.417;
 
 /// A material with refractions and specular reflections.
 #[derive(Debug, Default, Clone)]
 pub struct Dielectric {
     /// [Refractive index](https://en.wikipedia.org/wiki/List_of_refractive_indices) of the material.
     refractive_index: f32,
+    /// Variation of the refractive index with the wavelength, only used in spectral mode.
+    dispersion: Option<Dispersion>,
+    /// Absorption coefficients of the medium inside the material, per unit of distance.
+    absorption: Vec3,
+}
+
+/// A model of the variation of the refractive index with the wavelength.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Dispersion {
+    /// [Cauchy's equation](https://en.wikipedia.org/wiki/Cauchy%27s_equation)
+    /// `n(λ) = a + b / λ^2` with the wavelength in micrometers.
+    Cauchy { a: f32, b: f32 },
+    /// [Sellmeier equation](https://en.wikipedia.org/wiki/Sellmeier_equation)
+    /// `n(λ)^2 = 1 + Σ b_i λ^2 / (λ^2 - c_i)` with the wavelength in micrometers.
+    Sellmeier { b: [f32; 3], c: [f32; 3] },
+}
+
+impl Dispersion {
+    /// Returns the refractive index at the given wavelength in nanometers.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::dielectric::Dispersion;
+    ///
+    /// let dispersion = Dispersion::Cauchy { a: 1.5, b: 0.004 };
+    /// assert!(dispersion.refractive_index(400.) > dispersion.refractive_index(700.));
+    /// ```
+    #[must_use]
+    pub fn refractive_index(&self, wavelength: f32) -> f32 {
+        let micrometers = wavelength / 1000.;
+        let squared = micrometers * micrometers;
+
+        match self {
+            Self::Cauchy { a, b } => a + b / squared,
+            Self::Sellmeier { b, c } => f32::sqrt(
+                1. + b
+                    .iter()
+                    .zip(c)
+                    .map(|(b, c)| b * squared / (squared - c))
+                    .sum::<f32>(),
+            ),
+        }
+    }
 }
 
 impl Dielectric {
@@ -36,7 +79,36 @@ impl Dielectric {
     pub fn new(refractive_index: f32) -> Self {
         assert!(refractive_index >= 1.);
 
-        Self { refractive_index }
+        Self {
+            refractive_index,
+            dispersion: None,
+            absorption: Vec3::zero(),
+        }
+    }
+
+    /// Constructs a new dispersive `Dielectric` material whose refractive index varies with the
+    /// wavelength.
+    ///
+    /// Outside of spectral mode the refractive index at the sodium D line is used.
+    ///
+    /// # Panics
+    /// Panics if the refractive index at the sodium D line is lower than 1.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::dielectric::{Dielectric, Dispersion};
+    ///
+    /// let material = Dielectric::dispersive(Dispersion::Cauchy { a: 1.5, b: 0.004 });
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn dispersive(dispersion: Dispersion) -> Self {
+        let refractive_index = dispersion.refractive_index(SODIUM_D_LINE);
+
+        Self {
+            dispersion: Some(dispersion),
+            ..Self::new(refractive_index)
+        }
     }
 
     /// Constructs a new `Dielecric` material with the water's refractive index.
@@ -64,18 +136,127 @@ impl Dielectric {
     #[inline]
     #[must_use]
     pub fn diamond() -> Self {
-        Self::new(DIAMOND_REFRACTIVE_INDEX)
+        Self::dispersive(Dispersion::Sellmeier {
+            b: [0.3306, 4.3356, 0.],
+            c: [0.1750 * 0.1750, 0.1060 * 0.1060, 0.],
+        })
+    }
+
+    /// Constructs a new dispersive `Dielectric` material with the refractive index of the
+    /// BK7 crown glass.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::Dielectric;
+    ///
+    /// let glass_material = Dielectric::crown_glass();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn crown_glass() -> Self {
+        Self::dispersive(Dispersion::Sellmeier {
+            b: [1.039_612, 0.231_792_34, 1.010_469_4],
+            c: [0.006_000_699, 0.020_017_914, 103.560_65],
+        })
+    }
+
+    /// Constructs a new dispersive `Dielectric` material with the refractive index of the
+    /// SF11 dense flint glass, which disperses light much more than crown glass.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::Dielectric;
+    ///
+    /// let prism_material = Dielectric::flint_glass();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn flint_glass() -> Self {
+        Self::dispersive(Dispersion::Sellmeier {
+            b: [1.737_597, 0.313_747_35, 1.898_781],
+            c: [0.013_188_707, 0.062_306_814, 155.236_3],
+        })
+    }
+
+    /// Consumes the `Dielectric` and returns self with a medium absorbing light following the
+    /// [Beer-Lambert law](https://en.wikipedia.org/wiki/Beer%E2%80%93Lambert_law), which tints
+    /// the light going through it.
+    ///
+    /// `transmittance` is the color of white light after travelling `distance` inside the
+    /// material, so thick parts of an object look darker than thin parts.
+    ///
+    /// # Panics
+    /// Panics if a component of `transmittance` is not in range ]0, 1].
+    /// Panics if `distance <= 0.`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::Dielectric;
+    /// use crab_rt::vec::Color3;
+    ///
+    /// // Red wine lets through half of the red light after 1 unit of distance
+    /// let wine_material = Dielectric::water().absorption(Color3::new(0.5, 0.02, 0.05), 1.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn absorption(self, transmittance: Color3, distance: f32) -> Self {
+        assert!(
+            [transmittance.x, transmittance.y, transmittance.z]
+                .iter()
+                .all(|component| *component > 0. && *component <= 1.),
+            "transmittance should be in range ]0, 1]"
+        );
+        assert!(distance > 0., "distance should be positive");
+
+        Self {
+            absorption: Vec3::new(
+                -transmittance.x.ln() / distance,
+                -transmittance.y.ln() / distance,
+                -transmittance.z.ln() / distance,
+            ),
+            ..self
+        }
+    }
+
+    /// Returns the refractive index of the material for a ray of the given wavelength, or the
+    /// nominal refractive index if there is no wavelength.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::Dielectric;
+    ///
+    /// let material = Dielectric::diamond();
+    /// assert!((material.refractive_index(None) - 2.417).abs() < 1e-3);
+    /// assert!(material.refractive_index(Some(400.)) > material.refractive_index(Some(700.)));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn refractive_index(&self, wavelength: Option<f32>) -> f32 {
+        match (&self.dispersion, wavelength) {
+            (Some(dispersion), Some(wavelength)) => dispersion.refractive_index(wavelength),
+            _ => self.refractive_index,
+        }
+    }
+}
+
+impl Dielectric {
+    /// Returns the ratio of the refractive indices on the side of the ray and on the other side
+    /// of the surface.
+    #[inline]
+    fn refraction_ratio(&self, ray: &Ray, record: &HitRecord<'_>) -> f32 {
+        let refractive_index = self.refractive_index(ray.wavelength());
+        if record.front_face() {
+            1. / refractive_index
+        } else {
+            refractive_index
+        }
     }
 }
 
 impl Material for Dielectric {
-    fn scatter(&self, ray: &Ray, record: &HitRecord<'_>) -> Option<(Ray, Vec3)> {
+    fn scatter(&self, ray: &Ray, record: &HitRecord<'_>) -> Option<Scatter> {
         let mut rng = rng();
-        let refraction_ratio = if record.front_face() {
-            1. / self.refractive_index
-        } else {
-            self.refractive_index
-        };
+        let refraction_ratio = self.refraction_ratio(ray, record);
 
         let unit_direction = ray.direction().unit();
         let cos_theta = f32::min((-unit_direction).dot(record.normal()), 1.);
@@ -90,8 +271,75 @@ impl Material for Dielectric {
             refract(&unit_direction, record.normal(), refraction_ratio)
         };
 
-        let attenuation = Vec3::new(1., 1., 1.);
+        // A ray hitting a back face has travelled inside the medium since its origin
+        let attenuation = if record.front_face() {
+            Vec3::new(1., 1., 1.)
+        } else {
+            let distance = record.t() * ray.direction().length();
+            Vec3::new(
+                f32::exp(-self.absorption.x * distance),
+                f32::exp(-self.absorption.y * distance),
+                f32::exp(-self.absorption.z * distance),
+            )
+        };
         let scattered = Ray::new(*record.hit_point(), direction, ray.time());
-        Some((scattered, attenuation))
+        Some(Scatter::specular(scattered, attenuation))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec::Point3;
+
+    fn exit_attenuation(material: &Dielectric, distance: f32) -> Vec3 {
+        // Ray leaving the material perpendicularly, so it cannot be reflected internally
+        let ray = Ray::new(Point3::zero(), Vec3::new(0., 0., 1.), 0.);
+        let mut record = HitRecord::new(
+            distance,
+            Point3::new(0., 0., distance),
+            Vec3::new(0., 0., 1.),
+            (0., 0.),
+            material,
+        );
+        record.set_face_normal(&ray);
+
+        (0..100)
+            .filter_map(|_| material.scatter(&ray, &record))
+            .find(|scatter| scatter.ray.direction().z > 0.)
+            .unwrap()
+            .attenuation
+    }
+
+    #[test]
+    fn dielectric_absorption_follows_beer_lambert() {
+        let testee = Dielectric::new(1.5).absorption(Color3::new(0.5, 0.25, 1.), 2.);
+
+        let attenuation = exit_attenuation(&testee, 2.);
+        assert!((attenuation.x - 0.5).abs() < 1e-5);
+        assert!((attenuation.y - 0.25).abs() < 1e-5);
+        assert!((attenuation.z - 1.).abs() < 1e-5);
+
+        let attenuation = exit_attenuation(&testee, 4.);
+        assert!((attenuation.x - 0.25).abs() < 1e-5);
+    }
+
+    #[test]
+    fn dielectric_without_absorption_is_clear() {
+        let attenuation = exit_attenuation(&Dielectric::new(1.5), 10.);
+        assert_eq!(attenuation, Vec3::new(1., 1., 1.));
+    }
+
+    #[test]
+    fn dielectric_reflections_and_refractions_are_specular() {
+        let testee = Dielectric::new(1.5);
+        let ray = Ray::new(Point3::new(-1., 1., 0.), Vec3::new(1., -1., 0.), 0.);
+        let mut record =
+            HitRecord::new(1., Point3::zero(), Vec3::new(0., 1., 0.), (0., 0.), &testee);
+        record.set_face_normal(&ray);
+
+        assert!((0..100)
+            .filter_map(|_| testee.scatter(&ray, &record))
+            .all(|scatter| scatter.is_specular));
     }
 }
@@ -0,0 +1,212 @@
+use alloc::boxed::Box;
+use core::f32::consts::PI;
+use rand::Rng;
+
+use crate::hitable::HitRecord;
+use crate::materials::{Material, Scatter};
+use crate::ray::Ray;
+use crate::textures::Texture;
+use crate::utils::{orthonormal_basis, rng};
+use crate::vec::Vec3;
+
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
+/// A phase function for participating media that favors forward or backward scattering
+/// following the [Henyey-Greenstein](https://www.astro.umd.edu/~jph/HG_note.pdf) distribution.
+#[derive(Debug)]
+pub struct HenyeyGreenstein {
+    albedo: Box<dyn Texture>,
+    /// Asymmetry parameter of the first lobe.
+    g: f32,
+    /// Asymmetry parameter and weight of the second lobe.
+    second_lobe: Option<(f32, f32)>,
+}
+
+impl HenyeyGreenstein {
+    /// Constructs a new `HenyeyGreenstein` phase function with the given albedo and asymmetry
+    /// parameter.
+    ///
+    /// `g` is the mean cosine of the scattering angle: positive values scatter forward, negative
+    /// values scatter backward and 0 scatters uniformly like [`Isotropic`](super::Isotropic).
+    ///
+    /// # Panics
+    /// Panics if `g` is not in range ]-1, 1[.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use crab_rt::materials::{HenyeyGreenstein, Lambertian};
+    /// use crab_rt::objects::{ConstantMedium, Sphere};
+    /// use crab_rt::textures::Monochrome;
+    /// use crab_rt::vec::Vec3;
+    ///
+    /// // Clouds mostly scatter light forward
+    /// let cloud = ConstantMedium::new(
+    ///     Arc::new(Sphere::new(
+    ///         Vec3::zero(),
+    ///         1.,
+    ///         Arc::new(Lambertian::default()),
+    ///     )),
+    ///     2.,
+    ///     Arc::new(HenyeyGreenstein::new(
+    ///         Monochrome::from_rgb(1., 1., 1.),
+    ///         0.85,
+    ///     )),
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new<T: 'static + Texture>(texture: T, g: f32) -> Self {
+        assert!(g > -1. && g < 1., "g should be in range ]-1, 1[");
+
+        Self {
+            albedo: Box::new(texture),
+            g,
+            second_lobe: None,
+        }
+    }
+
+    /// Consumes the `HenyeyGreenstein` and returns self after adding a second lobe with the
+    /// given asymmetry parameter, blended with the first one by `weight`.
+    ///
+    /// A forward lobe blended with a weaker backward lobe matches real clouds and smoke better
+    /// than a single lobe.
+    ///
+    /// # Panics
+    /// Panics if `g` is not in range ]-1, 1[.
+    /// Panics if `weight` is not in range [0, 1].
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::HenyeyGreenstein;
+    /// use crab_rt::textures::Monochrome;
+    ///
+    /// let phase_function =
+    ///     HenyeyGreenstein::new(Monochrome::from_rgb(1., 1., 1.), 0.8).dual_lobe(-0.3, 0.2);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn dual_lobe(self, g: f32, weight: f32) -> Self {
+        assert!(g > -1. && g < 1., "g should be in range ]-1, 1[");
+        assert!(
+            (0. ..=1.).contains(&weight),
+            "weight should be in range [0, 1]"
+        );
+
+        Self {
+            second_lobe: Some((g, weight)),
+            ..self
+        }
+    }
+
+    /// Returns the probability density of scattering by an angle whose cosine is `cos_theta`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::HenyeyGreenstein;
+    /// use crab_rt::textures::Monochrome;
+    ///
+    /// let phase_function = HenyeyGreenstein::new(Monochrome::from_rgb(1., 1., 1.), 0.5);
+    /// assert!(phase_function.pdf(1.) > phase_function.pdf(-1.));
+    /// ```
+    #[must_use]
+    pub fn pdf(&self, cos_theta: f32) -> f32 {
+        match self.second_lobe {
+            None => lobe_pdf(self.g, cos_theta),
+            Some((g, weight)) => {
+                (1. - weight).mul_add(lobe_pdf(self.g, cos_theta), weight * lobe_pdf(g, cos_theta))
+            }
+        }
+    }
+
+    /// Samples the cosine of the scattering angle.
+    fn sample_cos_theta(&self) -> f32 {
+        let mut rng = rng();
+        let g = match self.second_lobe {
+            Some((g, weight)) if rng.gen::<f32>() < weight => g,
+            _ => self.g,
+        };
+
+        let xi = rng.gen::<f32>();
+        if g.abs() < 1e-3 {
+            return 1. - 2. * xi;
+        }
+
+        // Inverse of the cumulative distribution function
+        let sqr_term = (1. - g * g) / (1. + g - 2. * g * xi);
+        ((1. + g * g - sqr_term * sqr_term) / (2. * g)).clamp(-1., 1.)
+    }
+}
+
+impl Material for HenyeyGreenstein {
+    fn scatter(&self, ray: &Ray, record: &HitRecord<'_>) -> Option<Scatter> {
+        let forward = ray.direction().unit();
+        let (s, t) = orthonormal_basis(&forward);
+
+        let cos_theta = self.sample_cos_theta();
+        let sin_theta = f32::sqrt(f32::max(1. - cos_theta * cos_theta, 0.));
+        let phi = 2. * PI * rng().gen::<f32>();
+
+        let direction = sin_theta * phi.cos() * s + sin_theta * phi.sin() * t + cos_theta * forward;
+
+        Some(Scatter::diffuse(
+            Ray::new(*record.hit_point(), direction, ray.time()),
+            self.albedo.value_from_hit(record),
+        ))
+    }
+
+    #[inline]
+    fn scattering_pdf(&self, ray: &Ray, _record: &HitRecord<'_>, scattered: &Ray) -> f32 {
+        self.pdf(ray.direction().unit().dot(&scattered.direction().unit()))
+    }
+
+    #[inline]
+    fn evaluate(&self, ray: &Ray, record: &HitRecord<'_>, scattered: &Ray) -> Vec3 {
+        self.scattering_pdf(ray, record, scattered) * self.albedo.value_from_hit(record)
+    }
+}
+
+/// Returns the Henyey-Greenstein probability density of a single lobe.
+#[inline]
+fn lobe_pdf(g: f32, cos_theta: f32) -> f32 {
+    let denominator = (2. * g).mul_add(-cos_theta, g.mul_add(g, 1.));
+    (1. - g * g) / (4. * PI * denominator * denominator.sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::textures::Monochrome;
+
+    #[test]
+    fn henyey_greenstein_pdf_is_normalized() {
+        let testee =
+            HenyeyGreenstein::new(Monochrome::from_rgb(1., 1., 1.), 0.7).dual_lobe(-0.4, 0.3);
+
+        // Integrates the pdf over the sphere, which is 2 * PI * integral over cos(theta)
+        let n = 100_000;
+        let integral = (0..n)
+            .map(|i| {
+                let cos_theta = -1. + 2. * (i as f32 + 0.5) / n as f32;
+                testee.pdf(cos_theta) * 2. / n as f32
+            })
+            .sum::<f32>()
+            * 2.
+            * PI;
+
+        assert!((integral - 1.).abs() < 1e-2);
+    }
+
+    #[test]
+    fn henyey_greenstein_mean_cosine_is_g() {
+        let g = 0.6;
+        let testee = HenyeyGreenstein::new(Monochrome::from_rgb(1., 1., 1.), g);
+
+        let n = 100_000;
+        let mean = (0..n).map(|_| testee.sample_cos_theta()).sum::<f32>() / n as f32;
+
+        assert!((mean - g).abs() < 1e-2);
+    }
+}
@@ -1,7 +1,8 @@
 use alloc::boxed::Box;
+use core::f32::consts::PI;
 
 use crate::hitable::HitRecord;
-use crate::materials::Material;
+use crate::materials::{Material, Scatter};
 use crate::ray::Ray;
 use crate::textures::Texture;
 use crate::utils::random_in_unit_sphere;
@@ -24,10 +25,20 @@ impl Isotropic {
 
 impl Material for Isotropic {
     #[inline]
-    fn scatter(&self, ray: &Ray, record: &HitRecord<'_>) -> Option<(Ray, Vec3)> {
-        Some((
+    fn scatter(&self, ray: &Ray, record: &HitRecord<'_>) -> Option<Scatter> {
+        Some(Scatter::diffuse(
             Ray::new(*record.hit_point(), random_in_unit_sphere(), ray.time()),
             self.albedo.value_from_hit(record),
         ))
     }
+
+    #[inline]
+    fn scattering_pdf(&self, _ray: &Ray, _record: &HitRecord<'_>, _scattered: &Ray) -> f32 {
+        1. / (4. * PI)
+    }
+
+    #[inline]
+    fn evaluate(&self, ray: &Ray, record: &HitRecord<'_>, scattered: &Ray) -> Vec3 {
+        self.scattering_pdf(ray, record, scattered) * self.albedo.value_from_hit(record)
+    }
 }
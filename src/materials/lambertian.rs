@@ -1,6 +1,7 @@
 use alloc::boxed::Box;
+use core::f32::consts::PI;
 
-use super::material::Material;
+use super::material::{Material, Scatter};
 use crate::hitable::HitRecord;
 use crate::ray::Ray;
 use crate::textures::{Monochrome, Texture};
@@ -50,7 +51,7 @@ impl Lambertian {
 }
 
 impl Material for Lambertian {
-    fn scatter(&self, ray: &Ray, record: &HitRecord<'_>) -> Option<(Ray, Vec3)> {
+    fn scatter(&self, ray: &Ray, record: &HitRecord<'_>) -> Option<Scatter> {
         let mut scatter_direction = record.normal() + random_unit_vector();
 
         // Catch degenerate scatter direction
@@ -58,11 +59,21 @@ impl Material for Lambertian {
             scatter_direction = *record.normal();
         }
 
-        Some((
+        Some(Scatter::diffuse(
             Ray::new(*record.hit_point(), scatter_direction, ray.time()),
             self.albedo.value_from_hit(record),
         ))
     }
+
+    fn scattering_pdf(&self, _ray: &Ray, record: &HitRecord<'_>, scattered: &Ray) -> f32 {
+        // The scattered directions follow a cosine distribution
+        let cosine = record.normal().dot(&scattered.direction().unit());
+        f32::max(cosine, 0.) / PI
+    }
+
+    fn evaluate(&self, ray: &Ray, record: &HitRecord<'_>, scattered: &Ray) -> Vec3 {
+        self.scattering_pdf(ray, record, scattered) * self.albedo.value_from_hit(record)
+    }
 }
 
 impl Default for Lambertian {
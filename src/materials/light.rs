@@ -1,32 +1,211 @@
 use alloc::boxed::Box;
 
-use super::Material;
+use super::{Material, Scatter};
 use crate::hitable::HitRecord;
+use crate::lights::IesProfile;
 use crate::ray::Ray;
-use crate::textures::Texture;
-use crate::vec::{Point3, Vec3};
+use crate::spectrum::blackbody_rgb;
+use crate::textures::{Monochrome, Texture};
+use crate::vec::{Color3, Vec3};
 
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
+/// An emissive material, turning the surface it covers into a light.
 #[derive(Debug)]
 pub struct Light {
     emit: Box<dyn Texture>,
+    /// Multiplier of the emitted color.
+    intensity: f32,
+    /// Color multiplying the emitted color, given by a black body temperature.
+    tint: Color3,
+    /// Whether the surface emits from its back face too.
+    two_sided: bool,
+    /// Measured distribution scaling the emission by direction, around the normal of the
+    /// surface.
+    profile: Option<IesProfile>,
 }
 
 impl Light {
+    /// Constructs a new `Light` emitting the colors of the texture from both sides of the
+    /// surface.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::Light;
+    /// use crab_rt::textures::Monochrome;
+    ///
+    /// let light = Light::new(Monochrome::from_rgb(4., 4., 4.));
+    /// ```
     #[inline]
     #[must_use]
     pub fn new<T: 'static + Texture>(emit: T) -> Self {
         Self {
             emit: Box::new(emit),
+            intensity: 1.,
+            tint: Color3::new(1., 1., 1.),
+            two_sided: true,
+            profile: None,
+        }
+    }
+
+    /// Constructs a new `Light` emitting the color of a black body at the given temperature in
+    /// kelvins, with a luminance of 1.
+    ///
+    /// # Panics
+    /// Panics if `temperature` is not positive.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::Light;
+    ///
+    /// // A warm incandescent bulb
+    /// let light = Light::blackbody(2700.).intensity(15.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn blackbody(temperature: f32) -> Self {
+        Self::new(Monochrome::from_rgb(1., 1., 1.)).temperature(temperature)
+    }
+
+    /// Consumes the `Light` and returns self with the emitted color multiplied by `intensity`,
+    /// which sets the power of the light independently of its color.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::Light;
+    /// use crab_rt::textures::Monochrome;
+    ///
+    /// let light = Light::new(Monochrome::from_rgb(1., 0.9, 0.8)).intensity(10.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn intensity(self, intensity: f32) -> Self {
+        Self { intensity, ..self }
+    }
+
+    /// Consumes the `Light` and returns self with the emitted color tinted by the color of a
+    /// black body at the given temperature in kelvins.
+    ///
+    /// # Panics
+    /// Panics if `temperature` is not positive.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::Light;
+    /// use crab_rt::textures::Monochrome;
+    ///
+    /// let light = Light::new(Monochrome::from_rgb(1., 1., 1.)).temperature(5000.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn temperature(self, temperature: f32) -> Self {
+        Self {
+            tint: blackbody_rgb(temperature),
+            ..self
+        }
+    }
+
+    /// Consumes the `Light` and returns self emitting only from the front face of the surface,
+    /// the side its outward normal points to.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::Light;
+    /// use crab_rt::textures::Monochrome;
+    ///
+    /// let ceiling_panel = Light::new(Monochrome::from_rgb(4., 4., 4.)).one_sided();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn one_sided(self) -> Self {
+        Self {
+            two_sided: false,
+            ..self
         }
     }
+
+    /// Consumes the `Light` and returns self with the emission scaled by the relative intensity
+    /// of a measured distribution. The nadir of the distribution follows the normal of the
+    /// surface on the emitting side and its horizontal angle 0 the first partial derivative of
+    /// the surface.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::lights::IesProfile;
+    /// use crab_rt::materials::Light;
+    /// use crab_rt::textures::Monochrome;
+    ///
+    /// let profile = IesProfile::new(vec![0., 45., 90.], vec![0.], vec![1000., 500., 0.]);
+    /// let light = Light::new(Monochrome::from_rgb(4., 4., 4.)).profile(profile);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn profile(self, profile: IesProfile) -> Self {
+        Self {
+            profile: Some(profile),
+            ..self
+        }
+    }
+
+    /// Returns the relative intensity of the profile towards `direction`, leaving a hit of
+    /// normal `normal`.
+    fn distribution(profile: &IesProfile, record: &HitRecord<'_>, direction: &Vec3) -> f32 {
+        if profile.max_intensity() <= 0. {
+            return 0.;
+        }
+
+        let normal = record.normal();
+        let (zero, ninety) = record.shading_frame();
+        let vertical = direction.dot(normal).clamp(-1., 1.).acos().to_degrees();
+        let horizontal = f32::atan2(direction.dot(&ninety), direction.dot(&zero)).to_degrees();
+
+        profile.intensity(vertical, horizontal) / profile.max_intensity()
+    }
 }
 
 impl Material for Light {
-    fn scatter(&self, _ray: &Ray, _record: &HitRecord<'_>) -> Option<(Ray, Vec3)> {
+    fn scatter(&self, _ray: &Ray, _record: &HitRecord<'_>) -> Option<Scatter> {
         None
     }
 
-    fn emitted(&self, texture_coordinates: (f32, f32), p: &Point3) -> Vec3 {
-        self.emit.value(texture_coordinates, p)
+    fn emitted(&self, ray: &Ray, record: &HitRecord<'_>) -> Vec3 {
+        if !self.two_sided && !record.front_face() {
+            return Vec3::zero();
+        }
+
+        let color = self.intensity * self.tint * self.emit.value_from_hit(record);
+        self.profile.as_ref().map_or(color, |profile| {
+            Self::distribution(profile, record, &-ray.direction().unit()) * color
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec::Point3;
+
+    fn emitted(light: &Light, direction: Vec3) -> Vec3 {
+        let ray = Ray::new(-direction, direction, 0.);
+        let mut record = HitRecord::new(1., Point3::zero(), Vec3::new(0., 1., 0.), (0., 0.), light);
+        record.set_face_normal(&ray);
+        light.emitted(&ray, &record)
+    }
+
+    #[test]
+    fn one_sided_light_emits_from_front_face() {
+        let testee = Light::new(Monochrome::from_rgb(1., 1., 1.)).intensity(2.);
+        assert_eq!(
+            emitted(&testee, Vec3::new(0., 1., 0.)),
+            Vec3::new(2., 2., 2.)
+        );
+
+        let testee = testee.one_sided();
+        assert_eq!(
+            emitted(&testee, Vec3::new(0., -1., 0.)),
+            Vec3::new(2., 2., 2.)
+        );
+        assert_eq!(emitted(&testee, Vec3::new(0., 1., 0.)), Vec3::zero());
     }
 }
@@ -2,15 +2,79 @@ use core::fmt::Debug;
 
 use crate::hitable::HitRecord;
 use crate::ray::Ray;
-use crate::vec::{Point3, Vec3};
+use crate::vec::Vec3;
+
+/// A ray scattered by a material.
+#[derive(Debug)]
+pub struct Scatter {
+    /// The scattered ray.
+    pub ray: Ray,
+    /// The fraction of the light coming along `ray` which is scattered.
+    pub attenuation: Vec3,
+    /// Whether `ray` leaves the hit in the single direction of a specular bounce, like a mirror
+    /// reflection or a refraction, whose ray differentials can be followed.
+    pub is_specular: bool,
+}
+
+impl Scatter {
+    /// Creates a ray scattered in a direction chosen among many.
+    #[must_use]
+    pub const fn diffuse(ray: Ray, attenuation: Vec3) -> Self {
+        Self {
+            ray,
+            attenuation,
+            is_specular: false,
+        }
+    }
+
+    /// Creates a ray scattered in the single direction of a specular bounce.
+    #[must_use]
+    pub const fn specular(ray: Ray, attenuation: Vec3) -> Self {
+        Self {
+            ray,
+            attenuation,
+            is_specular: true,
+        }
+    }
+}
 
 pub trait Material: Debug + Send + Sync {
     #[must_use]
-    fn scatter(&self, ray: &Ray, record: &HitRecord<'_>) -> Option<(Ray, Vec3)>;
+    fn scatter(&self, ray: &Ray, record: &HitRecord<'_>) -> Option<Scatter>;
+
+    /// Returns the probability density of `scatter` choosing the direction of `scattered`.
+    ///
+    /// Materials that scatter in a single direction, like mirrors, return 0 as a given direction
+    /// has no chance of being chosen.
+    #[allow(unused_variables)]
+    #[must_use]
+    fn scattering_pdf(&self, ray: &Ray, record: &HitRecord<'_>, scattered: &Ray) -> f32 {
+        0.
+    }
+
+    /// Returns the fraction of the light coming from the direction of `scattered` which is
+    /// scattered towards the origin of `ray`: the BRDF times the cosine of the angle between
+    /// `scattered` and the normal, or the phase function for participating media.
+    ///
+    /// Materials that scatter in a single direction, like mirrors, return 0.
+    #[allow(unused_variables)]
+    #[must_use]
+    fn evaluate(&self, ray: &Ray, record: &HitRecord<'_>, scattered: &Ray) -> Vec3 {
+        Vec3::new(0., 0., 0.)
+    }
+
+    /// Returns the probability of the hit stopping light, rays going straight through it
+    /// otherwise.
+    #[allow(unused_variables)]
+    #[must_use]
+    fn opacity(&self, record: &HitRecord<'_>) -> f32 {
+        1.
+    }
 
+    /// Returns the light emitted from the hit towards the origin of `ray`.
     #[allow(unused_variables)]
     #[must_use]
-    fn emitted(&self, texture_coordinates: (f32, f32), p: &Point3) -> Vec3 {
+    fn emitted(&self, ray: &Ray, record: &HitRecord<'_>) -> Vec3 {
         Vec3::new(0., 0., 0.)
     }
 }
@@ -1,8 +1,8 @@
-use super::Material;
+use super::{Material, Scatter};
 use crate::hitable::HitRecord;
 use crate::ray::Ray;
 use crate::utils::{random_in_unit_sphere, reflect};
-use crate::vec::{Color3, Vec3};
+use crate::vec::Color3;
 
 /// A material with specular reflections.
 #[derive(Debug, Default, Clone)]
@@ -34,7 +34,7 @@ impl Metal {
 }
 
 impl Material for Metal {
-    fn scatter(&self, ray: &Ray, record: &HitRecord<'_>) -> Option<(Ray, Vec3)> {
+    fn scatter(&self, ray: &Ray, record: &HitRecord<'_>) -> Option<Scatter> {
         let reflected = reflect(&ray.direction().unit(), record.normal());
         let scattered = Ray::new(
             *record.hit_point(),
@@ -44,7 +44,11 @@ impl Material for Metal {
         let attenuation = self.albedo;
 
         if scattered.direction().dot(record.normal()) > 0. {
-            Some((scattered, attenuation))
+            Some(Scatter {
+                ray: scattered,
+                attenuation,
+                is_specular: self.fuzziness <= 0.,
+            })
         } else {
             None
         }
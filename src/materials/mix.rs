@@ -0,0 +1,220 @@
+use alloc::boxed::Box;
+use rand::Rng;
+
+use super::{Material, Scatter};
+use crate::hitable::HitRecord;
+use crate::ray::Ray;
+use crate::textures::{Monochrome, Texture};
+use crate::utils::rng;
+use crate::vec::{Point3, Vec3};
+
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
+/// A material blending two materials, by a constant factor or by a texture mask.
+#[derive(Debug)]
+pub struct Mix {
+    first: Box<dyn Material>,
+    second: Box<dyn Material>,
+    /// Weight of the second material, the mean of the color channels is used.
+    mask: Box<dyn Texture>,
+}
+
+impl Mix {
+    /// Constructs a new `Mix` material where `factor` is the weight of the second material.
+    ///
+    /// # Panics
+    /// Panics if `factor` is not in range [0, 1].
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::{Lambertian, Metal, Mix};
+    /// use crab_rt::vec::Color3;
+    ///
+    /// // Creates a slightly glossy red material
+    /// let material = Mix::new(
+    ///     Lambertian::from_rgb(0.8, 0.1, 0.1),
+    ///     Metal::new(Color3::new(1., 1., 1.), 0.2),
+    ///     0.1,
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new<A: 'static + Material, B: 'static + Material>(
+        first: A,
+        second: B,
+        factor: f32,
+    ) -> Self {
+        assert!(
+            (0. ..=1.).contains(&factor),
+            "factor should be in range [0, 1]"
+        );
+
+        Self::with_mask(first, second, Monochrome::from_rgb(factor, factor, factor))
+    }
+
+    /// Constructs a new `Mix` material where the texture gives the weight of the second material,
+    /// a black texel selecting the first material and a white texel the second one.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::{Lambertian, Metal, Mix};
+    /// use crab_rt::textures::Checker;
+    /// use crab_rt::vec::Color3;
+    ///
+    /// // Alternates between rusty and polished tiles
+    /// let material = Mix::with_mask(
+    ///     Lambertian::from_rgb(0.5, 0.2, 0.1),
+    ///     Metal::new(Color3::new(0.8, 0.8, 0.8), 0.),
+    ///     Checker::from_colors(Color3::new(0., 0., 0.), Color3::new(1., 1., 1.)),
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_mask<A: 'static + Material, B: 'static + Material, T: 'static + Texture>(
+        first: A,
+        second: B,
+        mask: T,
+    ) -> Self {
+        Self {
+            first: Box::new(first),
+            second: Box::new(second),
+            mask: Box::new(mask),
+        }
+    }
+
+    /// Returns the weight of the second material at the given point.
+    #[inline]
+    fn factor(&self, texture_coordinates: (f32, f32), p: &Point3) -> f32 {
+        let mask = self.mask.value(texture_coordinates, p);
+        ((mask.x + mask.y + mask.z) / 3.).clamp(0., 1.)
+    }
+}
+
+impl Material for Mix {
+    fn scatter(&self, ray: &Ray, record: &HitRecord<'_>) -> Option<Scatter> {
+        // Choosing one of the materials with the probability of its weight blends them on average
+        if rng().gen::<f32>() < self.factor(record.texture_coordinates(), record.hit_point()) {
+            self.second.scatter(ray, record)
+        } else {
+            self.first.scatter(ray, record)
+        }
+    }
+
+    fn scattering_pdf(&self, ray: &Ray, record: &HitRecord<'_>, scattered: &Ray) -> f32 {
+        let factor = self.factor(record.texture_coordinates(), record.hit_point());
+        (1. - factor).mul_add(
+            self.first.scattering_pdf(ray, record, scattered),
+            factor * self.second.scattering_pdf(ray, record, scattered),
+        )
+    }
+
+    fn evaluate(&self, ray: &Ray, record: &HitRecord<'_>, scattered: &Ray) -> Vec3 {
+        let factor = self.factor(record.texture_coordinates(), record.hit_point());
+        (1. - factor) * self.first.evaluate(ray, record, scattered)
+            + factor * self.second.evaluate(ray, record, scattered)
+    }
+
+    fn opacity(&self, record: &HitRecord<'_>) -> f32 {
+        let factor = self.factor(record.texture_coordinates(), record.hit_point());
+        (1. - factor).mul_add(
+            self.first.opacity(record),
+            factor * self.second.opacity(record),
+        )
+    }
+
+    fn emitted(&self, ray: &Ray, record: &HitRecord<'_>) -> Vec3 {
+        let factor = self.factor(record.texture_coordinates(), record.hit_point());
+        (1. - factor) * self.first.emitted(ray, record) + factor * self.second.emitted(ray, record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::materials::{Cutout, Lambertian, Light, Metal};
+    use crate::textures::{Checker, OpacityMask};
+    use crate::vec::Color3;
+
+    /// Returns a ray going down to the origin and a shadow ray going up from it.
+    fn rays() -> (Ray, Ray) {
+        (
+            Ray::new(Point3::new(-1., 1., 0.), Vec3::new(1., -1., 0.), 0.),
+            Ray::new(Point3::zero(), Vec3::new(0.5, 1., 0.), 0.),
+        )
+    }
+
+    /// Returns the hit of `ray` at the origin of a surface facing up.
+    fn record<'material>(
+        material: &'material dyn Material,
+        ray: &Ray,
+        texture_coordinates: (f32, f32),
+    ) -> HitRecord<'material> {
+        let mut record = HitRecord::new(
+            1.,
+            Point3::zero(),
+            Vec3::new(0., 1., 0.),
+            texture_coordinates,
+            material,
+        );
+        record.set_face_normal(ray);
+        record
+    }
+
+    #[test]
+    fn mix_weights_evaluate_and_scattering_pdf() {
+        // Metal only scatters in the mirror direction
+        let diffuse = Lambertian::from_rgb(0.8, 0.8, 0.8);
+        let testee = Mix::new(
+            Lambertian::from_rgb(0.8, 0.8, 0.8),
+            Metal::new(Color3::new(1., 1., 1.), 0.),
+            0.25,
+        );
+        let (ray, scattered) = rays();
+        let hit = record(&testee, &ray, (0., 0.));
+        let diffuse_hit = record(&diffuse, &ray, (0., 0.));
+
+        let expected = 0.75 * diffuse.evaluate(&ray, &diffuse_hit, &scattered);
+        assert!((testee.evaluate(&ray, &hit, &scattered) - expected).length() < 1e-6);
+        let expected = 0.75 * diffuse.scattering_pdf(&ray, &diffuse_hit, &scattered);
+        assert!((testee.scattering_pdf(&ray, &hit, &scattered) - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn mix_weights_opacity_and_emitted() {
+        let transparent = Cutout::new(
+            Lambertian::from_rgb(0.8, 0.8, 0.8),
+            OpacityMask::luminance(Monochrome::from_rgb(0., 0., 0.)),
+        );
+        let testee = Mix::new(Lambertian::from_rgb(0.8, 0.8, 0.8), transparent, 0.25);
+        let (ray, _) = rays();
+        assert!((testee.opacity(&record(&testee, &ray, (0., 0.))) - 0.75).abs() < 1e-6);
+
+        let testee = Mix::new(
+            Lambertian::from_rgb(0.8, 0.8, 0.8),
+            Light::new(Monochrome::from_rgb(4., 4., 4.)),
+            0.25,
+        );
+        let emitted = testee.emitted(&ray, &record(&testee, &ray, (0., 0.)));
+        assert!((emitted - Vec3::new(1., 1., 1.)).length() < 1e-6);
+    }
+
+    #[test]
+    fn mix_mask_selects_material() {
+        let testee = Mix::with_mask(
+            Lambertian::from_rgb(0.8, 0.8, 0.8),
+            Light::new(Monochrome::from_rgb(1., 1., 1.)),
+            Checker::from_colors(Color3::new(0., 0., 0.), Color3::new(1., 1., 1.)).texture_space(),
+        );
+        let (ray, scattered) = rays();
+
+        // The first material lies on the black squares of the mask
+        let black = record(&testee, &ray, (0.25, 0.25));
+        assert_eq!(testee.emitted(&ray, &black), Vec3::zero());
+        assert!(testee.evaluate(&ray, &black, &scattered).x > 0.);
+
+        let white = record(&testee, &ray, (1.25, 0.25));
+        assert_eq!(testee.emitted(&ray, &white), Vec3::new(1., 1., 1.));
+        assert_eq!(testee.evaluate(&ray, &white, &scattered), Vec3::zero());
+    }
+}
@@ -1,13 +1,27 @@
+pub mod bump_map;
+pub mod coated;
+pub mod conductor;
+pub mod cutout;
 pub mod dielectric;
+pub mod henyey_greenstein;
 pub mod isotropic;
 pub mod lambertian;
 pub mod light;
 pub mod material;
 pub mod metal;
+pub mod mix;
+pub mod normal_map;
 
+pub use bump_map::BumpMap;
+pub use coated::Coated;
+pub use conductor::Conductor;
+pub use cutout::Cutout;
 pub use dielectric::Dielectric;
+pub use henyey_greenstein::HenyeyGreenstein;
 pub use isotropic::Isotropic;
 pub use lambertian::Lambertian;
 pub use light::Light;
-pub use material::Material;
+pub use material::{Material, Scatter};
 pub use metal::Metal;
+pub use mix::Mix;
+pub use normal_map::NormalMap;
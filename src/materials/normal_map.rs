@@ -0,0 +1,149 @@
+use alloc::boxed::Box;
+
+use super::{Material, Scatter};
+use crate::hitable::HitRecord;
+use crate::ray::Ray;
+use crate::textures::Texture;
+use crate::vec::Vec3;
+
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
+/// A material wrapper perturbing the shading normal of a material with a tangent space normal
+/// map, whose red, green and blue channels map the tangent, bitangent and normal coordinates
+/// from [0, 1] to [-1, 1].
+///
+/// Image normal maps store directions rather than colors, so they should use the
+/// [`ColorSpace::Linear`](crate::textures::ColorSpace::Linear) color space.
+#[derive(Debug)]
+pub struct NormalMap {
+    material: Box<dyn Material>,
+    normals: Box<dyn Texture>,
+    /// Scale of the tangent components of the normals.
+    strength: f32,
+}
+
+impl NormalMap {
+    /// Constructs a new `NormalMap` applying the normals of the texture to the material.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::{Lambertian, NormalMap};
+    /// use crab_rt::textures::Monochrome;
+    ///
+    /// // A flat normal map leaves the material unchanged
+    /// let material = NormalMap::new(
+    ///     Lambertian::from_rgb(0.5, 0.5, 0.5),
+    ///     Monochrome::from_rgb(0.5, 0.5, 1.),
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new<M: 'static + Material, T: 'static + Texture>(material: M, normals: T) -> Self {
+        Self {
+            material: Box::new(material),
+            normals: Box::new(normals),
+            strength: 1.,
+        }
+    }
+
+    /// Consumes the `NormalMap` and returns self with the slopes of the normals scaled by
+    /// `strength`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::materials::{Lambertian, NormalMap};
+    /// use crab_rt::textures::Monochrome;
+    ///
+    /// let material = NormalMap::new(
+    ///     Lambertian::from_rgb(0.5, 0.5, 0.5),
+    ///     Monochrome::from_rgb(0.6, 0.5, 0.9),
+    /// )
+    /// .strength(0.5);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn strength(self, strength: f32) -> Self {
+        Self { strength, ..self }
+    }
+
+    /// Returns a copy of the record with the shading normal given by the normal map.
+    fn perturb<'material>(&self, record: &HitRecord<'material>) -> HitRecord<'material> {
+        let color = self.normals.value_from_hit(record);
+        let (tangent, bitangent) = record.shading_frame();
+
+        let normal = (self.strength * color.x.mul_add(2., -1.) * tangent
+            + self.strength * color.y.mul_add(2., -1.) * bitangent
+            + color.z.mul_add(2., -1.) * *record.normal())
+        .unit();
+
+        let mut record = record.clone();
+        // Keeps the normal on the side of the surface the ray comes from
+        if normal.dot(record.normal()) > 0. {
+            record.set_normal(normal);
+        }
+        record
+    }
+}
+
+impl Material for NormalMap {
+    fn scatter(&self, ray: &Ray, record: &HitRecord<'_>) -> Option<Scatter> {
+        self.material.scatter(ray, &self.perturb(record))
+    }
+
+    fn scattering_pdf(&self, ray: &Ray, record: &HitRecord<'_>, scattered: &Ray) -> f32 {
+        self.material
+            .scattering_pdf(ray, &self.perturb(record), scattered)
+    }
+
+    fn evaluate(&self, ray: &Ray, record: &HitRecord<'_>, scattered: &Ray) -> Vec3 {
+        self.material
+            .evaluate(ray, &self.perturb(record), scattered)
+    }
+
+    fn emitted(&self, ray: &Ray, record: &HitRecord<'_>) -> Vec3 {
+        self.material.emitted(ray, record)
+    }
+
+    fn opacity(&self, record: &HitRecord<'_>) -> f32 {
+        self.material.opacity(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::materials::Lambertian;
+    use crate::textures::Monochrome;
+    use crate::vec::Point3;
+
+    fn record(material: &dyn Material) -> HitRecord<'_> {
+        let mut record = HitRecord::new(
+            1.,
+            Point3::zero(),
+            Vec3::new(0., 1., 0.),
+            (0.5, 0.5),
+            material,
+        );
+        record.set_partial_derivatives(Vec3::new(1., 0., 0.), Vec3::new(0., 0., -1.));
+        record
+    }
+
+    #[test]
+    fn normal_map_tilts_normal_along_dpdu() {
+        let testee = NormalMap::new(Lambertian::default(), Monochrome::from_rgb(1., 0.5, 1.));
+        let perturbed = testee.perturb(&record(&testee));
+
+        // Equal tangent and normal components lean the normal halfway towards dpdu
+        let expected = Vec3::new(1., 1., 0.).unit();
+        assert!((*perturbed.normal() - expected).length() < 1e-5);
+    }
+
+    #[test]
+    fn flat_normal_map_keeps_normal() {
+        let testee = NormalMap::new(Lambertian::default(), Monochrome::from_rgb(0.5, 0.5, 1.));
+        let perturbed = testee.perturb(&record(&testee));
+
+        assert!((*perturbed.normal() - Vec3::new(0., 1., 0.)).length() < 1e-5);
+    }
+}
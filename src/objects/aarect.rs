@@ -50,6 +50,10 @@ impl<M: Material> Hitable for XyRect<M> {
             ),
             self.material.as_ref(),
         );
+        record.set_partial_derivatives(
+            Vec3::new(self.x.1 - self.x.0, 0., 0.),
+            Vec3::new(0., self.y.1 - self.y.0, 0.),
+        );
         record.set_face_normal(ray);
 
         Some(record)
@@ -104,6 +108,10 @@ impl<M: Material> Hitable for XzRect<M> {
             ),
             self.material.as_ref(),
         );
+        record.set_partial_derivatives(
+            Vec3::new(self.x.1 - self.x.0, 0., 0.),
+            Vec3::new(0., 0., self.z.1 - self.z.0),
+        );
         record.set_face_normal(ray);
 
         Some(record)
@@ -156,6 +164,10 @@ impl<M: Material> Hitable for YzRect<M> {
             ),
             self.material.as_ref(),
         );
+        record.set_partial_derivatives(
+            Vec3::new(0., self.y.1 - self.y.0, 0.),
+            Vec3::new(0., 0., self.z.1 - self.z.0),
+        );
         record.set_face_normal(ray);
 
         Some(record)
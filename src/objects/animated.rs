@@ -0,0 +1,191 @@
+use alloc::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::animation::Track;
+use crate::hitable::{HitRecord, Hitable};
+use crate::ray::Ray;
+use crate::vec::{Point3, Vec3};
+
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
+/// A hitable moved by a keyframed translation and rotation around the y axis, evaluated at the
+/// time of the rays.
+///
+/// The rotation is applied before the translation.
+#[derive(Debug)]
+pub struct Animated {
+    hitable: Arc<dyn Hitable>,
+    translation: Track<Vec3>,
+    /// Rotation around the y axis, in degrees.
+    rotation: Track<f32>,
+}
+
+impl Animated {
+    /// Constructs a new `Animated` hitable moved by the given translation.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use crab_rt::animation::{Interpolation, Track};
+    /// use crab_rt::materials::Lambertian;
+    /// use crab_rt::objects::{Animated, Sphere};
+    /// use crab_rt::vec::{Point3, Vec3};
+    ///
+    /// // A ball bouncing once
+    /// let ball = Sphere::new(Point3::zero(), 0.5, Arc::new(Lambertian::default()));
+    /// let bouncing = Animated::new(
+    ///     Arc::new(ball),
+    ///     Track::new(0., Vec3::new(0., 2., 0.))
+    ///         .key(0.5, Vec3::new(0.5, 0.5, 0.))
+    ///         .key(1., Vec3::new(1., 2., 0.)),
+    /// );
+    /// ```
+    #[must_use]
+    pub fn new<H: Hitable + 'static>(hitable: Arc<H>, translation: Track<Vec3>) -> Self {
+        Self {
+            hitable,
+            translation,
+            rotation: Track::new(0., 0.),
+        }
+    }
+
+    /// Consumes the `Animated` and returns self with the given rotation around the y axis, in
+    /// degrees.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use crab_rt::animation::Track;
+    /// use crab_rt::materials::Lambertian;
+    /// use crab_rt::objects::{AaBox, Animated};
+    /// use crab_rt::vec::{Point3, Vec3};
+    ///
+    /// // A box making a full turn in 4 seconds
+    /// let cube = AaBox::new(
+    ///     Point3::new(-1., -1., -1.),
+    ///     Point3::new(1., 1., 1.),
+    ///     Arc::new(Lambertian::default()),
+    /// );
+    /// let turntable = Animated::new(Arc::new(cube), Track::new(0., Vec3::zero()))
+    ///     .rotation_y(Track::new(0., 0.).key(4., 360.));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn rotation_y(self, rotation: Track<f32>) -> Self {
+        Self { rotation, ..self }
+    }
+}
+
+/// Rotates `v` around the y axis by the angle with the given sine and cosine.
+#[inline]
+fn rotate_y(v: &Vec3, sin_theta: f32, cos_theta: f32) -> Vec3 {
+    Vec3::new(
+        cos_theta.mul_add(v.x, sin_theta * v.z),
+        v.y,
+        (-sin_theta).mul_add(v.x, cos_theta * v.z),
+    )
+}
+
+impl Hitable for Animated {
+    #[allow(clippy::similar_names)]
+    fn hit(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        let offset = self.translation.value(ray.time());
+        let (sin_theta, cos_theta) = self.rotation.value(ray.time()).to_radians().sin_cos();
+
+        // Moves the ray to the space of the hitable, where the hit has the same distance
+        let local_ray = Ray::new(
+            rotate_y(&(ray.origin() - offset), -sin_theta, cos_theta),
+            rotate_y(ray.direction(), -sin_theta, cos_theta),
+            ray.time(),
+        );
+        let mut record = self.hitable.hit(&local_ray, t_min, t_max)?;
+
+        let to_world = |v: &Vec3| rotate_y(v, sin_theta, cos_theta);
+        let (dpdu, dpdv) = (to_world(record.dpdu()), to_world(record.dpdv()));
+        record.set_hit_point(to_world(record.hit_point()) + offset);
+        // The normal already faces the ray, which turned with it
+        record.set_normal(to_world(record.normal()));
+        record.set_partial_derivatives(dpdu, dpdv);
+
+        Some(record)
+    }
+
+    fn bounding_box(&self, time_interval: (f32, f32)) -> Option<Aabb> {
+        let bbox = self.hitable.bounding_box(time_interval)?;
+
+        // A turning hitable stays in the cylinder around the axis containing its box
+        let angles = self.rotation.hull(time_interval);
+        let (min, max) = if angles
+            .iter()
+            .all(|angle| (angle - angles[0]).abs() < f32::EPSILON)
+        {
+            let (sin_theta, cos_theta) = angles[0].to_radians().sin_cos();
+            let corners = [bbox.min(), bbox.max()];
+            (0..8)
+                .map(|i| {
+                    let corner =
+                        Point3::new(corners[i & 1].x, corners[(i >> 1) & 1].y, corners[i >> 2].z);
+                    rotate_y(&corner, sin_theta, cos_theta)
+                })
+                .fold(
+                    (
+                        Point3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY),
+                        Point3::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY),
+                    ),
+                    |(min, max), corner| (min.min(&corner), max.max(&corner)),
+                )
+        } else {
+            let radius = [bbox.min().x, bbox.max().x]
+                .iter()
+                .flat_map(|x| [bbox.min().z, bbox.max().z].map(|z| x.hypot(z)))
+                .fold(0., f32::max);
+            (
+                Point3::new(-radius, bbox.min().y, -radius),
+                Point3::new(radius, bbox.max().y, radius),
+            )
+        };
+
+        let offsets = self.translation.hull(time_interval);
+        let (min_offset, max_offset) = offsets.iter().fold(
+            (
+                Vec3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY),
+                Vec3::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY),
+            ),
+            |(min, max), offset| (min.min(offset), max.max(offset)),
+        );
+        Some(Aabb::new(min + min_offset, max + max_offset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::materials::Lambertian;
+    use crate::objects::Sphere;
+
+    #[test]
+    fn animated_follows_translation() {
+        let sphere = Sphere::new(Point3::zero(), 1., Arc::new(Lambertian::default()));
+        let testee = Animated::new(
+            Arc::new(sphere),
+            Track::new(0., Vec3::zero()).key(1., Vec3::new(10., 0., 0.)),
+        );
+
+        let ray = |time| Ray::new(Point3::new(5., 0., 5.), Vec3::new(0., 0., -1.), time);
+        assert!(testee.hit(&ray(0.), 0., f32::INFINITY).is_none());
+        let record = testee.hit(&ray(0.5), 0., f32::INFINITY).unwrap();
+        assert!((*record.hit_point() - Point3::new(5., 0., 1.)).length() < 1e-5);
+
+        let bbox = testee.bounding_box((0., 1.)).unwrap();
+        assert_eq!(bbox.min(), &Point3::new(-1., -1., -1.));
+        assert_eq!(bbox.max(), &Point3::new(11., 1., 1.));
+
+        // The box only covers the motion during the time interval
+        let bbox = testee.bounding_box((0.2, 0.5)).unwrap();
+        assert_eq!(bbox.min(), &Point3::new(1., -1., -1.));
+        assert_eq!(bbox.max(), &Point3::new(6., 1., 1.));
+    }
+}
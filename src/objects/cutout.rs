@@ -0,0 +1,96 @@
+use alloc::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::hitable::{HitRecord, Hitable};
+use crate::ray::Ray;
+use crate::textures::OpacityMask;
+
+/// A hitable whose transparent points, according to an opacity mask, are skipped by rays.
+///
+/// Unlike [`materials::Cutout`](crate::materials::Cutout) the ray keeps looking for a hit
+/// behind the transparent points, without using a reflection.
+#[derive(Debug)]
+pub struct Cutout<H: Hitable> {
+    hitable: Arc<H>,
+    mask: OpacityMask,
+}
+
+impl<H: Hitable> Cutout<H> {
+    /// Constructs a new `Cutout` of the hitable with the given opacity mask.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use crab_rt::materials::Lambertian;
+    /// use crab_rt::objects::{Cutout, XyRect};
+    /// use crab_rt::textures::{Image, OpacityMask};
+    ///
+    /// let leaf = Cutout::new(
+    ///     Arc::new(XyRect::new(
+    ///         (0., 1.),
+    ///         (0., 1.),
+    ///         0.,
+    ///         Arc::new(Lambertian::from_rgb(0.1, 0.6, 0.1)),
+    ///     )),
+    ///     OpacityMask::alpha(Image::from_rgba(1, 1, vec![0, 255, 0, 255])),
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new(hitable: Arc<H>, mask: OpacityMask) -> Self {
+        Self { hitable, mask }
+    }
+}
+
+impl<H: Hitable> Hitable for Cutout<H> {
+    fn hit(&self, ray: &Ray, mut t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        loop {
+            let record = self.hitable.hit(ray, t_min, t_max)?;
+            if self
+                .mask
+                .is_opaque(record.texture_coordinates(), record.hit_point())
+            {
+                return Some(record);
+            }
+
+            // Continues the traversal just after the transparent hit
+            t_min = record.t().next_up();
+        }
+    }
+
+    fn bounding_box(&self, time_interval: (f32, f32)) -> Option<Aabb> {
+        self.hitable.bounding_box(time_interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::materials::Lambertian;
+    use crate::objects::Sphere;
+    use crate::textures::Image;
+    use crate::vec::{Point3, Vec3};
+    use std::vec;
+
+    #[test]
+    fn cutout_skips_transparent_hits() {
+        // The left half of the texture, facing +z, is transparent
+        let testee = Cutout::new(
+            Arc::new(Sphere::new(
+                Point3::zero(),
+                1.,
+                Arc::new(Lambertian::default()),
+            )),
+            OpacityMask::alpha(Image::from_rgba(2, 1, vec![0, 0, 0, 0, 0, 0, 0, 255])),
+        );
+
+        let ray = Ray::new(Point3::new(0., 0., 2.), Vec3::new(0., 0., -1.), 0.);
+        let record = testee.hit(&ray, 0.001, f32::INFINITY).unwrap();
+        assert!((record.t() - 3.).abs() < 1e-4);
+
+        let ray = Ray::new(Point3::new(0., 0., -2.), Vec3::new(0., 0., 1.), 0.);
+        let record = testee.hit(&ray, 0.001, f32::INFINITY).unwrap();
+        assert!((record.t() - 1.).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,643 @@
+use alloc::{sync::Arc, vec::Vec};
+use rand::Rng;
+
+use crate::aabb::Aabb;
+use crate::hitable::{HitRecord, Hitable};
+use crate::materials::{Material, Scatter};
+use crate::perlin::Perlin;
+use crate::ray::Ray;
+use crate::utils::{index_to_f32, rng};
+use crate::vec::{Color3, Point3, Vec3};
+
+#[cfg(feature = "std")]
+use anyhow::{bail, Result};
+
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
+/// A 3D grid of densities placed in space.
+#[derive(Debug, Clone)]
+pub struct DensityGrid {
+    /// Number of samples along each axis.
+    resolution: (usize, usize, usize),
+    /// Densities stored with x varying the fastest, then y, then z.
+    data: Vec<f32>,
+    /// Region of space covered by the grid.
+    bbox: Aabb,
+    /// Highest density of the grid.
+    max_density: f32,
+}
+
+impl DensityGrid {
+    /// Constructs a new `DensityGrid` covering `bbox` from the given densities.
+    ///
+    /// # Panics
+    /// Panics if one of the resolution coordinates is 0.
+    /// Panics if the data length is not equal to the product of the resolution coordinates.
+    /// Panics if one of the densities is negative.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::aabb::Aabb;
+    /// use crab_rt::objects::DensityGrid;
+    /// use crab_rt::vec::Vec3;
+    ///
+    /// let grid = DensityGrid::new(
+    ///     (2, 1, 1),
+    ///     vec![0., 1.],
+    ///     Aabb::new(Vec3::zero(), Vec3::new(1., 1., 1.)),
+    /// );
+    /// assert_eq!(grid.density(&Vec3::new(0.5, 0.5, 0.5)), 0.5);
+    /// ```
+    #[must_use]
+    pub fn new(resolution: (usize, usize, usize), data: Vec<f32>, bbox: Aabb) -> Self {
+        assert!(resolution.0 > 0 && resolution.1 > 0 && resolution.2 > 0);
+        assert_eq!(data.len(), resolution.0 * resolution.1 * resolution.2);
+        assert!(
+            data.iter().all(|&d| d >= 0.),
+            "densities should be positive"
+        );
+
+        let max_density = data.iter().copied().fold(0., f32::max);
+
+        Self {
+            resolution,
+            data,
+            bbox,
+            max_density,
+        }
+    }
+
+    /// Constructs a new `DensityGrid` covering `bbox` by sampling `f` at each grid point.
+    ///
+    /// `f` takes the position of the sample in space.
+    ///
+    /// # Panics
+    /// Panics if one of the resolution coordinates is 0.
+    /// Panics if `f` returns a negative density.
+    #[must_use]
+    pub fn from_fn<F: Fn(&Point3) -> f32>(
+        resolution: (usize, usize, usize),
+        f: F,
+        bbox: Aabb,
+    ) -> Self {
+        let coordinate = |i: usize, n: usize, axis: usize| {
+            let t = if n == 1 {
+                0.5
+            } else {
+                index_to_f32(i) / index_to_f32(n - 1)
+            };
+            t.mul_add(bbox.max()[axis] - bbox.min()[axis], bbox.min()[axis])
+        };
+
+        let mut data = Vec::with_capacity(resolution.0 * resolution.1 * resolution.2);
+        for k in 0..resolution.2 {
+            for j in 0..resolution.1 {
+                for i in 0..resolution.0 {
+                    data.push(f(&Point3::new(
+                        coordinate(i, resolution.0, 0),
+                        coordinate(j, resolution.1, 1),
+                        coordinate(k, resolution.2, 2),
+                    )));
+                }
+            }
+        }
+
+        Self::new(resolution, data, bbox)
+    }
+
+    /// Constructs a new `DensityGrid` covering `bbox` from the fractional Brownian motion of a
+    /// Perlin noise.
+    ///
+    /// Densities below `threshold` are cut off to give the volume a shape, the remaining ones are
+    /// remapped to range [0, 1].
+    ///
+    /// # Panics
+    /// Panics if one of the resolution coordinates is 0.
+    /// Panics if `threshold` is not in range [0, 1[.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::aabb::Aabb;
+    /// use crab_rt::objects::DensityGrid;
+    /// use crab_rt::perlin::Perlin;
+    /// use crab_rt::vec::Vec3;
+    ///
+    /// let grid = DensityGrid::from_perlin(
+    ///     &Perlin::new(),
+    ///     2.,
+    ///     4,
+    ///     0.4,
+    ///     (32, 32, 32),
+    ///     Aabb::new(Vec3::zero(), Vec3::new(1., 1., 1.)),
+    /// );
+    /// ```
+    #[must_use]
+    pub fn from_perlin(
+        perlin: &Perlin,
+        frequency: f32,
+        octaves: usize,
+        threshold: f32,
+        resolution: (usize, usize, usize),
+        bbox: Aabb,
+    ) -> Self {
+        assert!(
+            (0. ..1.).contains(&threshold),
+            "threshold should be in range [0, 1["
+        );
+
+        Self::from_fn(
+            resolution,
+            |p| {
+                let noise = perlin.fbm(&(frequency * p), octaves, 2., 0.5);
+                f32::max(noise - threshold, 0.) / (1. - threshold)
+            },
+            bbox,
+        )
+    }
+
+    /// Loads a raw grid of little-endian 32-bit float densities covering `bbox`.
+    ///
+    /// # Errors
+    /// Returns an error if the file can not be read or if its size does not match the resolution.
+    ///
+    /// # Panics
+    /// Panics if one of the resolution coordinates is 0.
+    /// Panics if one of the densities is negative.
+    #[cfg(feature = "std")]
+    pub fn load_raw(filename: &str, resolution: (usize, usize, usize), bbox: Aabb) -> Result<Self> {
+        let bytes = std::fs::read(filename)?;
+        let count = resolution.0 * resolution.1 * resolution.2;
+        if bytes.len() != count * 4 {
+            bail!(
+                "raw grid should contain {} bytes but {} were found",
+                count * 4,
+                bytes.len()
+            );
+        }
+
+        Ok(Self::new(resolution, read_f32s(&bytes), bbox))
+    }
+
+    /// Loads a grid from a Mitsuba `.vol` file.
+    ///
+    /// Only single channel float32 volumes are supported. The grid covers the bounding box stored
+    /// in the file.
+    ///
+    /// # Errors
+    /// Returns an error if the file can not be read or is not a supported `.vol` file.
+    ///
+    /// # Panics
+    /// Panics if one of the densities is negative.
+    #[cfg(feature = "std")]
+    pub fn load_vol(filename: &str) -> Result<Self> {
+        const HEADER_SIZE: usize = 48;
+
+        let bytes = std::fs::read(filename)?;
+        if bytes.len() < HEADER_SIZE || &bytes[0..3] != b"VOL" || bytes[3] != 3 {
+            bail!("{filename} is not a version 3 .vol file");
+        }
+
+        let header = read_f32s(&bytes[4..HEADER_SIZE]);
+        let header_u32 = |i: usize| {
+            u32::from_le_bytes([
+                bytes[4 + i * 4],
+                bytes[5 + i * 4],
+                bytes[6 + i * 4],
+                bytes[7 + i * 4],
+            ]) as usize
+        };
+        let (encoding, channels) = (header_u32(0), header_u32(4));
+        if encoding != 1 || channels != 1 {
+            bail!("only single channel float32 .vol files are supported");
+        }
+
+        let resolution = (header_u32(1), header_u32(2), header_u32(3));
+        let count = resolution.0 * resolution.1 * resolution.2;
+        if count == 0 || bytes.len() != HEADER_SIZE + count * 4 {
+            bail!("{filename} has an invalid size");
+        }
+
+        let bbox = Aabb::new(
+            Point3::new(header[5], header[6], header[7]),
+            Point3::new(header[8], header[9], header[10]),
+        );
+
+        Ok(Self::new(
+            resolution,
+            read_f32s(&bytes[HEADER_SIZE..]),
+            bbox,
+        ))
+    }
+
+    /// Returns the density at point `p` by trilinear interpolation, 0 outside of the grid.
+    #[must_use]
+    pub fn density(&self, p: &Point3) -> f32 {
+        let local = (p - self.bbox.min()) / (self.bbox.max() - self.bbox.min());
+        if !(0. ..=1.).contains(&local.x)
+            || !(0. ..=1.).contains(&local.y)
+            || !(0. ..=1.).contains(&local.z)
+        {
+            return 0.;
+        }
+
+        // Returns the lower sample index and the interpolation weight along an axis
+        // The position is in range [0, n - 1] as the point is inside of the grid
+        #[allow(clippy::cast_possible_truncation)]
+        #[allow(clippy::cast_sign_loss)]
+        let axis = |t: f32, n: usize| {
+            let x = t * index_to_f32(n - 1);
+            let i = usize::min(x as usize, n.saturating_sub(2));
+            (i, x - index_to_f32(i))
+        };
+        let (i, tx) = axis(local.x, self.resolution.0);
+        let (j, ty) = axis(local.y, self.resolution.1);
+        let (k, tz) = axis(local.z, self.resolution.2);
+
+        let sample = |di: usize, dj: usize, dk: usize| {
+            let i = usize::min(i + di, self.resolution.0 - 1);
+            let j = usize::min(j + dj, self.resolution.1 - 1);
+            let k = usize::min(k + dk, self.resolution.2 - 1);
+            self.data[i + self.resolution.0 * (j + self.resolution.1 * k)]
+        };
+        let lerp = |a: f32, b: f32, t: f32| t.mul_add(b - a, a);
+
+        lerp(
+            lerp(
+                lerp(sample(0, 0, 0), sample(1, 0, 0), tx),
+                lerp(sample(0, 1, 0), sample(1, 1, 0), tx),
+                ty,
+            ),
+            lerp(
+                lerp(sample(0, 0, 1), sample(1, 0, 1), tx),
+                lerp(sample(0, 1, 1), sample(1, 1, 1), tx),
+                ty,
+            ),
+            tz,
+        )
+    }
+
+    /// Returns the highest density of the grid.
+    #[inline]
+    #[must_use]
+    pub const fn max_density(&self) -> f32 {
+        self.max_density
+    }
+}
+
+#[cfg(feature = "std")]
+fn read_f32s(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .as_chunks::<4>()
+        .0
+        .iter()
+        .map(|b| f32::from_le_bytes(*b))
+        .collect()
+}
+
+/// The material of the points where light is absorbed by a [`GridMedium`].
+#[derive(Debug)]
+struct Absorption {
+    emission: Color3,
+}
+
+impl Material for Absorption {
+    #[inline]
+    fn scatter(&self, _ray: &Ray, _record: &HitRecord<'_>) -> Option<Scatter> {
+        None
+    }
+
+    #[inline]
+    fn emitted(&self, _ray: &Ray, _record: &HitRecord<'_>) -> Vec3 {
+        self.emission
+    }
+}
+
+/// A participating medium whose density varies in space, like smoke, clouds or fire.
+///
+/// The density grid is scaled by the absorption and scattering coefficients. Collisions are
+/// sampled with delta tracking against the highest density of the grid, so the boundary does not
+/// need to be convex.
+#[derive(Debug)]
+pub struct GridMedium<M: Material> {
+    boundary: Arc<dyn Hitable>,
+    grid: DensityGrid,
+    /// Absorption coefficient per unit of density.
+    absorption: f32,
+    /// Scattering coefficient per unit of density.
+    scattering: f32,
+    phase_function: Arc<M>,
+    absorption_material: Absorption,
+}
+
+impl<M: Material> GridMedium<M> {
+    /// Constructs a new [`GridMedium`] with the given boundary, density grid and phase function.
+    ///
+    /// The medium only scatters light by default.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use crab_rt::aabb::Aabb;
+    /// use crab_rt::materials::{Isotropic, Lambertian};
+    /// use crab_rt::objects::{DensityGrid, GridMedium, Sphere};
+    /// use crab_rt::perlin::Perlin;
+    /// use crab_rt::textures::Monochrome;
+    /// use crab_rt::vec::{Color3, Vec3};
+    ///
+    /// let bbox = Aabb::new(Vec3::new(-1., -1., -1.), Vec3::new(1., 1., 1.));
+    /// let cloud = GridMedium::new(
+    ///     Arc::new(Sphere::new(
+    ///         Vec3::zero(),
+    ///         1.,
+    ///         Arc::new(Lambertian::default()),
+    ///     )),
+    ///     DensityGrid::from_perlin(&Perlin::new(), 2., 4, 0.3, (32, 32, 32), bbox),
+    ///     Arc::new(Isotropic::new(Monochrome::from_rgb(0.9, 0.9, 0.9))),
+    /// )
+    /// .scattering(5.)
+    /// .absorption(0.5);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new(
+        boundary: Arc<dyn Hitable>,
+        grid: DensityGrid,
+        phase_function: Arc<M>,
+    ) -> Self {
+        Self {
+            boundary,
+            grid,
+            absorption: 0.,
+            scattering: 1.,
+            phase_function,
+            absorption_material: Absorption {
+                emission: Color3::zero(),
+            },
+        }
+    }
+
+    /// Consumes the `GridMedium` and returns self after setting the absorption coefficient.
+    ///
+    /// # Panics
+    /// Panics if `absorption < 0.`.
+    #[inline]
+    #[must_use]
+    pub fn absorption(self, absorption: f32) -> Self {
+        assert!(
+            absorption >= 0.,
+            "absorption should be greater or equal to 0"
+        );
+
+        Self { absorption, ..self }
+    }
+
+    /// Consumes the `GridMedium` and returns self after setting the scattering coefficient.
+    ///
+    /// # Panics
+    /// Panics if `scattering < 0.`.
+    #[inline]
+    #[must_use]
+    pub fn scattering(self, scattering: f32) -> Self {
+        assert!(
+            scattering >= 0.,
+            "scattering should be greater or equal to 0"
+        );
+
+        Self { scattering, ..self }
+    }
+
+    /// Consumes the `GridMedium` and returns self after setting the radiance emitted where light
+    /// is absorbed.
+    ///
+    /// Emission only happens where the medium absorbs light, so the absorption coefficient must
+    /// not be 0 for the medium to glow.
+    #[inline]
+    #[must_use]
+    pub fn emission(self, emission: Color3) -> Self {
+        Self {
+            absorption_material: Absorption { emission },
+            ..self
+        }
+    }
+
+    /// Returns the extinction coefficient of the densest point of the medium.
+    #[inline]
+    fn majorant(&self) -> f32 {
+        (self.absorption + self.scattering) * self.grid.max_density()
+    }
+
+    /// Returns the intervals of the ray parameter inside the boundary, in increasing order.
+    fn segments<'a>(
+        &'a self,
+        ray: &'a Ray,
+        t_min: f32,
+        t_max: f32,
+    ) -> impl Iterator<Item = (f32, f32)> + 'a {
+        const EPSILON: f32 = 0.0001;
+
+        let mut t = t_min;
+        core::iter::from_fn(move || {
+            if t >= t_max {
+                return None;
+            }
+
+            let Some(record) = self.boundary.hit(ray, t, f32::INFINITY) else {
+                t = t_max;
+                return None;
+            };
+
+            // Leaving the boundary means we were inside since t
+            if !record.front_face() {
+                let segment = (t, f32::min(record.t(), t_max));
+                t = record.t() + EPSILON;
+                return Some(segment);
+            }
+
+            let enter = record.t();
+            if enter >= t_max {
+                t = t_max;
+                return None;
+            }
+
+            // Like `ConstantMedium`, a ray which does not leave the boundary, because the boundary
+            // is open or the ray grazes it, does not go through the medium
+            let Some(exit) = self
+                .boundary
+                .hit(ray, enter + EPSILON, f32::INFINITY)
+                .map(|record| record.t())
+            else {
+                t = t_max;
+                return None;
+            };
+            t = exit + EPSILON;
+
+            Some((enter, f32::min(exit, t_max)))
+        })
+    }
+
+    /// Estimates the fraction of light going through the medium along the ray between `t_min`
+    /// and `t_max` by ratio tracking.
+    #[must_use]
+    pub fn transmittance(&self, ray: &Ray, t_min: f32, t_max: f32) -> f32 {
+        let majorant = self.majorant();
+        if majorant == 0. {
+            return 1.;
+        }
+
+        let mut rng = rng();
+        let inv_step = 1. / (majorant * ray.direction().length());
+        let extinction = self.absorption + self.scattering;
+
+        let mut transmittance = 1.;
+        for (start, end) in self.segments(ray, t_min, t_max) {
+            let mut t = start;
+            loop {
+                t -= (1. - rng.gen::<f32>()).ln() * inv_step;
+                if t >= end {
+                    break;
+                }
+
+                transmittance *= 1. - extinction * self.grid.density(&ray.point(t)) / majorant;
+            }
+        }
+
+        transmittance
+    }
+}
+
+impl<M: Material> Hitable for GridMedium<M> {
+    fn hit(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        let majorant = self.majorant();
+        if majorant == 0. {
+            return None;
+        }
+
+        let mut rng = rng();
+        let inv_step = 1. / (majorant * ray.direction().length());
+
+        for (start, end) in self.segments(ray, t_min, t_max) {
+            // Delta tracking: we sample tentative collisions as if the medium had the majorant
+            // density everywhere and then decide if the collision is real.
+            let mut t = start;
+            loop {
+                t -= (1. - rng.gen::<f32>()).ln() * inv_step;
+                if t >= end {
+                    break;
+                }
+
+                let density = self.grid.density(&ray.point(t));
+                let xi = rng.gen::<f32>() * majorant;
+
+                let material: &dyn Material = if xi < self.absorption * density {
+                    &self.absorption_material
+                } else if xi < (self.absorption + self.scattering) * density {
+                    self.phase_function.as_ref()
+                } else {
+                    // Null collision
+                    continue;
+                };
+
+                return Some(HitRecord::new(
+                    t,
+                    ray.point(t),
+                    Vec3::new(1., 0., 0.),
+                    (0., 0.),
+                    material,
+                ));
+            }
+        }
+
+        None
+    }
+
+    fn bounding_box(&self, time_interval: (f32, f32)) -> Option<Aabb> {
+        self.boundary.bounding_box(time_interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::materials::{Isotropic, Lambertian};
+    use crate::objects::{Sphere, XzRect};
+    use crate::textures::Monochrome;
+    use std::vec;
+
+    fn unit_grid(density: f32) -> DensityGrid {
+        DensityGrid::new(
+            (2, 2, 2),
+            vec![density; 8],
+            Aabb::new(Vec3::new(-1., -1., -1.), Vec3::new(1., 1., 1.)),
+        )
+    }
+
+    #[test]
+    fn density_grid_interpolation() {
+        let testee = DensityGrid::new(
+            (2, 1, 1),
+            vec![0., 1.],
+            Aabb::new(Vec3::zero(), Vec3::new(1., 1., 1.)),
+        );
+
+        assert_eq!(testee.density(&Vec3::new(0.25, 0.5, 0.5)), 0.25);
+        assert_eq!(testee.density(&Vec3::new(2., 0.5, 0.5)), 0.);
+        assert_eq!(testee.max_density(), 1.);
+    }
+
+    #[test]
+    fn grid_medium_empty_is_transparent() {
+        let testee = GridMedium::new(
+            Arc::new(Sphere::new(
+                Vec3::zero(),
+                1.,
+                Arc::new(Lambertian::default()),
+            )),
+            unit_grid(0.),
+            Arc::new(Isotropic::new(Monochrome::from_rgb(1., 1., 1.))),
+        );
+        let ray = Ray::new(Point3::new(-2., 0., 0.), Vec3::new(1., 0., 0.), 0.);
+
+        assert!(testee.hit(&ray, 0.001, f32::INFINITY).is_none());
+        assert_eq!(testee.transmittance(&ray, 0.001, f32::INFINITY), 1.);
+    }
+
+    #[test]
+    fn grid_medium_dense_hits_inside_boundary() {
+        let testee = GridMedium::new(
+            Arc::new(Sphere::new(
+                Vec3::zero(),
+                1.,
+                Arc::new(Lambertian::default()),
+            )),
+            unit_grid(1.),
+            Arc::new(Isotropic::new(Monochrome::from_rgb(1., 1., 1.))),
+        )
+        .scattering(1000.);
+        let ray = Ray::new(Point3::new(-2., 0., 0.), Vec3::new(1., 0., 0.), 0.);
+
+        let record = testee.hit(&ray, 0.001, f32::INFINITY).unwrap();
+        assert!(record.t() >= 1. && record.t() < 3.);
+    }
+
+    #[test]
+    fn grid_medium_open_boundary_ends() {
+        let testee = GridMedium::new(
+            Arc::new(XzRect::new(
+                (-1., 1.),
+                (-1., 1.),
+                0.,
+                Arc::new(Lambertian::default()),
+            )),
+            unit_grid(0.5),
+            Arc::new(Isotropic::new(Monochrome::from_rgb(1., 1., 1.))),
+        )
+        .scattering(1.);
+        let ray = Ray::new(Point3::new(0., 2., 0.), Vec3::new(0., -1., 0.), 0.);
+
+        // The ray enters the boundary but never leaves it
+        assert!(testee.hit(&ray, 0.001, f32::INFINITY).is_none());
+        assert_eq!(testee.transmittance(&ray, 0.001, f32::INFINITY), 1.);
+    }
+}
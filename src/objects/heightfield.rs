@@ -0,0 +1,544 @@
+use alloc::{sync::Arc, vec::Vec};
+
+use crate::aabb::Aabb;
+use crate::hitable::{HitRecord, Hitable};
+use crate::materials::Material;
+use crate::perlin::Perlin;
+use crate::ray::Ray;
+use crate::textures::Image;
+use crate::utils::index_to_f32;
+use crate::vec::{Point3, Vec3};
+
+#[cfg(feature = "std")]
+use anyhow::Result;
+
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
+/// A terrain defined by a regular grid of heights.
+///
+/// The terrain spans `[0, size.x]` along the x axis, `[0, size.z]` along the z axis and its
+/// heights are in range `[0, size.y]`. The texture coordinates span the whole terrain.
+#[derive(Debug)]
+pub struct Heightfield<M: Material> {
+    /// Number of samples along the x axis.
+    width: usize,
+    /// Number of samples along the z axis.
+    depth: usize,
+    /// Heights in range [0, 1] stored row by row along the x axis.
+    heights: Vec<f32>,
+    /// Normals at each sample.
+    normals: Vec<Vec3>,
+    /// Size of the terrain.
+    size: Vec3,
+    bbox: Aabb,
+    /// Material of the terrain.
+    material: Arc<M>,
+}
+
+impl<M: Material> Heightfield<M> {
+    /// Constructs a new `Heightfield` from `width * depth` heights in range [0, 1].
+    ///
+    /// # Panics
+    /// Panics if `width < 2` or `depth < 2`.
+    /// Panics if the heights length is not equal to `width * depth`.
+    /// Panics if one of the size coordinates is not strictly positive.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use crab_rt::materials::Lambertian;
+    /// use crab_rt::objects::Heightfield;
+    /// use crab_rt::vec::Vec3;
+    ///
+    /// let terrain = Heightfield::new(
+    ///     2,
+    ///     2,
+    ///     vec![0., 0.5, 0.5, 1.],
+    ///     Vec3::new(10., 1., 10.),
+    ///     Arc::new(Lambertian::default()),
+    /// );
+    /// ```
+    #[must_use]
+    pub fn new(
+        width: usize,
+        depth: usize,
+        heights: Vec<f32>,
+        size: Vec3,
+        material: Arc<M>,
+    ) -> Self {
+        assert!(
+            width >= 2 && depth >= 2,
+            "heightfield needs at least 2x2 samples"
+        );
+        assert_eq!(heights.len(), width * depth);
+        assert!(size.x > 0. && size.y > 0. && size.z > 0.);
+
+        let (min_height, max_height) = heights
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), &h| {
+                (f32::min(min, h), f32::max(max, h))
+            });
+
+        let mut heightfield = Self {
+            width,
+            depth,
+            heights,
+            normals: Vec::new(),
+            size,
+            // The bounding box must have a non-zero height so we pad it by a small amount
+            bbox: Aabb::new(
+                Point3::new(0., min_height * size.y - 0.0001, 0.),
+                Point3::new(size.x, max_height * size.y + 0.0001, size.z),
+            ),
+            material,
+        };
+        heightfield.normals = (0..depth)
+            .flat_map(|j| (0..width).map(move |i| (i, j)))
+            .map(|(i, j)| heightfield.sample_normal(i, j))
+            .collect();
+
+        heightfield
+    }
+
+    /// Constructs a new `Heightfield` by sampling `f` on a `width * depth` grid.
+    ///
+    /// `f` takes texture coordinates in range [0, 1] and returns a height in range [0, 1].
+    ///
+    /// # Panics
+    /// Panics if `width < 2` or `depth < 2`.
+    /// Panics if one of the size coordinates is not strictly positive.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use crab_rt::materials::Lambertian;
+    /// use crab_rt::objects::Heightfield;
+    /// use crab_rt::vec::Vec3;
+    ///
+    /// // A ramp going up along the x axis
+    /// let terrain = Heightfield::from_fn(
+    ///     16,
+    ///     16,
+    ///     |u, _v| u,
+    ///     Vec3::new(10., 2., 10.),
+    ///     Arc::new(Lambertian::default()),
+    /// );
+    /// ```
+    #[must_use]
+    pub fn from_fn<F: Fn(f32, f32) -> f32>(
+        width: usize,
+        depth: usize,
+        f: F,
+        size: Vec3,
+        material: Arc<M>,
+    ) -> Self {
+        assert!(
+            width >= 2 && depth >= 2,
+            "heightfield needs at least 2x2 samples"
+        );
+
+        let heights = (0..depth)
+            .flat_map(|j| (0..width).map(move |i| (i, j)))
+            .map(|(i, j)| {
+                f(
+                    index_to_f32(i) / index_to_f32(width - 1),
+                    index_to_f32(j) / index_to_f32(depth - 1),
+                )
+            })
+            .collect();
+
+        Self::new(width, depth, heights, size, material)
+    }
+
+    /// Constructs a new `Heightfield` from the fractional Brownian motion of a Perlin noise.
+    ///
+    /// `frequency` is the number of noise periods spanning the terrain.
+    ///
+    /// # Panics
+    /// Panics if `width < 2` or `depth < 2`.
+    /// Panics if one of the size coordinates is not strictly positive.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use crab_rt::materials::Lambertian;
+    /// use crab_rt::objects::Heightfield;
+    /// use crab_rt::perlin::Perlin;
+    /// use crab_rt::vec::Vec3;
+    ///
+    /// let terrain = Heightfield::from_perlin(
+    ///     &Perlin::new(),
+    ///     4.,
+    ///     6,
+    ///     256,
+    ///     256,
+    ///     Vec3::new(100., 15., 100.),
+    ///     Arc::new(Lambertian::default()),
+    /// );
+    /// ```
+    #[must_use]
+    pub fn from_perlin(
+        perlin: &Perlin,
+        frequency: f32,
+        octaves: usize,
+        width: usize,
+        depth: usize,
+        size: Vec3,
+        material: Arc<M>,
+    ) -> Self {
+        Self::from_fn(
+            width,
+            depth,
+            |u, v| {
+                perlin.fbm(
+                    &Point3::new(u * frequency, 0., v * frequency),
+                    octaves,
+                    2.,
+                    0.5,
+                )
+            },
+            size,
+            material,
+        )
+    }
+
+    /// Constructs a new `Heightfield` from the luminance of an image.
+    ///
+    /// The top of the image is at the far end of the z axis so that the texture coordinates
+    /// of the terrain match the image ones. The stored values are used as heights whatever the
+    /// color space of the image, like [`Heightfield::load`].
+    ///
+    /// # Panics
+    /// Panics if the image is smaller than 2x2 pixels.
+    /// Panics if one of the size coordinates is not strictly positive.
+    #[must_use]
+    pub fn from_image(image: &Image, size: Vec3, material: Arc<M>) -> Self {
+        let width = image.width();
+        let depth = image.height();
+        assert!(
+            width >= 2 && depth >= 2,
+            "heightfield needs at least 2x2 pixels"
+        );
+
+        let heights = (0..depth)
+            .flat_map(|j| (0..width).map(move |i| (i, j)))
+            .map(|(i, j)| {
+                let pixel = image.raw_pixel(i, depth - 1 - j);
+                // Rec. 709 luma coefficients
+                0.0722f32.mul_add(pixel.z, 0.2126f32.mul_add(pixel.x, 0.7152 * pixel.y))
+            })
+            .collect();
+
+        Self::new(width, depth, heights, size, material)
+    }
+
+    /// Loads a grayscale image file as a `Heightfield`.
+    ///
+    /// The full bit depth of the image is kept, which avoids terracing with 16-bit heightmaps.
+    ///
+    /// # Errors
+    /// Returns an error if the image can not be opened or decoded.
+    ///
+    /// # Panics
+    /// Panics if the image is smaller than 2x2 pixels.
+    /// Panics if one of the size coordinates is not strictly positive.
+    #[cfg(feature = "std")]
+    pub fn load(filename: &str, size: Vec3, material: Arc<M>) -> Result<Self> {
+        let image_buffer = image::open(filename)?.into_luma16();
+        let width = image_buffer.width() as usize;
+        let depth = image_buffer.height() as usize;
+        assert!(
+            width >= 2 && depth >= 2,
+            "heightfield needs at least 2x2 pixels"
+        );
+
+        let raw = image_buffer.into_raw();
+        let heights = (0..depth)
+            .flat_map(|j| (0..width).map(move |i| (i, j)))
+            .map(|(i, j)| f32::from(raw[i + (depth - 1 - j) * width]) / f32::from(u16::MAX))
+            .collect();
+
+        Ok(Self::new(width, depth, heights, size, material))
+    }
+
+    #[inline]
+    fn height(&self, i: usize, j: usize) -> f32 {
+        self.heights[i + j * self.width]
+    }
+
+    #[inline]
+    fn vertex(&self, i: usize, j: usize) -> Point3 {
+        Point3::new(
+            index_to_f32(i) / index_to_f32(self.width - 1) * self.size.x,
+            self.height(i, j) * self.size.y,
+            index_to_f32(j) / index_to_f32(self.depth - 1) * self.size.z,
+        )
+    }
+
+    /// Computes the normal at a sample from the central differences of the heights.
+    fn sample_normal(&self, i: usize, j: usize) -> Vec3 {
+        let (i0, i1) = (i.saturating_sub(1), usize::min(i + 1, self.width - 1));
+        let (j0, j1) = (j.saturating_sub(1), usize::min(j + 1, self.depth - 1));
+
+        let dx = index_to_f32(i1 - i0) / index_to_f32(self.width - 1) * self.size.x;
+        let dz = index_to_f32(j1 - j0) / index_to_f32(self.depth - 1) * self.size.z;
+        let slope_x = (self.height(i1, j) - self.height(i0, j)) * self.size.y / dx;
+        let slope_z = (self.height(i, j1) - self.height(i, j0)) * self.size.y / dz;
+
+        Vec3::new(-slope_x, 1., -slope_z).unit()
+    }
+
+    /// Intersects the ray with the two triangles of the cell `(i, j)`.
+    fn hit_cell(
+        &self,
+        ray: &Ray,
+        i: usize,
+        j: usize,
+        t_min: f32,
+        t_max: f32,
+    ) -> Option<HitRecord<'_>> {
+        let corners = [(i, j), (i + 1, j), (i + 1, j + 1), (i, j + 1)];
+        let triangles = [
+            [corners[0], corners[1], corners[2]],
+            [corners[0], corners[2], corners[3]],
+        ];
+
+        let mut closest = None;
+        let mut closest_t = t_max;
+        for triangle in triangles {
+            let [v0, v1, v2] = triangle.map(|(i, j)| self.vertex(i, j));
+            if let Some((t, beta, gamma)) = hit_triangle(ray, &v0, &v1, &v2, t_min, closest_t) {
+                closest_t = t;
+                closest = Some((t, triangle, beta, gamma));
+            }
+        }
+
+        let (t, [v0, v1, v2], beta, gamma) = closest?;
+        let normal_at = |(i, j): (usize, usize)| self.normals[i + j * self.width];
+        let normal =
+            ((1. - beta - gamma) * normal_at(v0) + beta * normal_at(v1) + gamma * normal_at(v2))
+                .unit();
+
+        let hit_point = ray.point(t);
+        let mut record = HitRecord::new(
+            t,
+            hit_point,
+            normal,
+            (hit_point.x / self.size.x, hit_point.z / self.size.z),
+            self.material.as_ref(),
+        );
+        // Tangents of the surface y = h(x, z) perpendicular to the interpolated normal
+        record.set_partial_derivatives(
+            self.size.x * Vec3::new(1., -normal.x / normal.y, 0.),
+            self.size.z * Vec3::new(0., -normal.z / normal.y, 1.),
+        );
+        record.set_face_normal(ray);
+
+        Some(record)
+    }
+}
+
+impl<M: Material> Hitable for Heightfield<M> {
+    fn hit(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        let (t_enter, t_exit) = self.bbox.intersection(ray, t_min, t_max)?;
+
+        // We walk the cells crossed by the projection of the ray on the xz plane with a
+        // digital differential analyzer (DDA), from the entry to the exit of the bounding box.
+        let cells = (index_to_f32(self.width - 1), index_to_f32(self.depth - 1));
+        let cell_size = (self.size.x / cells.0, self.size.z / cells.1);
+        let entry = ray.point(t_enter);
+        // The cell index is clamped to the cells of the grid
+        #[allow(clippy::cast_possible_truncation)]
+        #[allow(clippy::cast_sign_loss)]
+        let to_cell = |x: f32, size: f32, n: usize| (f32::max(x / size, 0.) as usize).min(n - 2);
+        let mut i = to_cell(entry.x, cell_size.0, self.width);
+        let mut j = to_cell(entry.z, cell_size.1, self.depth);
+
+        let direction = ray.direction();
+        let axis_setup = |d: f32, origin: f32, cell: usize, size: f32| {
+            if d > 0. {
+                (1, (index_to_f32(cell + 1) * size - origin) / d, size / d)
+            } else if d < 0. {
+                (-1, (index_to_f32(cell) * size - origin) / d, -size / d)
+            } else {
+                (0, f32::INFINITY, f32::INFINITY)
+            }
+        };
+        let (step_i, mut t_next_i, t_delta_i) =
+            axis_setup(direction.x, ray.origin().x, i, cell_size.0);
+        let (step_j, mut t_next_j, t_delta_j) =
+            axis_setup(direction.z, ray.origin().z, j, cell_size.1);
+
+        let mut t_cell_enter = t_enter;
+        loop {
+            let t_cell_exit = f32::min(f32::min(t_next_i, t_next_j), t_exit);
+
+            // Skips the cell if the ray stays above or below it
+            let corner_heights =
+                [(i, j), (i + 1, j), (i, j + 1), (i + 1, j + 1)].map(|(i, j)| self.height(i, j));
+            let cell_min_height =
+                corner_heights.into_iter().fold(f32::INFINITY, f32::min) * self.size.y;
+            let cell_max_height =
+                corner_heights.into_iter().fold(f32::NEG_INFINITY, f32::max) * self.size.y;
+            let y_enter = ray.point(t_cell_enter).y;
+            let y_exit = ray.point(t_cell_exit).y;
+            let above = f32::min(y_enter, y_exit) > cell_max_height;
+            let below = f32::max(y_enter, y_exit) < cell_min_height;
+
+            if !above && !below {
+                // The cell boundaries are slightly widened to not miss hits on the edges
+                let epsilon = 1e-4 * (t_cell_exit - t_cell_enter);
+                if let Some(record) = self.hit_cell(
+                    ray,
+                    i,
+                    j,
+                    f32::max(t_cell_enter - epsilon, t_min),
+                    f32::min(t_cell_exit + epsilon, t_max),
+                ) {
+                    return Some(record);
+                }
+            }
+
+            if t_cell_exit >= t_exit {
+                return None;
+            }
+
+            if t_next_i < t_next_j {
+                if (step_i < 0 && i == 0) || (step_i > 0 && i + 2 >= self.width) {
+                    return None;
+                }
+                i = i.wrapping_add_signed(step_i);
+                t_next_i += t_delta_i;
+            } else {
+                if (step_j < 0 && j == 0) || (step_j > 0 && j + 2 >= self.depth) {
+                    return None;
+                }
+                j = j.wrapping_add_signed(step_j);
+                t_next_j += t_delta_j;
+            }
+            t_cell_enter = t_cell_exit;
+        }
+    }
+
+    #[inline]
+    fn bounding_box(&self, _time_interval: (f32, f32)) -> Option<Aabb> {
+        Some(self.bbox)
+    }
+}
+
+/// Intersects the ray with the triangle `abc` using the Möller–Trumbore algorithm.
+///
+/// Returns the ray parameter and the barycentric coordinates of `b` and `c`.
+// The names follow the usual notation of the algorithm
+#[allow(clippy::many_single_char_names)]
+fn hit_triangle(
+    ray: &Ray,
+    a: &Point3,
+    b: &Point3,
+    c: &Point3,
+    t_min: f32,
+    t_max: f32,
+) -> Option<(f32, f32, f32)> {
+    const EPSILON: f32 = 1e-8;
+
+    let edge1 = b - a;
+    let edge2 = c - a;
+    let h = ray.direction().cross(&edge2);
+    let det = edge1.dot(&h);
+    if det.abs() < EPSILON {
+        return None;
+    }
+
+    let inv_det = 1. / det;
+    let s = ray.origin() - a;
+    let beta = inv_det * s.dot(&h);
+    if !(0. ..=1.).contains(&beta) {
+        return None;
+    }
+
+    let q = s.cross(&edge1);
+    let gamma = inv_det * ray.direction().dot(&q);
+    if gamma < 0. || beta + gamma > 1. {
+        return None;
+    }
+
+    let t = inv_det * edge2.dot(&q);
+    (t_min <= t && t <= t_max).then_some((t, beta, gamma))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::materials::Lambertian;
+    use std::vec;
+
+    #[test]
+    fn heightfield_bounding_box() {
+        let testee = Heightfield::new(
+            2,
+            2,
+            vec![0.25, 0.5, 0.5, 0.75],
+            Vec3::new(10., 2., 5.),
+            Arc::new(Lambertian::default()),
+        );
+        let bbox = testee.bounding_box((0., 0.)).unwrap();
+
+        assert!((bbox.min().y - 0.5).abs() < 1e-3);
+        assert!((bbox.max().y - 1.5).abs() < 1e-3);
+        assert_eq!(bbox.max().x, 10.);
+        assert_eq!(bbox.max().z, 5.);
+    }
+
+    #[test]
+    fn heightfield_hit_flat() {
+        let testee = Heightfield::from_fn(
+            8,
+            8,
+            |_, _| 0.5,
+            Vec3::new(10., 2., 10.),
+            Arc::new(Lambertian::default()),
+        );
+        let ray = Ray::new(Point3::new(3., 5., 7.), Vec3::new(0., -1., 0.), 0.);
+
+        let record = testee.hit(&ray, 0.001, f32::INFINITY).unwrap();
+        assert!((record.t() - 4.).abs() < 1e-3);
+        assert!((record.normal().y - 1.).abs() < 1e-3);
+        assert!((record.texture_coordinates().0 - 0.3).abs() < 1e-3);
+        assert!((record.texture_coordinates().1 - 0.7).abs() < 1e-3);
+    }
+
+    #[test]
+    fn heightfield_hit_grazing_ray() {
+        // A ramp going up along the x axis from 0 to 1
+        let testee = Heightfield::from_fn(
+            16,
+            4,
+            |u, _| u,
+            Vec3::new(1., 1., 1.),
+            Arc::new(Lambertian::default()),
+        );
+        let ray = Ray::new(Point3::new(-1., 0.5, 0.5), Vec3::new(1., 0., 0.), 0.);
+
+        let record = testee.hit(&ray, 0.001, f32::INFINITY).unwrap();
+        assert!((record.hit_point().x - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn heightfield_from_image_keeps_stored_values() {
+        // 8-bit images are gamma encoded by default, which should not bend the heights
+        let testee = Heightfield::from_image(
+            &Image::new(2, 2, vec![128; 12]),
+            Vec3::new(1., 1., 1.),
+            Arc::new(Lambertian::default()),
+        );
+
+        assert!(testee
+            .heights
+            .iter()
+            .all(|height| (height - 0.5).abs() < 1e-2));
+    }
+}
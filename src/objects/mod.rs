@@ -1,17 +1,27 @@
 pub mod aabox;
 pub mod aarect;
+pub mod animated;
 pub mod constant_medium;
+pub mod cutout;
+pub mod grid_medium;
+pub mod heightfield;
 pub mod moving_sphere;
 pub mod object;
 pub mod rotate;
+pub mod sdf_object;
 pub mod sphere;
 pub mod translate;
 
 pub use aabox::AaBox;
 pub use aarect::{XyRect, XzRect, YzRect};
+pub use animated::Animated;
 pub use constant_medium::ConstantMedium;
+pub use cutout::Cutout;
+pub use grid_medium::{DensityGrid, GridMedium};
+pub use heightfield::Heightfield;
 pub use moving_sphere::MovingSphere;
 pub use object::Object;
 pub use rotate::RotateY;
+pub use sdf_object::SdfObject;
 pub use sphere::Sphere;
 pub use translate::Translate;
@@ -1,5 +1,6 @@
 use alloc::sync::Arc;
 
+use super::sphere::{partial_derivatives, texture_coordinates};
 use crate::aabb::Aabb;
 use crate::hitable::{HitRecord, Hitable};
 use crate::materials::Material;
@@ -42,6 +43,7 @@ impl<M: Material> MovingSphere<M> {
 }
 
 impl<M: Material> Hitable for MovingSphere<M> {
+    #[allow(clippy::similar_names)]
     fn hit(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
         let center = self.center(ray.time());
         let oc = ray.origin() - center;
@@ -69,13 +71,16 @@ impl<M: Material> Hitable for MovingSphere<M> {
         }
 
         let hit_point = ray.point(root);
+        let outward_normal = (hit_point - center) / self.radius;
         let mut record = HitRecord::new(
             root,
             hit_point,
-            (hit_point - center) / self.radius,
-            (0., 0.),
+            outward_normal,
+            texture_coordinates(&outward_normal),
             self.material.as_ref(),
         );
+        let (dpdu, dpdv) = partial_derivatives(self.radius, &outward_normal);
+        record.set_partial_derivatives(dpdu, dpdv);
         record.set_face_normal(ray);
         Some(record)
     }
@@ -96,3 +101,31 @@ impl<M: Material> Hitable for MovingSphere<M> {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::materials::Lambertian;
+    use crate::objects::Sphere;
+    use crate::vec::Point3;
+
+    #[test]
+    #[allow(clippy::similar_names)]
+    fn moving_sphere_hit_matches_sphere_at_ray_time() {
+        let material = Arc::new(Lambertian::default());
+        let testee = MovingSphere::new(
+            (Vec3::zero(), Vec3::new(0., 0., 2.)),
+            (0., 1.),
+            0.5,
+            Arc::clone(&material),
+        );
+        let sphere = Sphere::new(Point3::new(0., 0., 1.), 0.5, material);
+        let ray = Ray::new(Point3::new(3., 0.2, 1.1), Vec3::new(-1., 0., 0.), 0.5);
+
+        let record = testee.hit(&ray, 0.0001, f32::INFINITY).unwrap();
+        let expected = sphere.hit(&ray, 0.0001, f32::INFINITY).unwrap();
+        assert_eq!(record.texture_coordinates(), expected.texture_coordinates());
+        assert_eq!(record.dpdu(), expected.dpdu());
+        assert_eq!(record.dpdv(), expected.dpdv());
+    }
+}
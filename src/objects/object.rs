@@ -8,7 +8,6 @@ use crate::ray::Ray;
 #[derive(Debug)]
 pub struct Object {
     volume: Box<dyn Hitable>,
-    bbox: Option<Aabb>,
 }
 
 impl Object {
@@ -30,10 +29,8 @@ impl Object {
     /// ```
     #[inline]
     pub fn new<H: 'static + Hitable>(volume: H) -> Self {
-        let bbox = volume.bounding_box((0., 0.1)); // TODO: Fix time interval
         Self {
             volume: Box::new(volume),
-            bbox,
         }
     }
 
@@ -50,8 +47,8 @@ impl Hitable for Object {
     }
 
     #[inline]
-    fn bounding_box(&self, _time_interval: (f32, f32)) -> Option<Aabb> {
-        self.bbox // TODO: We could maybe use a Cow
+    fn bounding_box(&self, time_interval: (f32, f32)) -> Option<Aabb> {
+        self.volume.bounding_box(time_interval)
     }
 }
 
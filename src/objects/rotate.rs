@@ -13,56 +13,24 @@ pub struct RotateY {
     hitable: Arc<dyn Hitable>,
     sin_theta: f32,
     cos_theta: f32,
-    hasbox: bool,
-    bbox: Aabb,
 }
 
 impl RotateY {
     #[must_use]
     pub fn new(hitable: Arc<dyn Hitable>, angle: f32) -> Self {
         let theta = angle.to_radians();
-        let sin_theta = theta.sin();
-        let cos_theta = theta.cos();
-
-        let bbox = hitable.bounding_box((0., 1.));
-        let hasbox = bbox.is_some();
-        let bbox = bbox.unwrap_or_default();
-
-        let mut min = Point3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY);
-        let mut max = Point3::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY);
-
-        for i in 0..2 {
-            for j in 0..2 {
-                for k in 0..2 {
-                    let x = i as f32 * bbox.max().x + (1 - i) as f32 * bbox.min().x;
-                    let y = j as f32 * bbox.max().y + (1 - j) as f32 * bbox.min().y;
-                    let z = k as f32 * bbox.max().z + (1 - k) as f32 * bbox.min().z;
-
-                    let new_x = cos_theta * x + sin_theta * z;
-                    let new_z = -sin_theta * x + cos_theta * z;
-
-                    let tester = Vec3::new(new_x, y, new_z);
-
-                    for c in 0..3 {
-                        min[c] = f32::min(min[c], tester[c]);
-                        max[c] = f32::max(max[c], tester[c]);
-                    }
-                }
-            }
-        }
 
         Self {
             hitable,
-            sin_theta,
-            cos_theta,
-            hasbox,
-            bbox: Aabb::new(min, max),
+            sin_theta: theta.sin(),
+            cos_theta: theta.cos(),
         }
     }
 }
 
 impl Hitable for RotateY {
     #[must_use]
+    #[allow(clippy::similar_names)]
     fn hit(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
         let mut origin = *ray.origin();
         let mut direction = *ray.direction();
@@ -86,15 +54,50 @@ impl Hitable for RotateY {
         normal.x = self.cos_theta * record.normal().x + self.sin_theta * record.normal().z;
         normal.z = -self.sin_theta * record.normal().x + self.cos_theta * record.normal().z;
 
+        let rotate = |v: &Vec3| {
+            Vec3::new(
+                self.cos_theta * v.x + self.sin_theta * v.z,
+                v.y,
+                -self.sin_theta * v.x + self.cos_theta * v.z,
+            )
+        };
+        let (dpdu, dpdv) = (rotate(record.dpdu()), rotate(record.dpdv()));
+
         record.set_hit_point(p);
         record.set_normal(normal);
+        record.set_partial_derivatives(dpdu, dpdv);
         record.set_face_normal(&rotated_ray);
 
         Some(record)
     }
 
     #[must_use]
-    fn bounding_box(&self, _time_interval: (f32, f32)) -> Option<Aabb> {
-        self.hasbox.then_some(self.bbox)
+    fn bounding_box(&self, time_interval: (f32, f32)) -> Option<Aabb> {
+        let bbox = self.hitable.bounding_box(time_interval)?;
+
+        let mut min = Point3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+        let mut max = Point3::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY);
+
+        for i in 0..2 {
+            for j in 0..2 {
+                for k in 0..2 {
+                    let x = i as f32 * bbox.max().x + (1 - i) as f32 * bbox.min().x;
+                    let y = j as f32 * bbox.max().y + (1 - j) as f32 * bbox.min().y;
+                    let z = k as f32 * bbox.max().z + (1 - k) as f32 * bbox.min().z;
+
+                    let new_x = self.cos_theta * x + self.sin_theta * z;
+                    let new_z = -self.sin_theta * x + self.cos_theta * z;
+
+                    let tester = Vec3::new(new_x, y, new_z);
+
+                    for c in 0..3 {
+                        min[c] = f32::min(min[c], tester[c]);
+                        max[c] = f32::max(max[c], tester[c]);
+                    }
+                }
+            }
+        }
+
+        Some(Aabb::new(min, max))
     }
 }
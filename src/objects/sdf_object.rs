@@ -0,0 +1,226 @@
+use alloc::{boxed::Box, sync::Arc};
+
+use crate::aabb::Aabb;
+use crate::hitable::{HitRecord, Hitable};
+use crate::materials::Material;
+use crate::ray::Ray;
+use crate::sdf::Sdf;
+use crate::utils::orthonormal_basis;
+use crate::vec::{Point3, Vec3};
+
+const DEFAULT_MAX_STEPS: usize = 256;
+const DEFAULT_EPSILON: f32 = 1e-4;
+
+/// An object whose surface is described by a signed distance function and rendered by
+/// [sphere tracing](https://en.wikipedia.org/wiki/Ray_marching#Sphere_tracing).
+#[derive(Debug)]
+pub struct SdfObject<M: Material> {
+    /// The signed distance function of the surface.
+    sdf: Box<dyn Sdf>,
+    /// A box containing the whole surface, the ray is only marched inside it.
+    bbox: Aabb,
+    /// Material of the surface.
+    material: Arc<M>,
+    /// Maximal number of steps along a ray before giving up.
+    max_steps: usize,
+    /// Distance to the surface under which the surface is considered hit.
+    epsilon: f32,
+    /// Factor applied to each step.
+    step_scale: f32,
+}
+
+impl<M: Material> SdfObject<M> {
+    /// Constructs a new `SdfObject` from the given signed distance function, bounding box and
+    /// material.
+    ///
+    /// The bounding box must contain the whole surface as nothing is rendered outside of it.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use crab_rt::aabb::Aabb;
+    /// use crab_rt::materials::Lambertian;
+    /// use crab_rt::objects::SdfObject;
+    /// use crab_rt::sdf::Sphere;
+    /// use crab_rt::vec::{Point3, Vec3};
+    ///
+    /// let object = SdfObject::new(
+    ///     Sphere::new(Point3::zero(), 1.),
+    ///     Aabb::new(Vec3::new(-1., -1., -1.), Vec3::new(1., 1., 1.)),
+    ///     Arc::new(Lambertian::default()),
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new<S: 'static + Sdf>(sdf: S, bbox: Aabb, material: Arc<M>) -> Self {
+        Self {
+            sdf: Box::new(sdf),
+            bbox,
+            material,
+            max_steps: DEFAULT_MAX_STEPS,
+            epsilon: DEFAULT_EPSILON,
+            step_scale: 1.,
+        }
+    }
+
+    /// Consumes the `SdfObject` and returns self after setting the maximal number of steps.
+    #[inline]
+    #[must_use]
+    pub fn max_steps(self, max_steps: usize) -> Self {
+        Self { max_steps, ..self }
+    }
+
+    /// Consumes the `SdfObject` and returns self after setting the hit distance threshold.
+    ///
+    /// # Panics
+    /// Panics if `epsilon <= 0.`.
+    #[inline]
+    #[must_use]
+    pub fn epsilon(self, epsilon: f32) -> Self {
+        assert!(epsilon > 0., "epsilon should be greater than 0");
+
+        Self { epsilon, ..self }
+    }
+
+    /// Consumes the `SdfObject` and returns self after setting the step scale.
+    ///
+    /// Sphere tracing relies on the distance never overestimating the real distance to the
+    /// surface. Deforming combinators and fractal estimators break that assumption, in which
+    /// case a step scale lower than 1 avoids stepping through the surface.
+    ///
+    /// # Panics
+    /// Panics if `step_scale <= 0.` or `step_scale > 1.`.
+    #[inline]
+    #[must_use]
+    pub fn step_scale(self, step_scale: f32) -> Self {
+        assert!(
+            step_scale > 0. && step_scale <= 1.,
+            "step_scale should be in range ]0, 1]"
+        );
+
+        Self { step_scale, ..self }
+    }
+
+    /// Computes the surface normal at point `p` from the gradient of the distance function.
+    fn normal(&self, p: &Point3) -> Vec3 {
+        // Tetrahedron technique, it only needs 4 evaluations of the distance function
+        let h = self.epsilon;
+        let k0 = Vec3::new(1., -1., -1.);
+        let k1 = Vec3::new(-1., -1., 1.);
+        let k2 = Vec3::new(-1., 1., -1.);
+        let k3 = Vec3::new(1., 1., 1.);
+
+        (k0 * self.sdf.distance(&(p + h * k0))
+            + k1 * self.sdf.distance(&(p + h * k1))
+            + k2 * self.sdf.distance(&(p + h * k2))
+            + k3 * self.sdf.distance(&(p + h * k3)))
+        .unit()
+    }
+}
+
+impl<M: Material> Hitable for SdfObject<M> {
+    fn hit(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        let (mut t, t_end) = self.bbox.intersection(ray, t_min, t_max)?;
+        let inv_direction_length = 1. / ray.direction().length();
+
+        // Rays leaving the surface (eg. after a reflection) start right on it so we first
+        // step away from it before looking for a hit.
+        let starts_inside_bbox = t <= t_min;
+        let mut distance = self.sdf.distance(&ray.point(t));
+        let mut steps = 0;
+        while starts_inside_bbox && distance.abs() < self.epsilon && steps < self.max_steps {
+            t += self.epsilon * inv_direction_length;
+            distance = self.sdf.distance(&ray.point(t));
+            steps += 1;
+        }
+
+        // Rays refracted inside the surface march toward the boundary from the inside.
+        let sign = distance.signum();
+
+        while steps < self.max_steps && t <= t_end {
+            distance = sign * self.sdf.distance(&ray.point(t));
+
+            if distance < self.epsilon {
+                let hit_point = ray.point(t);
+                let normal = self.normal(&hit_point);
+                let mut record =
+                    HitRecord::new(t, hit_point, normal, (0., 0.), self.material.as_ref());
+                // Distance functions have no texture coordinates to follow, so any tangent
+                // frame of the surface does for anisotropic materials and normal maps
+                let (tangent, bitangent) = orthonormal_basis(&normal);
+                record.set_partial_derivatives(tangent, bitangent);
+                record.set_face_normal(ray);
+
+                return Some(record);
+            }
+
+            t += self.step_scale * distance * inv_direction_length;
+            steps += 1;
+        }
+
+        None
+    }
+
+    #[inline]
+    fn bounding_box(&self, _time_interval: (f32, f32)) -> Option<Aabb> {
+        Some(self.bbox)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::materials::Lambertian;
+    use crate::sdf::Sphere;
+
+    fn unit_sphere() -> SdfObject<Lambertian> {
+        SdfObject::new(
+            Sphere::new(Point3::zero(), 1.),
+            Aabb::new(Vec3::new(-1., -1., -1.), Vec3::new(1., 1., 1.)),
+            Arc::new(Lambertian::default()),
+        )
+    }
+
+    #[test]
+    fn sdf_object_hit_hitting_ray() {
+        let testee = unit_sphere();
+        let ray = Ray::new(Point3::new(3., 0., 0.), Vec3::new(-2., 0., 0.), 0.);
+
+        let record = testee.hit(&ray, 0.001, f32::INFINITY).unwrap();
+        assert!((record.t() - 1.).abs() < 1e-3);
+        assert!((record.normal().x - 1.).abs() < 1e-3);
+        assert!(record.front_face());
+    }
+
+    #[test]
+    fn sdf_object_hit_has_tangent_frame() {
+        let testee = unit_sphere();
+        let ray = Ray::new(Point3::new(1., 3., 2.), Vec3::new(-1., -3., -2.), 0.);
+
+        let record = testee.hit(&ray, 0.001, f32::INFINITY).unwrap();
+        let (tangent, bitangent) = (record.dpdu(), record.dpdv());
+        assert!((tangent.length() - 1.).abs() < 1e-5 && (bitangent.length() - 1.).abs() < 1e-5);
+        assert!(tangent.dot(record.normal()).abs() < 1e-5);
+        assert!(bitangent.dot(record.normal()).abs() < 1e-5);
+        assert!(tangent.dot(bitangent).abs() < 1e-5);
+    }
+
+    #[test]
+    fn sdf_object_hit_from_inside() {
+        let testee = unit_sphere();
+        let ray = Ray::new(Point3::zero(), Vec3::new(0., 1., 0.), 0.);
+
+        let record = testee.hit(&ray, 0.001, f32::INFINITY).unwrap();
+        assert!((record.t() - 1.).abs() < 1e-3);
+        assert!(!record.front_face());
+    }
+
+    #[test]
+    fn sdf_object_hit_not_hitting_ray() {
+        let testee = unit_sphere();
+        let ray = Ray::new(Point3::new(3., 0., 0.), Vec3::new(0., 1., 0.), 0.);
+
+        assert!(testee.hit(&ray, 0.001, f32::INFINITY).is_none());
+    }
+}
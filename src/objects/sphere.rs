@@ -48,35 +48,10 @@ impl<M: Material> Sphere<M> {
             material,
         }
     }
-
-    /// Maps a point on the sphere to texture coordinates in range [0, 1].
-    fn texture_coordinates(p: &Point3) -> (f32, f32) {
-        // We want to map spherical coordinates to 2D texture coordinates in range [0, 1].
-        // theta is defined as the angle up from the bottom pole
-        // so theta is in range [0, PI]
-        // phi is defined as the angle around the y axis starting at -x in the direction of +z
-        // so phi is in range [0, 2*PI]
-        //
-        // So for a point (x, y, z) on the sphere we have:
-        // x = -sin(theta) * cos(phi)
-        // y = -cos(theta)
-        // z = sin(theta) * sin(phi)
-        //
-        // Thus:
-        // theta = arccos(-y)
-        // phi = arctan(z / -x)
-        let theta = f32::acos(-p.y);
-
-        // Since atan2 returns an angle in range [-PI, PI] we need to add PI
-        // in order to have phi in range [0, 2*PI].
-        let phi = f32::atan2(-p.z, p.x) + PI;
-
-        // Maps angles to range [0, 1].
-        (phi / (2. * PI), theta / PI)
-    }
 }
 
 impl<M: Material> Hitable for Sphere<M> {
+    #[allow(clippy::similar_names)]
     fn hit(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
         let oc = ray.origin() - self.center;
         let a = ray.direction().square();
@@ -108,9 +83,11 @@ impl<M: Material> Hitable for Sphere<M> {
             root,
             hit_point,
             outward_normal,
-            Self::texture_coordinates(&outward_normal),
+            texture_coordinates(&outward_normal),
             self.material.as_ref(),
         );
+        let (dpdu, dpdv) = partial_derivatives(self.radius, &outward_normal);
+        record.set_partial_derivatives(dpdu, dpdv);
         record.set_face_normal(ray);
         Some(record)
     }
@@ -123,6 +100,50 @@ impl<M: Material> Hitable for Sphere<M> {
     }
 }
 
+/// Maps a point on the unit sphere to texture coordinates in range [0, 1].
+pub(super) fn texture_coordinates(p: &Point3) -> (f32, f32) {
+    // We want to map spherical coordinates to 2D texture coordinates in range [0, 1].
+    // theta is defined as the angle up from the bottom pole
+    // so theta is in range [0, PI]
+    // phi is defined as the angle around the y axis starting at -x in the direction of +z
+    // so phi is in range [0, 2*PI]
+    //
+    // So for a point (x, y, z) on the sphere we have:
+    // x = -sin(theta) * cos(phi)
+    // y = -cos(theta)
+    // z = sin(theta) * sin(phi)
+    //
+    // Thus:
+    // theta = arccos(-y)
+    // phi = arctan(z / -x)
+    let theta = f32::acos(-p.y);
+
+    // Since atan2 returns an angle in range [-PI, PI] we need to add PI
+    // in order to have phi in range [0, 2*PI].
+    let phi = f32::atan2(-p.z, p.x) + PI;
+
+    // Maps angles to range [0, 1].
+    (phi / (2. * PI), theta / PI)
+}
+
+/// Returns the partial derivatives of a point on a sphere of radius `radius` with respect to its
+/// texture coordinates, from its outward normal.
+#[allow(clippy::similar_names)]
+pub(super) fn partial_derivatives(radius: f32, normal: &Vec3) -> (Vec3, Vec3) {
+    // Derivatives of the spherical coordinates above with respect to phi and theta
+    // scaled by the derivatives of the angles with respect to the texture coordinates
+    let sin_theta = f32::sqrt(f32::max(1. - normal.y * normal.y, 1e-12));
+    let dpdu = 2. * PI * radius * Vec3::new(normal.z, 0., -normal.x);
+    let dpdv = PI
+        * radius
+        * Vec3::new(
+            -normal.x * normal.y / sin_theta,
+            sin_theta,
+            -normal.y * normal.z / sin_theta,
+        );
+    (dpdu, dpdv)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -147,6 +168,30 @@ mod tests {
             .is_none());
     }
 
+    #[test]
+    #[allow(clippy::similar_names)]
+    fn sphere_partial_derivatives_follow_texture_coordinates() {
+        let normal = Vec3::new(0.48, 0.6, 0.64);
+        let (dpdu, dpdv) = partial_derivatives(2., &normal);
+
+        // Finite differences of the point along the texture coordinates
+        let (u, v) = texture_coordinates(&normal);
+        let point = |u: f32, v: f32| {
+            let (phi, theta) = (u * 2. * PI, v * PI);
+            2. * Vec3::new(
+                -theta.sin() * phi.cos(),
+                -theta.cos(),
+                theta.sin() * phi.sin(),
+            )
+        };
+        let h = 1e-3;
+        let expected_dpdu = (point(u + h, v) - point(u - h, v)) / (2. * h);
+        let expected_dpdv = (point(u, v + h) - point(u, v - h)) / (2. * h);
+
+        assert!((dpdu - expected_dpdu).length() < 1e-2);
+        assert!((dpdv - expected_dpdv).length() < 1e-2);
+    }
+
     #[test]
     fn sphere_bounding_box() {
         let testee = Sphere::new(Vec3::new(1., 2., 3.), 1., Arc::new(Lambertian::default()));
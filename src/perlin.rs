@@ -1,5 +1,6 @@
 use alloc::{vec, vec::Vec};
-use rand::Rng;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
 
 use crate::utils::rng;
 use crate::vec::Point3;
@@ -19,8 +20,30 @@ pub struct Perlin {
 impl Perlin {
     #[must_use]
     pub fn new() -> Self {
+        Self::from_rng(&mut rng())
+    }
+
+    /// Constructs a new `Perlin` noise generated from `seed`, so that the same seed always gives
+    /// the same noise.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::perlin::Perlin;
+    /// use crab_rt::vec::Point3;
+    ///
+    /// let p = Point3::new(0.3, 1.7, -2.1);
+    /// assert_eq!(
+    ///     Perlin::with_seed(7).noise(&p),
+    ///     Perlin::with_seed(7).noise(&p)
+    /// );
+    /// ```
+    #[must_use]
+    pub fn with_seed(seed: u64) -> Self {
+        Self::from_rng(&mut SmallRng::seed_from_u64(seed))
+    }
+
+    fn from_rng<R: Rng>(rng: &mut R) -> Self {
         const POINT_COUNT: usize = 256;
-        let mut rng = rng();
 
         let mut random_floats = vec![0.; POINT_COUNT];
         for f in &mut random_floats {
@@ -29,9 +52,9 @@ impl Perlin {
 
         Self {
             random_floats,
-            x_permutation: Self::permutation(POINT_COUNT),
-            y_permutation: Self::permutation(POINT_COUNT),
-            z_permutation: Self::permutation(POINT_COUNT),
+            x_permutation: Self::permutation(POINT_COUNT, rng),
+            y_permutation: Self::permutation(POINT_COUNT, rng),
+            z_permutation: Self::permutation(POINT_COUNT, rng),
         }
     }
 
@@ -79,9 +102,33 @@ impl Perlin {
         acc.abs()
     }
 
-    fn permutation(n: usize) -> Vec<usize> {
-        let mut rng = rng();
+    /// Returns the fractional Brownian motion (fBm) of the noise at point `p`, the sum of
+    /// `octaves` layers of noise whose frequency is multiplied by `lacunarity` and amplitude
+    /// by `gain` at each layer.
+    ///
+    /// The result is normalized to stay in range [0, 1].
+    #[must_use]
+    pub fn fbm(&self, p: &Point3, octaves: usize, lacunarity: f32, gain: f32) -> f32 {
+        let mut acc = 0.;
+        let mut total_weight = 0.;
+        let mut temp_p = *p;
+        let mut weight = 1.;
+
+        for _ in 0..octaves {
+            acc += weight * self.noise(&temp_p);
+            total_weight += weight;
+            weight *= gain;
+            temp_p *= lacunarity;
+        }
+
+        if total_weight == 0. {
+            0.
+        } else {
+            acc / total_weight
+        }
+    }
 
+    fn permutation<R: Rng>(n: usize, rng: &mut R) -> Vec<usize> {
         let mut perm: Vec<usize> = (0..n).collect();
         for i in (1..n).rev() {
             perm.swap(i, rng.gen_range(0..i));
@@ -115,7 +162,7 @@ mod tests {
     #[test]
     fn test_permutation() {
         let size = 256;
-        let permutation = Perlin::permutation(size);
+        let permutation = Perlin::permutation(size, &mut rng());
         assert_eq!(permutation.len(), size);
 
         let mut is_in_permutation = vec![false; size];
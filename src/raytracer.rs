@@ -1,21 +1,31 @@
 use rand::Rng;
 
 use crate::camera::Camera;
-use crate::hitable::Hitable;
+use crate::hitable::{HitRecord, Hitable};
+use crate::materials::Scatter;
 use crate::ray::Ray;
 use crate::scene::Scene;
-use crate::utils::{gamma_encode, rng};
+use crate::spectrum::{spectral_sample_to_rgb, WAVELENGTH_MAX, WAVELENGTH_MIN};
+use crate::utils::{gamma_encode, index_to_f32, rng};
 use crate::vec::{Color3, Vec3};
 
 #[cfg(feature = "std")]
 use {
+    crate::animation::CameraAnimation,
+    crate::stereo::StereoRig,
     crate::utils::partial_row_views_mut,
     alloc::vec,
+    alloc::vec::Vec,
+    anyhow::{bail, Context, Result},
+    core::ops::Range,
     core_affinity,
     image::{ImageBuffer, Rgb, RgbImage},
-    std::{iter::zip, println, sync::Arc, thread},
+    std::{format, iter::zip, println, thread},
 };
 
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
 const NB_THREADS: usize = 8;
 
 /// A renderer using raytracing to produce images.
@@ -26,12 +36,19 @@ pub struct RayTracer {
 
     samples: usize,
     max_reflections: usize,
+    /// Whether each sample traces a single wavelength instead of RGB colors.
+    spectral: bool,
 
     camera: Camera,
     scene: Scene,
 }
 
 impl RayTracer {
+    /// Constructs a new `RayTracer` rendering the scene seen by the camera.
+    ///
+    /// # Panics
+    /// Panics in `debug` mode if the time interval of the scene does not contain the shutter
+    /// interval of the camera, as moving objects would be missing from the images.
     #[inline]
     #[must_use]
     pub const fn new(
@@ -42,25 +59,196 @@ impl RayTracer {
         camera: Camera,
         scene: Scene,
     ) -> Self {
+        debug_assert!(
+            covers(scene.time_interval(), camera.shutter_interval()),
+            "the time interval of the scene should contain the shutter interval of the camera"
+        );
+
         Self {
             width,
             height,
             samples,
             max_reflections,
+            spectral: false,
             camera,
             scene,
         }
     }
 
+    /// Consumes the `RayTracer` and returns self with the spectral rendering mode enabled or
+    /// disabled.
+    ///
+    /// In spectral mode each sample traces a single wavelength, which makes dispersive materials
+    /// split white light into its colors. It converges slower than the RGB mode.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::camera::Camera;
+    /// use crab_rt::raytracer::RayTracer;
+    /// use crab_rt::scene::Scene;
+    ///
+    /// let raytracer =
+    ///     RayTracer::new(200, 100, 50, 20, Camera::default(), Scene::default()).spectral(true);
+    /// assert!(raytracer.is_spectral());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn spectral(self, spectral: bool) -> Self {
+        Self { spectral, ..self }
+    }
+
     #[cfg(feature = "std")]
     #[must_use]
     pub fn raytrace(self) -> RgbImage {
+        self.render()
+    }
+
+    /// Renders one image per camera, so that the scene is loaded once for several views.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use crab_rt::camera::Camera;
+    /// use crab_rt::raytracer::RayTracer;
+    /// use crab_rt::scene::Scene;
+    /// use crab_rt::vec::Point3;
+    ///
+    /// let views = [Point3::new(5., 1., 0.), Point3::new(0., 1., 5.)]
+    ///     .map(|lookfrom| Camera::new(lookfrom, Point3::zero(), 30., 2.));
+    /// let images =
+    ///     RayTracer::new(200, 100, 50, 20, Camera::default(), Scene::default()).render_views(&views);
+    /// ```
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn render_views(mut self, cameras: &[Camera]) -> Vec<RgbImage> {
+        cameras
+            .iter()
+            .map(|camera| {
+                self.camera = camera.clone();
+                self.render()
+            })
+            .collect()
+    }
+
+    /// Renders the views of the eyes of the stereo rig looking through the camera, composited
+    /// in the layout of the rig.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use crab_rt::camera::Camera;
+    /// use crab_rt::raytracer::RayTracer;
+    /// use crab_rt::scene::Scene;
+    /// use crab_rt::stereo::{StereoLayout, StereoRig};
+    ///
+    /// let rig = StereoRig::new(0.064, 2.).layout(StereoLayout::OverUnder);
+    /// let image =
+    ///     RayTracer::new(200, 100, 50, 20, Camera::default(), Scene::default()).render_stereo(&rig);
+    /// ```
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn render_stereo(self, rig: &StereoRig) -> RgbImage {
+        let cameras = rig.cameras(&self.camera);
+        let images = self.render_views(&cameras);
+        rig.composite(&images[0], &images[1])
+    }
+
+    /// Renders the frames of an animation seen by the animated camera, and saves them to files
+    /// named after `path` with its run of `#` characters replaced by the zero-padded frame
+    /// number. Without `#` characters, the frame number is inserted before the extension.
+    ///
+    /// Frame `n` shows the animation at `n / fps` seconds, with the shutter open during the
+    /// fraction `shutter` of the frame, so that moving objects are blurred. The scene should be
+    /// built with a time interval containing the frames, see [`SceneBuilder::time_interval`].
+    ///
+    /// [`SceneBuilder::time_interval`]: crate::scene::SceneBuilder::time_interval
+    ///
+    /// # Errors
+    /// Returns an error if the `#` characters of `path` are not contiguous, or if a frame cannot
+    /// be saved.
+    ///
+    /// # Panics
+    /// Panics if `fps <= 0.` or `shutter` is not in range [0, 1].
+    /// Panics if the time interval of the scene does not contain the shutter intervals of the
+    /// frames.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use crab_rt::animation::{CameraAnimation, Track};
+    /// use crab_rt::camera::Camera;
+    /// use crab_rt::raytracer::RayTracer;
+    /// use crab_rt::scene::{Background, SceneBuilder};
+    /// use crab_rt::vec::Point3;
+    ///
+    /// // A 2 second turntable around the origin
+    /// let orbit = (0..=8).fold(Track::new(0., Point3::new(5., 1., 0.)), |track, i| {
+    ///     let angle = i as f32 * std::f32::consts::FRAC_PI_4;
+    ///     track.key(
+    ///         i as f32 / 4.,
+    ///         Point3::new(5. * angle.cos(), 1., 5. * angle.sin()),
+    ///     )
+    /// });
+    /// let animation = CameraAnimation::new(
+    ///     orbit,
+    ///     Track::new(0., Point3::zero()),
+    ///     Track::new(0., 30.),
+    ///     2.,
+    /// );
+    ///
+    /// let scene = SceneBuilder::new(Background::default())
+    ///     .time_interval((0., 2.))
+    ///     .build();
+    ///
+    /// RayTracer::new(200, 100, 50, 20, Camera::default(), scene)
+    ///     .render_sequence(&animation, 0..48, 24., 0.5, "frames/turntable_####.png")
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn render_sequence(
+        mut self,
+        animation: &CameraAnimation,
+        frames: Range<usize>,
+        fps: f32,
+        shutter: f32,
+        path: &str,
+    ) -> Result<()> {
+        assert!(fps > 0., "fps should be greater than 0");
+        assert!(
+            (0. ..=1.).contains(&shutter),
+            "shutter should be in range [0, 1]"
+        );
+
+        let exposure = (
+            index_to_f32(frames.start) / fps,
+            shutter.mul_add(1. / fps, index_to_f32(frames.end.saturating_sub(1)) / fps),
+        );
+        assert!(
+            frames.is_empty() || covers(self.scene.time_interval(), exposure),
+            "the time interval of the scene should contain the shutter intervals of the frames"
+        );
+
+        let (prefix, digits, suffix) = frame_pattern(path)?;
+        for frame in frames {
+            let time = index_to_f32(frame) / fps;
+            self.camera = animation
+                .camera(time)
+                .time_interval((time, shutter.mul_add(1. / fps, time)));
+
+            let filename = format!("{prefix}{frame:0digits$}{suffix}");
+            self.render()
+                .save(&filename)
+                .with_context(|| format!("failed to save frame {filename}"))?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    fn render(&self) -> RgbImage {
         let core_ids = core_affinity::get_core_ids();
         if core_ids.is_none() {
             println!("Failed to get core ids");
         }
 
-        let raytracer = Arc::new(self);
+        let raytracer = self;
 
         let mut image_buffer =
             vec![0u8; raytracer.width() as usize * raytracer.height() as usize * 3];
@@ -72,7 +260,6 @@ impl RayTracer {
 
         thread::scope(|s| {
             for (i, mut image_buffer_view) in zip(0..NB_THREADS, image_buffer_views) {
-                let raytracer = Arc::clone(&raytracer);
                 let core_id = core_ids.as_ref().map(|ids| ids[i]);
 
                 s.spawn(move || {
@@ -108,18 +295,39 @@ impl RayTracer {
     fn pixel(&self, x: usize, y: usize) -> Color3 {
         let mut rng = rng();
         let y = self.height as usize - y - 1;
+        let (width, height) = (
+            index_to_f32(self.width as usize),
+            index_to_f32(self.height as usize),
+        );
+        let samples = index_to_f32(self.samples);
 
-        let color = (0..self.samples)
-            .map(|_| {
-                let u = (x as f32 + rng.gen::<f32>()) / self.width as f32;
-                let v = (y as f32 + rng.gen::<f32>()) / self.height as f32;
-
-                let ray = self.camera.ray(u, v);
+        // The differentials span the spacing between the samples of the pixel rather than the
+        // pixel itself, so that textures are not blurred by antialiasing
+        let spacing = f32::max(0.125, 1. / samples.sqrt());
+        let (ds, dt) = (spacing / width, spacing / height);
 
-                self.cast(&ray, 0)
+        let color = (0..self.samples)
+            .map(|i| {
+                let u = (index_to_f32(x) + rng.gen::<f32>()) / width;
+                let v = (index_to_f32(y) + rng.gen::<f32>()) / height;
+
+                // Rays blocked by the lens system of the camera bring no light
+                let Some(ray) = self.camera.ray_with_differentials(u, v, ds, dt) else {
+                    return Color3::zero();
+                };
+
+                if self.spectral {
+                    // Stratifies the wavelengths over the samples of the pixel
+                    let wavelength = ((index_to_f32(i) + rng.gen::<f32>()) / samples)
+                        .mul_add(WAVELENGTH_MAX - WAVELENGTH_MIN, WAVELENGTH_MIN);
+                    let radiance = self.cast(&ray.with_wavelength(wavelength), 0);
+                    spectral_sample_to_rgb(&radiance, wavelength)
+                } else {
+                    self.cast(&ray, 0)
+                }
             })
             .sum::<Vec3>()
-            / self.samples as f32;
+            / samples;
 
         // We gamma correct the color
         Color3::new(
@@ -136,22 +344,82 @@ impl RayTracer {
         }
 
         let record = self.scene.bvh().hit(ray, 0.001, f32::INFINITY);
-        let Some(record) = record else {
-            let unit_direction = ray.direction().unit();
-            let t = 0.5 * (unit_direction.y + 1.);
-            return self.scene.background().color(t);
+        let Some(mut record) = record else {
+            return self.scene.background().color_towards(ray.direction());
         };
 
-        let emitted = record
-            .material()
-            .emitted(record.texture_coordinates(), record.hit_point());
+        record.compute_uv_derivatives(ray);
 
-        let record = record.material().scatter(ray, &record);
-        let Some((scattered, attenuation)) = record else {
+        let emitted = record.material().emitted(ray, &record);
+
+        let Some(Scatter {
+            ray: mut scattered,
+            attenuation,
+            is_specular,
+        }) = record.material().scatter(ray, &record)
+        else {
             return emitted;
         };
+        if let Some(wavelength) = ray.wavelength() {
+            scattered = scattered.with_wavelength(wavelength);
+        }
+
+        // Only specular bounces keep a coherent footprint to track
+        if let Some(differentials) = ray.differentials() {
+            if is_specular {
+                if let Some(differentials) = differentials.scatter(ray, &record, &scattered) {
+                    scattered = scattered.with_differentials(differentials);
+                }
+            }
+        }
+
+        emitted
+            + self.direct_lighting(ray, &record)
+            + attenuation * self.cast(&scattered, depth + 1)
+    }
+
+    /// Returns the light reaching the eye from the light sources of the scene after a bounce on
+    /// the hit.
+    ///
+    /// Each light source is sampled with a shadow ray. Specular materials, which cannot scatter
+    /// towards a given direction, receive no direct lighting.
+    fn direct_lighting(&self, ray: &Ray, record: &HitRecord<'_>) -> Color3 {
+        let p = record.hit_point();
+
+        self.scene
+            .lights()
+            .iter()
+            .filter_map(|light| light.sample(p))
+            .filter_map(|sample| {
+                let shadow_ray = Ray::new(*p, sample.direction, ray.time());
+                let reflected = record.material().evaluate(ray, record, &shadow_ray);
+                if reflected.is_near_zero() {
+                    return None;
+                }
+
+                let visibility = self.visibility(&shadow_ray, sample.distance);
+                (visibility > 0.).then(|| visibility * reflected * sample.irradiance)
+            })
+            .sum()
+    }
+
+    /// Returns the fraction of the light going along the shadow ray up to `distance`, which
+    /// goes through the transparent points of the hits.
+    fn visibility(&self, shadow_ray: &Ray, distance: f32) -> f32 {
+        let t_max = distance * (1. - 1e-4);
+        let mut t_min = 0.001;
+        let mut visibility = 1.;
+
+        // Each hit moves the start of the shadow ray forward until it reaches the light
+        while let Some(record) = self.scene.bvh().hit(shadow_ray, t_min, t_max) {
+            visibility *= 1. - record.material().opacity(&record);
+            if visibility <= 0. {
+                return 0.;
+            }
+            t_min = record.t() + 0.001;
+        }
 
-        emitted + attenuation * self.cast(&scattered, depth + 1)
+        visibility
     }
 
     /// Returns the width of the rendering window.
@@ -222,6 +490,23 @@ impl RayTracer {
         self.max_reflections
     }
 
+    /// Returns whether the raytracer renders in spectral mode.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::camera::Camera;
+    /// use crab_rt::raytracer::RayTracer;
+    /// use crab_rt::scene::Scene;
+    ///
+    /// let raytracer = RayTracer::new(200, 100, 50, 20, Camera::default(), Scene::default());
+    /// assert!(!raytracer.is_spectral());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn is_spectral(&self) -> bool {
+        self.spectral
+    }
+
     /// Returns the camera of the raytracer.
     ///
     /// # Examples
@@ -256,3 +541,168 @@ impl RayTracer {
         &self.scene
     }
 }
+
+/// Returns whether the time interval `outer` contains the time interval `inner`.
+#[inline]
+const fn covers(outer: (f32, f32), inner: (f32, f32)) -> bool {
+    outer.0 <= inner.0 && inner.1 <= outer.1
+}
+
+/// Splits `path` around the place of the frame number of a sequence: its run of `#` characters,
+/// whose length is the number of digits, or else the start of its extension.
+#[cfg(feature = "std")]
+fn frame_pattern(path: &str) -> Result<(&str, usize, &str)> {
+    let Some(start) = path.find('#') else {
+        let name = path.rfind(['/', '\\']).map_or(0, |i| i + 1);
+        let extension = path[name..].rfind('.').map_or(path.len(), |i| name + i);
+        return Ok((&path[..extension], 0, &path[extension..]));
+    };
+
+    let end = path[start..]
+        .find(|c| c != '#')
+        .map_or(path.len(), |i| start + i);
+    if path[end..].contains('#') {
+        bail!("the # characters of {path} should be contiguous");
+    }
+
+    Ok((&path[..start], end - start, &path[end..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lights::PointLight;
+    use crate::materials::{BumpMap, Cutout, Lambertian, Material, Metal, Mix, NormalMap};
+    use crate::objects::{Object, XzRect};
+    use crate::scene::{Background, SceneBuilder};
+    use crate::textures::{Image, Monochrome, OpacityMask};
+    use crate::vec::Point3;
+    use alloc::sync::Arc;
+    use core::f32::consts::PI;
+
+    fn raytracer(light_height: f32) -> RayTracer {
+        let scene = SceneBuilder::new(Background::default())
+            .add_object(Object::new(XzRect::new(
+                (-1., 1.),
+                (-1., 1.),
+                0.,
+                Arc::new(Lambertian::from_rgb(1., 1., 1.)),
+            )))
+            .add_light(PointLight::new(
+                Point3::new(0., light_height, 0.),
+                Color3::new(4., 4., 4.),
+            ))
+            .build();
+
+        RayTracer::new(1, 1, 1, 1, Camera::default(), scene)
+    }
+
+    #[test]
+    fn raytracer_samples_point_lights() {
+        let ray = Ray::new(Point3::new(0., 1., 0.), Vec3::new(0., -1., 0.), 0.);
+
+        // The radiance of a white diffuse surface is its irradiance divided by pi
+        let color = raytracer(2.).cast(&ray, 0);
+        assert!((color.x - 1. / PI).abs() < 1e-5);
+
+        // Lights below the surface do not light it
+        let color = raytracer(-2.).cast(&ray, 0);
+        assert_eq!(color, Color3::zero());
+    }
+
+    #[test]
+    fn raytracer_weights_direct_lighting_of_mixed_materials() {
+        let scene = SceneBuilder::new(Background::default())
+            .add_object(Object::new(XzRect::new(
+                (-1., 1.),
+                (-1., 1.),
+                0.,
+                Arc::new(Mix::new(
+                    Lambertian::from_rgb(1., 1., 1.),
+                    Metal::new(Color3::new(0., 1., 0.), 0.5),
+                    0.5,
+                )),
+            )))
+            .add_light(PointLight::new(
+                Point3::new(0., 2., 0.),
+                Color3::new(4., 4., 4.),
+            ))
+            .build();
+        let raytracer = RayTracer::new(1, 1, 1, 1, Camera::default(), scene);
+        let ray = Ray::new(Point3::new(0., 1., 0.), Vec3::new(0., -1., 0.), 0.);
+
+        // Only the diffuse half is lit, whichever material scatters the ray
+        for _ in 0..100 {
+            let color = raytracer.cast(&ray, 0);
+            assert!((color - Color3::new(0.5, 0.5, 0.5) / PI).length() < 1e-5);
+        }
+    }
+
+    fn shade_below_occluder<M: 'static + Material>(occluder: M) -> Color3 {
+        let scene = SceneBuilder::new(Background::default())
+            .add_object(Object::new(XzRect::new(
+                (-1., 1.),
+                (-1., 1.),
+                0.,
+                Arc::new(Lambertian::from_rgb(1., 1., 1.)),
+            )))
+            .add_object(Object::new(XzRect::new(
+                (-1., 1.),
+                (-1., 1.),
+                1.,
+                Arc::new(occluder),
+            )))
+            .add_light(PointLight::new(
+                Point3::new(0., 2., 0.),
+                Color3::new(4., 4., 4.),
+            ))
+            .build();
+        let raytracer = RayTracer::new(1, 1, 1, 1, Camera::default(), scene);
+        let ray = Ray::new(Point3::new(0., 0.5, 0.), Vec3::new(0., -1., 0.), 0.);
+        raytracer.cast(&ray, 0)
+    }
+
+    fn cutout(alpha: u8) -> Cutout {
+        Cutout::new(
+            Lambertian::from_rgb(1., 1., 1.),
+            OpacityMask::alpha(Image::from_rgba(1, 1, vec![0, 0, 0, alpha])),
+        )
+    }
+
+    #[test]
+    fn raytracer_shadow_rays_go_through_cutouts() {
+        let color = shade_below_occluder(cutout(0));
+        assert!((color.x - 1. / PI).abs() < 1e-5);
+        let color = shade_below_occluder(cutout(255));
+        assert_eq!(color, Color3::zero());
+    }
+
+    #[test]
+    fn raytracer_shadow_rays_go_through_wrapped_cutouts() {
+        let normal_map = |alpha| NormalMap::new(cutout(alpha), Monochrome::from_rgb(0.5, 0.5, 1.));
+        let color = shade_below_occluder(normal_map(0));
+        assert!((color.x - 1. / PI).abs() < 1e-5);
+        let color = shade_below_occluder(normal_map(255));
+        assert_eq!(color, Color3::zero());
+
+        let bump_map = |alpha| BumpMap::new(cutout(alpha), Monochrome::from_rgb(0., 0., 0.));
+        let color = shade_below_occluder(bump_map(0));
+        assert!((color.x - 1. / PI).abs() < 1e-5);
+        let color = shade_below_occluder(bump_map(255));
+        assert_eq!(color, Color3::zero());
+    }
+
+    #[test]
+    fn raytracer_sequence_filenames() {
+        let filename = |path, frame: usize| {
+            frame_pattern(path)
+                .map(|(prefix, digits, suffix)| format!("{prefix}{frame:0digits$}{suffix}"))
+        };
+
+        assert_eq!(filename("out_####.png", 7).unwrap(), "out_0007.png");
+        assert_eq!(filename("out_##.png", 123).unwrap(), "out_123.png");
+        assert_eq!(filename("out.png", 7).unwrap(), "out7.png");
+        assert_eq!(filename("frames.v2/out", 7).unwrap(), "frames.v2/out7");
+        assert!(filename("a#b#.png", 7).is_err());
+    }
+}
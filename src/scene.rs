@@ -1,19 +1,31 @@
+use alloc::boxed::Box;
 use alloc::vec::Vec;
 
 use crate::bvh::BvhNode;
+use crate::lights::LightSource;
 use crate::materials::Material;
 use crate::objects::{Object, Sphere};
-use crate::vec::Color3;
+use crate::sky::Sky;
+use crate::vec::{Color3, Vec3};
+
+/// Time interval of the scenes built without one, which contains the usual shutter intervals of
+/// the cameras.
+const DEFAULT_TIME_INTERVAL: (f32, f32) = (0., 1.);
 
 /// A structure containing what to render.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct Scene {
     bvh: BvhNode,
     background: Background,
+    /// Light sources outside of the geometry, sampled with shadow rays.
+    lights: Vec<Box<dyn LightSource>>,
+    /// Time interval during which the bounding boxes of the moving objects contain them.
+    time_interval: (f32, f32),
 }
 
 impl Scene {
-    /// Constructs a new `Scene` containing the given objects and the given background.
+    /// Constructs a new `Scene` containing the given objects and the given background, for
+    /// cameras whose shutter is open during the time interval [0, 1].
     ///
     /// # Examples
     /// ```
@@ -21,13 +33,45 @@ impl Scene {
     #[inline]
     #[must_use]
     pub fn new(objects: Vec<Object>, background: Background) -> Self {
+        Self::with_time_interval(objects, background, DEFAULT_TIME_INTERVAL)
+    }
+
+    /// Constructs a new `Scene` whose moving objects are bounded during the given time interval.
+    fn with_time_interval(
+        objects: Vec<Object>,
+        background: Background,
+        time_interval: (f32, f32),
+    ) -> Self {
         let bvh = if objects.is_empty() {
             BvhNode::default()
         } else {
-            BvhNode::new(objects, (0., 0.1)) // TODO: time inteval
+            BvhNode::new(objects, time_interval)
         };
 
-        Self { bvh, background }
+        Self {
+            bvh,
+            background,
+            lights: Vec::new(),
+            time_interval,
+        }
+    }
+
+    /// Returns the time interval during which the moving objects of the scene can be rendered,
+    /// which should contain the shutter interval of the camera.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::scene::{Background, SceneBuilder};
+    ///
+    /// let scene = SceneBuilder::new(Background::default())
+    ///     .time_interval((0., 2.))
+    ///     .build();
+    /// assert_eq!(scene.time_interval(), (0., 2.));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn time_interval(&self) -> (f32, f32) {
+        self.time_interval
     }
 
     /// Returns the bvh of the objects present in the scene.
@@ -68,13 +112,42 @@ impl Scene {
     pub const fn background(&self) -> &Background {
         &self.background
     }
+
+    /// Returns the light sources of the scene.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::lights::PointLight;
+    /// use crab_rt::scene::{Background, SceneBuilder};
+    /// use crab_rt::vec::{Color3, Point3};
+    ///
+    /// let scene = SceneBuilder::new(Background::default())
+    ///     .add_light(PointLight::new(Point3::zero(), Color3::new(1., 1., 1.)))
+    ///     .build();
+    /// assert_eq!(scene.lights().len(), 1);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn lights(&self) -> &[Box<dyn LightSource>] {
+        &self.lights
+    }
+}
+
+impl Default for Scene {
+    /// The default scene is empty, with the default background.
+    #[inline]
+    fn default() -> Self {
+        Self::new(Vec::new(), Background::default())
+    }
 }
 
 /// A builder for `Scene`.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct SceneBuilder {
     objects: Vec<Object>,
     background: Background,
+    lights: Vec<Box<dyn LightSource>>,
+    time_interval: (f32, f32),
 }
 
 impl SceneBuilder {
@@ -97,6 +170,8 @@ impl SceneBuilder {
         Self {
             objects: Vec::new(),
             background,
+            lights: Vec::new(),
+            time_interval: DEFAULT_TIME_INTERVAL,
         }
     }
 
@@ -146,6 +221,67 @@ impl SceneBuilder {
         self.add_object(Object::new(sphere))
     }
 
+    /// Adds a light source to the `SceneBuilder`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::lights::DirectionalLight;
+    /// use crab_rt::scene::{Background, SceneBuilder};
+    /// use crab_rt::vec::{Color3, Vec3};
+    ///
+    /// let scene_builder = SceneBuilder::new(Background::Color(Vec3::zero())).add_light(
+    ///     DirectionalLight::sun(Vec3::new(1., -2., 0.), Color3::new(3., 3., 3.)),
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn add_light<L: 'static + LightSource>(mut self, light: L) -> Self {
+        self.lights.push(Box::new(light));
+
+        self
+    }
+
+    /// Sets the time interval during which the moving objects are rendered, which should contain
+    /// the shutter interval of the camera, [0, 1] by default.
+    ///
+    /// # Panics
+    /// Panics if `time_interval.0 > time_interval.1`.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use crab_rt::camera::Camera;
+    /// use crab_rt::materials::Lambertian;
+    /// use crab_rt::objects::{MovingSphere, Object};
+    /// use crab_rt::scene::{Background, SceneBuilder};
+    /// use crab_rt::vec::Point3;
+    ///
+    /// let camera = Camera::default().time_interval((2., 2.5));
+    /// let scene = SceneBuilder::new(Background::default())
+    ///     .add_object(Object::new(MovingSphere::new(
+    ///         (Point3::zero(), Point3::new(4., 0., 0.)),
+    ///         (0., 4.),
+    ///         1.,
+    ///         Arc::new(Lambertian::default()),
+    ///     )))
+    ///     .time_interval(camera.shutter_interval())
+    ///     .build();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn time_interval(self, time_interval: (f32, f32)) -> Self {
+        assert!(
+            time_interval.0 <= time_interval.1,
+            "the time interval should not end before its start"
+        );
+
+        Self {
+            time_interval,
+            ..self
+        }
+    }
+
     /// Consumes the `SceneBuilder` to build a `Scene`.
     ///
     /// # Examples
@@ -158,7 +294,26 @@ impl SceneBuilder {
     #[inline]
     #[must_use]
     pub fn build(self) -> Scene {
-        Scene::new(self.objects, self.background)
+        Scene {
+            lights: self.lights,
+            ..Scene::with_time_interval(self.objects, self.background, self.time_interval)
+        }
+    }
+}
+
+impl Default for SceneBuilder {
+    /// The default builder is empty, with the default background and time interval.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::scene::SceneBuilder;
+    ///
+    /// let scene = SceneBuilder::default().build();
+    /// assert_eq!(scene.time_interval(), (0., 1.));
+    /// ```
+    #[inline]
+    fn default() -> Self {
+        Self::new(Background::default())
     }
 }
 
@@ -166,14 +321,42 @@ impl SceneBuilder {
 pub enum Background {
     Color(Color3),
     Gradient(Color3, Color3),
+    /// A physically based daylight sky, to be lit by its [`Sky::sun`].
+    Sky(Sky),
 }
 
 impl Background {
+    /// Returns the color of the background at height `t` in range [0, 1], from the bottom to the
+    /// top of the background.
+    ///
+    /// The sky has no color for a height only, its color is the one towards the zenith.
     #[must_use]
     pub fn color(&self, t: f32) -> Color3 {
         match self {
             Self::Color(c) => *c,
             Self::Gradient(c1, c2) => t * c1 + (1. - t) * c2,
+            Self::Sky(sky) => sky.radiance(&Vec3::new(0., 1., 0.)),
+        }
+    }
+
+    /// Returns the color of the background seen in the given direction.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::scene::Background;
+    /// use crab_rt::vec::{Color3, Vec3};
+    ///
+    /// let background = Background::Gradient(Color3::new(1., 1., 1.), Color3::zero());
+    /// assert_eq!(
+    ///     background.color_towards(&Vec3::new(0., 2., 0.)),
+    ///     Color3::new(1., 1., 1.)
+    /// );
+    /// ```
+    #[must_use]
+    pub fn color_towards(&self, direction: &Vec3) -> Color3 {
+        match self {
+            Self::Sky(sky) => sky.radiance(direction),
+            _ => self.color(direction.unit().y.midpoint(1.)),
         }
     }
 }
@@ -0,0 +1,736 @@
+//! Signed distance functions (SDF) to be rendered with [`SdfObject`](crate::objects::SdfObject).
+//!
+//! A signed distance function returns, for any point in space, the distance to the closest
+//! point of the surface it describes. The distance is negative inside the surface.
+//!
+//! Combinators such as [`SmoothUnion`] or [`Twist`] do not always return an exact distance but a
+//! bound of it, see [`SdfObject::step_scale`](crate::objects::SdfObject::step_scale).
+use alloc::boxed::Box;
+use core::fmt::{self, Debug};
+
+use crate::vec::{Point3, Vec3};
+
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
+pub trait Sdf: Debug + Send + Sync {
+    /// Returns the signed distance of the point `p` to the surface.
+    #[must_use]
+    fn distance(&self, p: &Point3) -> f32;
+}
+
+/// A user-supplied distance function.
+pub struct Function<F>
+where
+    F: Fn(&Point3) -> f32 + Send + Sync,
+{
+    function: F,
+}
+
+impl<F> Function<F>
+where
+    F: Fn(&Point3) -> f32 + Send + Sync,
+{
+    /// Constructs a new `Function` from the given closure.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::sdf::{Function, Sdf};
+    /// use crab_rt::vec::Point3;
+    ///
+    /// // A unit sphere
+    /// let sdf = Function::new(|p: &Point3| p.length() - 1.);
+    /// assert_eq!(sdf.distance(&Point3::new(2., 0., 0.)), 1.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new(function: F) -> Self {
+        Self { function }
+    }
+}
+
+impl<F> Sdf for Function<F>
+where
+    F: Fn(&Point3) -> f32 + Send + Sync,
+{
+    #[inline]
+    fn distance(&self, p: &Point3) -> f32 {
+        (self.function)(p)
+    }
+}
+
+impl<F> Debug for Function<F>
+where
+    F: Fn(&Point3) -> f32 + Send + Sync,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Function").finish_non_exhaustive()
+    }
+}
+
+/// A sphere.
+#[derive(Debug, Clone)]
+pub struct Sphere {
+    center: Point3,
+    radius: f32,
+}
+
+impl Sphere {
+    /// Constructs a new `Sphere` with the given center and radius.
+    ///
+    /// # Panics
+    /// Panics if `radius <= 0.`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::sdf::{Sdf, Sphere};
+    /// use crab_rt::vec::Point3;
+    ///
+    /// let sdf = Sphere::new(Point3::zero(), 1.);
+    /// assert_eq!(sdf.distance(&Point3::new(0., 3., 0.)), 2.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(center: Point3, radius: f32) -> Self {
+        assert!(radius > 0., "radius should be greater than 0");
+
+        Self { center, radius }
+    }
+}
+
+impl Sdf for Sphere {
+    #[inline]
+    fn distance(&self, p: &Point3) -> f32 {
+        (p - self.center).length() - self.radius
+    }
+}
+
+/// An axis-aligned box with optionally rounded edges.
+#[derive(Debug, Clone)]
+pub struct Cuboid {
+    center: Point3,
+    half_extents: Vec3,
+    rounding: f32,
+}
+
+impl Cuboid {
+    /// Constructs a new `Cuboid` with the given center and half extents.
+    ///
+    /// # Panics
+    /// Panics if one of the half extents is negative.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::sdf::{Cuboid, Sdf};
+    /// use crab_rt::vec::{Point3, Vec3};
+    ///
+    /// let sdf = Cuboid::new(Point3::zero(), Vec3::new(1., 1., 1.));
+    /// assert_eq!(sdf.distance(&Point3::new(3., 0., 0.)), 2.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(center: Point3, half_extents: Vec3) -> Self {
+        assert!(
+            half_extents.x >= 0. && half_extents.y >= 0. && half_extents.z >= 0.,
+            "half_extents should not be negative"
+        );
+
+        Self {
+            center,
+            half_extents,
+            rounding: 0.,
+        }
+    }
+
+    /// Consumes the `Cuboid` and returns self after setting the radius of its rounded edges.
+    ///
+    /// The rounding is taken from the inside so the overall size of the box is preserved.
+    ///
+    /// # Panics
+    /// Panics if `rounding < 0.`.
+    #[inline]
+    #[must_use]
+    pub fn rounding(self, rounding: f32) -> Self {
+        assert!(rounding >= 0., "rounding should not be negative");
+
+        Self { rounding, ..self }
+    }
+}
+
+impl Sdf for Cuboid {
+    fn distance(&self, p: &Point3) -> f32 {
+        let rounding = Vec3::new(self.rounding, self.rounding, self.rounding);
+        let q = (p - self.center).abs() - self.half_extents + rounding;
+
+        q.max(&Vec3::zero()).length() + f32::min(f32::max(q.x, f32::max(q.y, q.z)), 0.)
+            - self.rounding
+    }
+}
+
+/// A torus lying in the xz plane.
+#[derive(Debug, Clone)]
+pub struct Torus {
+    center: Point3,
+    major_radius: f32,
+    minor_radius: f32,
+}
+
+impl Torus {
+    /// Constructs a new `Torus` with the given center, distance from the center to the tube
+    /// and radius of the tube.
+    ///
+    /// # Panics
+    /// Panics if `major_radius <= 0.` or `minor_radius <= 0.`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::sdf::{Sdf, Torus};
+    /// use crab_rt::vec::Point3;
+    ///
+    /// let sdf = Torus::new(Point3::zero(), 1., 0.25);
+    /// assert_eq!(sdf.distance(&Point3::new(1., 0., 0.)), -0.25);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(center: Point3, major_radius: f32, minor_radius: f32) -> Self {
+        assert!(
+            major_radius > 0. && minor_radius > 0.,
+            "major_radius and minor_radius should be greater than 0"
+        );
+
+        Self {
+            center,
+            major_radius,
+            minor_radius,
+        }
+    }
+}
+
+impl Sdf for Torus {
+    fn distance(&self, p: &Point3) -> f32 {
+        let p = p - self.center;
+        let q_x = f32::hypot(p.x, p.z) - self.major_radius;
+
+        f32::hypot(q_x, p.y) - self.minor_radius
+    }
+}
+
+/// A segment with a thickness.
+#[derive(Debug, Clone)]
+pub struct Capsule {
+    a: Point3,
+    b: Point3,
+    radius: f32,
+}
+
+impl Capsule {
+    /// Constructs a new `Capsule` around the segment `[a, b]` with the given radius.
+    ///
+    /// # Panics
+    /// Panics if `radius <= 0.`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::sdf::{Capsule, Sdf};
+    /// use crab_rt::vec::Point3;
+    ///
+    /// let sdf = Capsule::new(Point3::zero(), Point3::new(0., 2., 0.), 0.5);
+    /// assert_eq!(sdf.distance(&Point3::new(1., 1., 0.)), 0.5);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(a: Point3, b: Point3, radius: f32) -> Self {
+        assert!(radius > 0., "radius should be greater than 0");
+
+        Self { a, b, radius }
+    }
+}
+
+impl Sdf for Capsule {
+    fn distance(&self, p: &Point3) -> f32 {
+        let pa = p - self.a;
+        let ba = self.b - self.a;
+        let h = if ba.is_zero() {
+            0.
+        } else {
+            (pa.dot(&ba) / ba.square()).clamp(0., 1.)
+        };
+
+        (pa - h * ba).length() - self.radius
+    }
+}
+
+/// An infinite plane.
+#[derive(Debug, Clone)]
+pub struct Plane {
+    normal: Vec3,
+    offset: f32,
+}
+
+impl Plane {
+    /// Constructs a new `Plane` with the given normal, at the given signed distance
+    /// from the origin along the normal.
+    ///
+    /// # Panics
+    /// Panics if `normal == Vec3::new(0., 0., 0.)`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::sdf::{Plane, Sdf};
+    /// use crab_rt::vec::{Point3, Vec3};
+    ///
+    /// let sdf = Plane::new(Vec3::new(0., 1., 0.), -1.);
+    /// assert_eq!(sdf.distance(&Point3::new(0., 1., 0.)), 2.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(normal: Vec3, offset: f32) -> Self {
+        assert!(!normal.is_zero(), "normal should not be zero");
+
+        Self {
+            normal: normal.unit(),
+            offset,
+        }
+    }
+}
+
+impl Sdf for Plane {
+    #[inline]
+    fn distance(&self, p: &Point3) -> f32 {
+        p.dot(&self.normal) - self.offset
+    }
+}
+
+/// The [Mandelbulb](https://en.wikipedia.org/wiki/Mandelbulb) fractal centered at the origin.
+///
+/// The returned distance is an estimation so the [`SdfObject`](crate::objects::SdfObject)
+/// rendering it should use a [`step_scale`](crate::objects::SdfObject::step_scale) lower than 1.
+#[derive(Debug, Clone)]
+pub struct Mandelbulb {
+    power: f32,
+    iterations: usize,
+}
+
+impl Mandelbulb {
+    /// Constructs a new `Mandelbulb` with the given power and number of iterations.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::sdf::Mandelbulb;
+    ///
+    /// // The classic power 8 Mandelbulb
+    /// let sdf = Mandelbulb::new(8., 10);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new(power: f32, iterations: usize) -> Self {
+        Self { power, iterations }
+    }
+}
+
+impl Sdf for Mandelbulb {
+    fn distance(&self, p: &Point3) -> f32 {
+        const BAILOUT: f32 = 2.;
+
+        let mut z = *p;
+        let mut dr = 1.;
+        let mut r = 0.;
+
+        for _ in 0..self.iterations {
+            r = z.length();
+            if r > BAILOUT {
+                break;
+            }
+
+            // Converts to polar coordinates
+            let theta = f32::acos((z.z / r).clamp(-1., 1.)) * self.power;
+            let phi = f32::atan2(z.y, z.x) * self.power;
+            dr = r.powf(self.power - 1.) * self.power * dr + 1.;
+
+            // Scales, rotates and converts back to cartesian coordinates
+            let zr = r.powf(self.power);
+            z =
+                zr * Vec3::new(
+                    theta.sin() * phi.cos(),
+                    phi.sin() * theta.sin(),
+                    theta.cos(),
+                ) + p;
+        }
+
+        if r == 0. {
+            return 0.;
+        }
+
+        0.5 * r.ln() * r / dr
+    }
+}
+
+/// The union of two SDF.
+#[derive(Debug)]
+pub struct Union {
+    a: Box<dyn Sdf>,
+    b: Box<dyn Sdf>,
+}
+
+impl Union {
+    /// Constructs a new `Union` of `a` and `b`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::sdf::{Sdf, Sphere, Union};
+    /// use crab_rt::vec::Point3;
+    ///
+    /// let sdf = Union::new(
+    ///     Sphere::new(Point3::new(-1., 0., 0.), 0.5),
+    ///     Sphere::new(Point3::new(1., 0., 0.), 0.5),
+    /// );
+    /// assert_eq!(sdf.distance(&Point3::zero()), 0.5);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new<A, B>(a: A, b: B) -> Self
+    where
+        A: 'static + Sdf,
+        B: 'static + Sdf,
+    {
+        Self {
+            a: Box::new(a),
+            b: Box::new(b),
+        }
+    }
+}
+
+impl Sdf for Union {
+    #[inline]
+    fn distance(&self, p: &Point3) -> f32 {
+        f32::min(self.a.distance(p), self.b.distance(p))
+    }
+}
+
+/// The intersection of two SDF.
+#[derive(Debug)]
+pub struct Intersection {
+    a: Box<dyn Sdf>,
+    b: Box<dyn Sdf>,
+}
+
+impl Intersection {
+    /// Constructs a new `Intersection` of `a` and `b`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::sdf::{Intersection, Sdf, Sphere};
+    /// use crab_rt::vec::Point3;
+    ///
+    /// // A lens shaped by two overlapping spheres
+    /// let sdf = Intersection::new(
+    ///     Sphere::new(Point3::new(-0.5, 0., 0.), 1.),
+    ///     Sphere::new(Point3::new(0.5, 0., 0.), 1.),
+    /// );
+    /// assert_eq!(sdf.distance(&Point3::zero()), -0.5);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new<A, B>(a: A, b: B) -> Self
+    where
+        A: 'static + Sdf,
+        B: 'static + Sdf,
+    {
+        Self {
+            a: Box::new(a),
+            b: Box::new(b),
+        }
+    }
+}
+
+impl Sdf for Intersection {
+    #[inline]
+    fn distance(&self, p: &Point3) -> f32 {
+        f32::max(self.a.distance(p), self.b.distance(p))
+    }
+}
+
+/// The SDF `a` from which the SDF `b` is carved out.
+#[derive(Debug)]
+pub struct Subtraction {
+    a: Box<dyn Sdf>,
+    b: Box<dyn Sdf>,
+}
+
+impl Subtraction {
+    /// Constructs a new `Subtraction` of `b` from `a`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::sdf::{Sdf, Sphere, Subtraction};
+    /// use crab_rt::vec::Point3;
+    ///
+    /// // A sphere with a bite taken out of it
+    /// let sdf = Subtraction::new(
+    ///     Sphere::new(Point3::zero(), 1.),
+    ///     Sphere::new(Point3::new(1., 0., 0.), 0.5),
+    /// );
+    /// assert_eq!(sdf.distance(&Point3::new(1., 0., 0.)), 0.5);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new<A, B>(a: A, b: B) -> Self
+    where
+        A: 'static + Sdf,
+        B: 'static + Sdf,
+    {
+        Self {
+            a: Box::new(a),
+            b: Box::new(b),
+        }
+    }
+}
+
+impl Sdf for Subtraction {
+    #[inline]
+    fn distance(&self, p: &Point3) -> f32 {
+        f32::max(self.a.distance(p), -self.b.distance(p))
+    }
+}
+
+/// The union of two SDF blended together over a distance `k`.
+#[derive(Debug)]
+pub struct SmoothUnion {
+    a: Box<dyn Sdf>,
+    b: Box<dyn Sdf>,
+    k: f32,
+}
+
+impl SmoothUnion {
+    /// Constructs a new `SmoothUnion` of `a` and `b` with the given blending distance.
+    ///
+    /// # Panics
+    /// Panics if `k <= 0.`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::sdf::{SmoothUnion, Sphere};
+    /// use crab_rt::vec::Point3;
+    ///
+    /// let blob = SmoothUnion::new(
+    ///     Sphere::new(Point3::new(-0.5, 0., 0.), 0.6),
+    ///     Sphere::new(Point3::new(0.5, 0., 0.), 0.6),
+    ///     0.3,
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new<A, B>(a: A, b: B, k: f32) -> Self
+    where
+        A: 'static + Sdf,
+        B: 'static + Sdf,
+    {
+        assert!(k > 0., "k should be greater than 0");
+
+        Self {
+            a: Box::new(a),
+            b: Box::new(b),
+            k,
+        }
+    }
+}
+
+impl Sdf for SmoothUnion {
+    fn distance(&self, p: &Point3) -> f32 {
+        let d1 = self.a.distance(p);
+        let d2 = self.b.distance(p);
+
+        // Polynomial smooth minimum
+        let h = (0.5 + 0.5 * (d2 - d1) / self.k).clamp(0., 1.);
+        lerp(d2, d1, h) - self.k * h * (1. - h)
+    }
+}
+
+/// The SDF `a` from which the SDF `b` is carved out with edges blended over a distance `k`.
+#[derive(Debug)]
+pub struct SmoothSubtraction {
+    a: Box<dyn Sdf>,
+    b: Box<dyn Sdf>,
+    k: f32,
+}
+
+impl SmoothSubtraction {
+    /// Constructs a new `SmoothSubtraction` of `b` from `a` with the given blending distance.
+    ///
+    /// # Panics
+    /// Panics if `k <= 0.`.
+    #[inline]
+    #[must_use]
+    pub fn new<A, B>(a: A, b: B, k: f32) -> Self
+    where
+        A: 'static + Sdf,
+        B: 'static + Sdf,
+    {
+        assert!(k > 0., "k should be greater than 0");
+
+        Self {
+            a: Box::new(a),
+            b: Box::new(b),
+            k,
+        }
+    }
+}
+
+impl Sdf for SmoothSubtraction {
+    fn distance(&self, p: &Point3) -> f32 {
+        let d1 = self.a.distance(p);
+        let d2 = self.b.distance(p);
+
+        let h = (0.5 - f32::midpoint(d2, d1) / self.k).clamp(0., 1.);
+        lerp(d1, -d2, h) + self.k * h * (1. - h)
+    }
+}
+
+/// An SDF twisted around the y axis by `k` radians per unit of height.
+///
+/// The twist stretches space so the [`SdfObject`](crate::objects::SdfObject) rendering it
+/// should use a [`step_scale`](crate::objects::SdfObject::step_scale) lower than 1.
+#[derive(Debug)]
+pub struct Twist {
+    sdf: Box<dyn Sdf>,
+    k: f32,
+}
+
+impl Twist {
+    /// Constructs a new `Twist` of the given SDF by `k` radians per unit of height.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::sdf::{Cuboid, Twist};
+    /// use crab_rt::vec::{Point3, Vec3};
+    ///
+    /// // A bar making a quarter turn over its height
+    /// let sdf = Twist::new(
+    ///     Cuboid::new(Point3::zero(), Vec3::new(0.5, 1., 0.1)),
+    ///     std::f32::consts::FRAC_PI_4,
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new<S: 'static + Sdf>(sdf: S, k: f32) -> Self {
+        Self {
+            sdf: Box::new(sdf),
+            k,
+        }
+    }
+}
+
+impl Sdf for Twist {
+    fn distance(&self, p: &Point3) -> f32 {
+        let (sin, cos) = f32::sin_cos(self.k * p.y);
+        let q = Point3::new(cos * p.x - sin * p.z, p.y, sin * p.x + cos * p.z);
+
+        self.sdf.distance(&q)
+    }
+}
+
+/// An SDF infinitely repeated along the axes with the given period.
+///
+/// A period of 0 on an axis disables the repetition along that axis.
+#[derive(Debug)]
+pub struct Repeat {
+    sdf: Box<dyn Sdf>,
+    period: Vec3,
+}
+
+impl Repeat {
+    /// Constructs a new `Repeat` of the given SDF.
+    ///
+    /// # Panics
+    /// Panics if one of the period coordinates is negative.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::sdf::{Repeat, Sdf, Sphere};
+    /// use crab_rt::vec::{Point3, Vec3};
+    ///
+    /// // Spheres every 2 units along the x and z axes
+    /// let sdf = Repeat::new(Sphere::new(Point3::zero(), 0.5), Vec3::new(2., 0., 2.));
+    /// assert_eq!(sdf.distance(&Point3::new(4., 0., 0.)), -0.5);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new<S: 'static + Sdf>(sdf: S, period: Vec3) -> Self {
+        assert!(
+            period.x >= 0. && period.y >= 0. && period.z >= 0.,
+            "period should not be negative"
+        );
+
+        Self {
+            sdf: Box::new(sdf),
+            period,
+        }
+    }
+}
+
+impl Sdf for Repeat {
+    fn distance(&self, p: &Point3) -> f32 {
+        let repeat = |x: f32, period: f32| {
+            if period == 0. {
+                x
+            } else {
+                x - period * (x / period).round()
+            }
+        };
+
+        self.sdf.distance(&Point3::new(
+            repeat(p.x, self.period.x),
+            repeat(p.y, self.period.y),
+            repeat(p.z, self.period.z),
+        ))
+    }
+}
+
+#[inline]
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    t.mul_add(b - a, a)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn smooth_union_is_below_union() {
+        let a = Sphere::new(Point3::new(-0.5, 0., 0.), 0.6);
+        let b = Sphere::new(Point3::new(0.5, 0., 0.), 0.6);
+        let p = Point3::new(0., 0.7, 0.);
+        let union = f32::min(a.distance(&p), b.distance(&p));
+
+        let testee = SmoothUnion::new(a, b, 0.3);
+        assert!(testee.distance(&p) < union);
+    }
+
+    #[test]
+    fn smooth_union_far_from_blend() {
+        let a = Sphere::new(Point3::new(-10., 0., 0.), 1.);
+        let b = Sphere::new(Point3::new(10., 0., 0.), 1.);
+        let p = Point3::new(-10., 2., 0.);
+
+        let testee = SmoothUnion::new(a, b, 0.3);
+        assert_eq!(testee.distance(&p), 1.);
+    }
+
+    #[test]
+    fn subtraction_carves_hole() {
+        let testee = Subtraction::new(
+            Sphere::new(Point3::zero(), 1.),
+            Sphere::new(Point3::zero(), 0.5),
+        );
+
+        assert_eq!(testee.distance(&Point3::zero()), 0.5);
+        assert_eq!(testee.distance(&Point3::new(0.75, 0., 0.)), -0.25);
+    }
+
+    #[test]
+    fn twist_keeps_axis() {
+        let testee = Twist::new(Cuboid::new(Point3::zero(), Vec3::new(1., 1., 1.)), 1.);
+
+        assert_eq!(testee.distance(&Point3::new(0., 0.5, 0.)), -0.5);
+    }
+}
@@ -0,0 +1,290 @@
+use core::f32::consts::{FRAC_PI_2, PI};
+
+use crate::lights::DirectionalLight;
+use crate::spectrum::xyz_to_rgb;
+use crate::vec::{Color3, Vec3};
+
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
+/// Radiance of the sky per kcd/m² of luminance of the Preetham model, which brings a clear sky
+/// close to the brightness of the other backgrounds.
+const SKY_SCALE: f32 = 0.05;
+
+/// Illuminance of the sun outside of the atmosphere, in klx.
+const SUN_ILLUMINANCE: f32 = 128.;
+
+/// Wavelengths of the red, green and blue components of the sunlight, in µm.
+const RGB_WAVELENGTHS: [f32; 3] = [0.68, 0.55, 0.44];
+
+/// Number of zenith and azimuth steps of the integration of the sky irradiance on the ground.
+const GROUND_STEPS: (u16, u16) = (16, 32);
+
+/// A clear daylight sky following the analytic model of Preetham et al., "A Practical Analytic
+/// Model for Daylight" (1999).
+///
+/// The sun itself is not part of the sky, [`Sky::sun`] gives the matching light to add to the
+/// scene. Below the horizon, the sky shows a ground reflecting the light of the sky and the sun.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sky {
+    /// Unit direction towards the sun.
+    sun_direction: Vec3,
+    turbidity: f32,
+    ground_albedo: Color3,
+    /// Coefficients of the Perez distribution of the luminance and of the x and y chromaticities.
+    perez: [[f32; 5]; 3],
+    /// Luminance and chromaticities at the zenith.
+    zenith: [f32; 3],
+    /// Radiance of the ground.
+    ground: Color3,
+}
+
+impl Sky {
+    /// Constructs a new `Sky` lit by the sun in `sun_direction`, the y axis pointing to the
+    /// zenith, with the given turbidity, from 2 for a very clear sky to 10 for a hazy one.
+    ///
+    /// The ground is grey, with an albedo of 0.3.
+    ///
+    /// # Panics
+    /// Panics if `turbidity` is less than 1.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::scene::{Background, SceneBuilder};
+    /// use crab_rt::sky::Sky;
+    /// use crab_rt::vec::Vec3;
+    ///
+    /// let sky = Sky::new(Vec3::new(1., 1., 0.5), 3.);
+    /// let scene = SceneBuilder::new(Background::Sky(sky))
+    ///     .add_light(sky.sun())
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn new(sun_direction: Vec3, turbidity: f32) -> Self {
+        assert!(turbidity >= 1., "turbidity should be at least 1");
+
+        let sun_direction = sun_direction.unit();
+        // The model is only valid for a sun above the horizon
+        let theta_sun = f32::acos(sun_direction.y.clamp(0., 1.));
+        let t = turbidity;
+
+        let perez = [
+            [
+                0.1787f32.mul_add(t, -1.4630),
+                (-0.3554f32).mul_add(t, 0.4275),
+                (-0.0227f32).mul_add(t, 5.3251),
+                0.1206f32.mul_add(t, -2.5771),
+                (-0.0670f32).mul_add(t, 0.3703),
+            ],
+            [
+                (-0.0193f32).mul_add(t, -0.2592),
+                (-0.0665f32).mul_add(t, 0.0008),
+                (-0.0004f32).mul_add(t, 0.2125),
+                (-0.0641f32).mul_add(t, -0.8989),
+                (-0.0033f32).mul_add(t, 0.0452),
+            ],
+            [
+                (-0.0167f32).mul_add(t, -0.2608),
+                (-0.0950f32).mul_add(t, 0.0092),
+                (-0.0079f32).mul_add(t, 0.2102),
+                (-0.0441f32).mul_add(t, -1.6537),
+                (-0.0109f32).mul_add(t, 0.0529),
+            ],
+        ];
+
+        let chi = (4. / 9. - t / 120.) * 2f32.mul_add(-theta_sun, PI);
+        let zenith_luminance =
+            4.0453f32.mul_add(t, -4.9710) * chi.tan() + (-0.2155f32).mul_add(t, 2.4192);
+        let chromaticity = |coefficients: [[f32; 4]; 3]| {
+            let polynomial = |c: [f32; 4]| {
+                c[0].mul_add(theta_sun, c[1])
+                    .mul_add(theta_sun, c[2])
+                    .mul_add(theta_sun, c[3])
+            };
+            (t * t).mul_add(
+                polynomial(coefficients[0]),
+                t.mul_add(polynomial(coefficients[1]), polynomial(coefficients[2])),
+            )
+        };
+        let zenith = [
+            zenith_luminance,
+            chromaticity([
+                [0.00166, -0.00375, 0.00209, 0.],
+                [-0.02903, 0.06377, -0.03202, 0.00394],
+                [0.11693, -0.21196, 0.06052, 0.25886],
+            ]),
+            chromaticity([
+                [0.00275, -0.00610, 0.00317, 0.],
+                [-0.04214, 0.08970, -0.04153, 0.00516],
+                [0.15346, -0.26756, 0.06670, 0.26688],
+            ]),
+        ];
+
+        let sky = Self {
+            sun_direction,
+            turbidity,
+            ground_albedo: Color3::new(0.3, 0.3, 0.3),
+            perez,
+            zenith,
+            ground: Color3::zero(),
+        };
+        sky.ground_albedo(sky.ground_albedo)
+    }
+
+    /// Consumes the `Sky` and returns self with the given albedo of the ground below the
+    /// horizon.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::sky::Sky;
+    /// use crab_rt::vec::{Color3, Vec3};
+    ///
+    /// let sky = Sky::new(Vec3::new(1., 1., 0.5), 3.).ground_albedo(Color3::new(0.2, 0.25, 0.1));
+    /// ```
+    #[must_use]
+    pub fn ground_albedo(self, ground_albedo: Color3) -> Self {
+        // The ground is diffuse and lit by the sky and the sun
+        let irradiance =
+            self.sky_irradiance() + self.sun_direction.y.max(0.) * self.sun_irradiance();
+        Self {
+            ground_albedo,
+            ground: ground_albedo * irradiance / PI,
+            ..self
+        }
+    }
+
+    /// Returns the sun matching the sky, as a light with the angular diameter of the sun whose
+    /// color is reddened by the atmosphere.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::sky::Sky;
+    /// use crab_rt::vec::Vec3;
+    ///
+    /// let sun = Sky::new(Vec3::new(1., 1., 0.5), 3.).sun();
+    /// ```
+    #[must_use]
+    pub fn sun(&self) -> DirectionalLight {
+        DirectionalLight::sun(-self.sun_direction, self.sun_irradiance())
+    }
+
+    /// Returns the radiance of the sky in the given direction.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::sky::Sky;
+    /// use crab_rt::vec::Vec3;
+    ///
+    /// let sky = Sky::new(Vec3::new(1., 1., 0.), 3.);
+    ///
+    /// // A clear sky is blue
+    /// let zenith = sky.radiance(&Vec3::new(0., 1., 0.));
+    /// assert!(zenith.z > zenith.x);
+    /// ```
+    #[allow(clippy::many_single_char_names)]
+    #[must_use]
+    pub fn radiance(&self, direction: &Vec3) -> Color3 {
+        let direction = direction.unit();
+        if direction.y < 0. {
+            return self.ground;
+        }
+
+        // Avoids the singularity of the model at the horizon
+        let cos_theta = direction.y.max(0.01);
+        let cos_gamma = direction.dot(&self.sun_direction).clamp(-1., 1.);
+        let gamma = cos_gamma.acos();
+        let theta_sun = f32::acos(self.sun_direction.y.clamp(0., 1.));
+
+        let [luminance, x, y] = [0, 1, 2].map(|i| {
+            let perez = |cos_theta: f32, gamma: f32, cos_gamma: f32| {
+                let [a, b, c, d, e] = self.perez[i];
+                a.mul_add(f32::exp(b / cos_theta), 1.)
+                    * c.mul_add(f32::exp(d * gamma), e.mul_add(cos_gamma * cos_gamma, 1.))
+            };
+            self.zenith[i] * perez(cos_theta, gamma, cos_gamma)
+                / perez(1., theta_sun, self.sun_direction.y.clamp(0., 1.))
+        });
+
+        let xyz = Vec3::new(x / y * luminance, luminance, (1. - x - y) / y * luminance);
+        SKY_SCALE * xyz_to_rgb(&xyz).max(&Vec3::zero())
+    }
+
+    /// Returns the irradiance of the sun on a surface facing it, after its travel through the
+    /// atmosphere.
+    fn sun_irradiance(&self) -> Color3 {
+        if self.sun_direction.y <= 0. {
+            return Color3::zero();
+        }
+
+        // Relative optical mass of the air on the path of the sunlight
+        let zenith_degrees = f32::acos(self.sun_direction.y).to_degrees();
+        let mass = 1. / (self.sun_direction.y + 0.15 * f32::powf(93.885 - zenith_degrees, -1.253));
+
+        // Rayleigh scattering by the air and Angstrom scattering by the aerosols
+        let beta = 0.04608f32.mul_add(self.turbidity, -0.04586);
+        let [r, g, b] = RGB_WAVELENGTHS.map(|wavelength| {
+            let rayleigh = f32::exp(-0.008_735 * wavelength.powf(-4.08) * mass);
+            let aerosols = f32::exp(-beta * wavelength.powf(-1.3) * mass);
+            rayleigh * aerosols
+        });
+
+        SKY_SCALE * SUN_ILLUMINANCE * Color3::new(r, g, b)
+    }
+
+    /// Returns the irradiance of the sky on the ground, integrating its radiance over the upper
+    /// hemisphere.
+    fn sky_irradiance(&self) -> Color3 {
+        let (theta_steps, phi_steps) = GROUND_STEPS;
+        let d_theta = FRAC_PI_2 / f32::from(theta_steps);
+        let d_phi = 2. * PI / f32::from(phi_steps);
+
+        let mut irradiance = Color3::zero();
+        for i in 0..theta_steps {
+            let theta = (f32::from(i) + 0.5) * d_theta;
+            for j in 0..phi_steps {
+                let phi = (f32::from(j) + 0.5) * d_phi;
+                let direction = Vec3::new(
+                    theta.sin() * phi.cos(),
+                    theta.cos(),
+                    theta.sin() * phi.sin(),
+                );
+                irradiance +=
+                    (theta.cos() * theta.sin() * d_theta * d_phi) * self.radiance(&direction);
+            }
+        }
+
+        irradiance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sky_is_brighter_around_the_sun() {
+        let testee = Sky::new(Vec3::new(1., 1., 0.), 3.);
+
+        let towards_sun = testee.radiance(&Vec3::new(1., 1.1, 0.));
+        let opposite = testee.radiance(&Vec3::new(-1., 1.1, 0.));
+        assert!(towards_sun.y > opposite.y);
+    }
+
+    #[test]
+    fn sun_reddens_near_the_horizon() {
+        let high = Sky::new(Vec3::new(0.2, 1., 0.), 3.).sun_irradiance();
+        let low = Sky::new(Vec3::new(1., 0.05, 0.), 3.).sun_irradiance();
+
+        assert!(low.x / low.z > high.x / high.z);
+        assert!(low.y < high.y);
+    }
+
+    #[test]
+    fn ground_reflects_its_albedo() {
+        let testee = Sky::new(Vec3::new(1., 1., 0.), 3.);
+        let black = testee.ground_albedo(Color3::zero());
+
+        assert!(testee.radiance(&Vec3::new(0., -1., 0.)).y > 0.);
+        assert_eq!(black.radiance(&Vec3::new(0., -1., 0.)), Color3::zero());
+    }
+}
@@ -0,0 +1,176 @@
+//! Conversions between RGB colors and spectral radiance used by the spectral rendering mode.
+//!
+//! In spectral mode each camera path carries a single wavelength. The RGB colors of textures and
+//! emitters are upsampled to spectra made of three boxes (blue, green and red parts of the visible
+//! spectrum), which makes the product of two upsampled spectra equal to the upsampled product of
+//! the colors. Thus the RGB radiance computed along a path only has to be upsampled once at its
+//! end, then weighted by the CIE color matching functions of the wavelength.
+use crate::vec::{Color3, Vec3};
+
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
+/// Shortest wavelength of the visible spectrum in nanometers.
+pub const WAVELENGTH_MIN: f32 = 380.;
+/// Longest wavelength of the visible spectrum in nanometers.
+pub const WAVELENGTH_MAX: f32 = 780.;
+
+/// Wavelength in nanometers of the sodium D line, at which refractive indices are usually given.
+pub const SODIUM_D_LINE: f32 = 587.6;
+
+/// Boundary between the blue and green boxes of the upsampled spectra.
+const BLUE_GREEN_WAVELENGTH: f32 = 490.;
+/// Boundary between the green and red boxes of the upsampled spectra.
+const GREEN_RED_WAVELENGTH: f32 = 590.;
+
+/// Integral over the visible spectrum of the linear sRGB colors of the wavelengths, computed
+/// numerically from [`cie_xyz`]. It is used to map a constant spectrum to white.
+const WHITE_INTEGRAL: Vec3 = Vec3 {
+    x: 128.361,
+    y: 101.538,
+    z: 97.0648,
+};
+
+/// Returns the value of the spectrum upsampled from the given RGB color at the given wavelength
+/// in nanometers.
+///
+/// # Examples
+/// ```
+/// use crab_rt::spectrum::rgb_to_spectrum;
+/// use crab_rt::vec::Color3;
+///
+/// let red = Color3::new(1., 0., 0.);
+/// assert_eq!(rgb_to_spectrum(&red, 650.), 1.);
+/// assert_eq!(rgb_to_spectrum(&red, 450.), 0.);
+/// ```
+#[inline]
+#[must_use]
+pub fn rgb_to_spectrum(color: &Color3, wavelength: f32) -> f32 {
+    if wavelength < BLUE_GREEN_WAVELENGTH {
+        color.z
+    } else if wavelength < GREEN_RED_WAVELENGTH {
+        color.y
+    } else {
+        color.x
+    }
+}
+
+/// Returns the CIE 1931 XYZ color matching functions at the given wavelength in nanometers.
+///
+/// This is the multi-lobe analytic approximation from
+/// [Simple Analytic Approximations to the CIE XYZ Color Matching Functions](https://jcgt.org/published/0002/02/01/)
+/// (Wyman et al. 2013).
+#[must_use]
+pub fn cie_xyz(wavelength: f32) -> Vec3 {
+    let gaussian = |mu: f32, sigma_low: f32, sigma_high: f32| {
+        let sigma = if wavelength < mu {
+            sigma_low
+        } else {
+            sigma_high
+        };
+        let t = (wavelength - mu) / sigma;
+        f32::exp(-0.5 * t * t)
+    };
+
+    Vec3::new(
+        0.065f32.mul_add(
+            -gaussian(501.1, 20.4, 26.2),
+            1.056f32.mul_add(
+                gaussian(599.8, 37.9, 31.),
+                0.362 * gaussian(442., 16., 26.7),
+            ),
+        ),
+        0.821f32.mul_add(
+            gaussian(568.8, 46.9, 40.5),
+            0.286 * gaussian(530.9, 16.3, 31.1),
+        ),
+        1.217f32.mul_add(gaussian(437., 11.8, 36.), 0.681 * gaussian(459., 26., 13.8)),
+    )
+}
+
+/// Converts a CIE XYZ color to a linear sRGB color.
+#[must_use]
+pub fn xyz_to_rgb(xyz: &Vec3) -> Color3 {
+    Color3::new(
+        (-0.498_531_4f32).mul_add(xyz.z, 3.240_454_2f32.mul_add(xyz.x, -1.537_138_5 * xyz.y)),
+        0.041_556f32.mul_add(xyz.z, (-0.969_266f32).mul_add(xyz.x, 1.876_010_8 * xyz.y)),
+        1.057_225_2f32.mul_add(xyz.z, 0.055_643_4f32.mul_add(xyz.x, -0.204_025_9 * xyz.y)),
+    )
+}
+
+/// Returns the RGB contribution of a radiance of 1 at the given wavelength, when wavelengths are
+/// sampled uniformly over the visible spectrum.
+///
+/// A constant spectrum of 1 averages to white.
+#[inline]
+#[must_use]
+pub fn wavelength_to_rgb(wavelength: f32) -> Color3 {
+    (WAVELENGTH_MAX - WAVELENGTH_MIN) * xyz_to_rgb(&cie_xyz(wavelength)) / WHITE_INTEGRAL
+}
+
+/// Converts the RGB radiance carried by a path of the given wavelength to its RGB contribution.
+#[inline]
+#[must_use]
+pub fn spectral_sample_to_rgb(radiance: &Color3, wavelength: f32) -> Color3 {
+    rgb_to_spectrum(radiance, wavelength) * wavelength_to_rgb(wavelength)
+}
+
+/// Second radiation constant of Planck's law, in nm.K.
+const SECOND_RADIATION_CONSTANT: f32 = 1.438_777e7;
+
+/// Returns the linear sRGB color of the light emitted by a black body at the given temperature in
+/// kelvins, normalized to a luminance of 1.
+///
+/// Candles burn around 1900 K, incandescent bulbs around 2700 K and the daylight is close to
+/// 6500 K.
+///
+/// # Panics
+/// Panics if `temperature` is not positive.
+///
+/// # Examples
+/// ```
+/// use crab_rt::spectrum::blackbody_rgb;
+///
+/// let bulb = blackbody_rgb(2700.);
+/// assert!(bulb.x > bulb.y && bulb.y > bulb.z);
+/// ```
+#[must_use]
+pub fn blackbody_rgb(temperature: f32) -> Color3 {
+    const STEPS: u16 = 80;
+    assert!(temperature > 0., "temperature should be positive");
+
+    // Planck's law up to a constant factor, with wavelengths in micrometers to stay in range
+    let step = (WAVELENGTH_MAX - WAVELENGTH_MIN) / f32::from(STEPS);
+    let xyz = (0..STEPS)
+        .map(|i| {
+            let wavelength = (f32::from(i) + 0.5).mul_add(step, WAVELENGTH_MIN);
+            let radiance = f32::powi(wavelength * 1e-3, -5)
+                / f32::exp_m1(SECOND_RADIATION_CONSTANT / (wavelength * temperature));
+            radiance * cie_xyz(wavelength)
+        })
+        .sum::<Vec3>();
+
+    (xyz_to_rgb(&xyz) / xyz.y).max(&Color3::zero())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constant_spectrum_is_white() {
+        let n = 4000;
+        let white = (0..n)
+            .map(|i| {
+                let wavelength = (i as f32 + 0.5)
+                    .mul_add((WAVELENGTH_MAX - WAVELENGTH_MIN) / n as f32, WAVELENGTH_MIN);
+                spectral_sample_to_rgb(&Color3::new(1., 1., 1.), wavelength)
+            })
+            .sum::<Vec3>()
+            / n as f32;
+
+        assert!((white.x - 1.).abs() < 1e-3);
+        assert!((white.y - 1.).abs() < 1e-3);
+        assert!((white.z - 1.).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,180 @@
+//! Stereo rigs rendering a pair of views for VR headsets and 3D displays.
+
+use crate::camera::Camera;
+
+#[cfg(feature = "std")]
+use image::{GenericImage, Rgb, RgbImage};
+
+/// Arrangement of the two views of a stereo pair in a single image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StereoLayout {
+    /// The left view on the left and the right view on the right, doubling the width.
+    #[default]
+    SideBySide,
+    /// The left view on top of the right view, doubling the height.
+    OverUnder,
+    /// The red channel of the left view with the green and blue channels of the right view, for
+    /// red-cyan glasses.
+    Anaglyph,
+}
+
+/// A pair of eyes looking through a camera, whose images match at the convergence distance.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StereoRig {
+    interocular: f32,
+    convergence: f32,
+    layout: StereoLayout,
+}
+
+impl StereoRig {
+    /// Constructs a new `StereoRig` with eyes `interocular` apart, converging at `convergence`
+    /// from the camera, and composited side by side.
+    ///
+    /// # Panics
+    /// Panics if `interocular < 0.` or `convergence <= 0.`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::stereo::StereoRig;
+    ///
+    /// // Usual interocular distance, with the screen 2 meters away
+    /// let rig = StereoRig::new(0.064, 2.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(interocular: f32, convergence: f32) -> Self {
+        assert!(
+            interocular >= 0.,
+            "interocular should be greater or equal to 0"
+        );
+        assert!(convergence > 0., "convergence should be greater than 0");
+
+        Self {
+            interocular,
+            convergence,
+            layout: StereoLayout::SideBySide,
+        }
+    }
+
+    /// Consumes the `StereoRig` and returns self with the given composite layout.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::stereo::{StereoLayout, StereoRig};
+    ///
+    /// let rig = StereoRig::new(0.064, 2.).layout(StereoLayout::Anaglyph);
+    /// assert_eq!(rig.composite_layout(), StereoLayout::Anaglyph);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn layout(self, layout: StereoLayout) -> Self {
+        Self { layout, ..self }
+    }
+
+    /// Returns the composite layout.
+    #[inline]
+    #[must_use]
+    pub const fn composite_layout(&self) -> StereoLayout {
+        self.layout
+    }
+
+    /// Returns the left and right eye cameras of `camera`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::camera::Camera;
+    /// use crab_rt::stereo::StereoRig;
+    /// use crab_rt::vec::Point3;
+    ///
+    /// let camera = Camera::new(Point3::new(0., 1.7, 2.), Point3::new(0., 1.7, 0.), 60., 1.);
+    /// let [left, right] = StereoRig::new(0.064, 2.).cameras(&camera);
+    /// ```
+    #[must_use]
+    pub fn cameras(&self, camera: &Camera) -> [Camera; 2] {
+        let offset = self.interocular / 2.;
+        [
+            camera.clone().eye(-offset, self.convergence),
+            camera.clone().eye(offset, self.convergence),
+        ]
+    }
+
+    /// Returns the composite of the left and right eye images.
+    ///
+    /// # Panics
+    /// Panics if the images do not have the same dimensions.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn composite(&self, left: &RgbImage, right: &RgbImage) -> RgbImage {
+        assert_eq!(
+            left.dimensions(),
+            right.dimensions(),
+            "left and right images should have the same dimensions"
+        );
+
+        let (width, height) = left.dimensions();
+        match self.layout {
+            StereoLayout::SideBySide => {
+                let mut image = RgbImage::new(2 * width, height);
+                image.copy_from(left, 0, 0).unwrap();
+                image.copy_from(right, width, 0).unwrap();
+                image
+            }
+            StereoLayout::OverUnder => {
+                let mut image = RgbImage::new(width, 2 * height);
+                image.copy_from(left, 0, 0).unwrap();
+                image.copy_from(right, 0, height).unwrap();
+                image
+            }
+            StereoLayout::Anaglyph => RgbImage::from_fn(width, height, |x, y| {
+                let (l, r) = (left.get_pixel(x, y), right.get_pixel(x, y));
+                Rgb([l[0], r[1], r[2]])
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ray::Ray;
+    use crate::vec::Point3;
+
+    #[test]
+    fn rig_eyes_are_interocular_apart() {
+        let camera = Camera::new(Point3::zero(), Point3::new(0., 0., -1.), 40., 1.);
+        let [left, right] = StereoRig::new(0.2, 3.).cameras(&camera);
+
+        let (left, right) = (left.ray(0.5, 0.5), right.ray(0.5, 0.5));
+        assert_eq!(*left.origin(), Point3::new(-0.1, 0., 0.));
+        assert_eq!(*right.origin(), Point3::new(0.1, 0., 0.));
+
+        // The off-axis centre rays meet on the view axis at the convergence distance
+        let at_convergence = |ray: &Ray| {
+            let t = (-3. - ray.origin().z) / ray.direction().z;
+            ray.point(t)
+        };
+        assert!((at_convergence(&left) - Point3::new(0., 0., -3.)).length() < 1e-5);
+        assert!((at_convergence(&right) - Point3::new(0., 0., -3.)).length() < 1e-5);
+    }
+
+    #[test]
+    fn composite_layouts() {
+        let left = RgbImage::from_pixel(4, 2, Rgb([10, 20, 30]));
+        let right = RgbImage::from_pixel(4, 2, Rgb([40, 50, 60]));
+        let rig = StereoRig::new(0.064, 2.);
+
+        let testee = rig.composite(&left, &right);
+        assert_eq!(testee.dimensions(), (8, 2));
+        assert_eq!(testee.get_pixel(3, 1), &Rgb([10, 20, 30]));
+        assert_eq!(testee.get_pixel(4, 0), &Rgb([40, 50, 60]));
+
+        let testee = rig.layout(StereoLayout::OverUnder).composite(&left, &right);
+        assert_eq!(testee.dimensions(), (4, 4));
+        assert_eq!(testee.get_pixel(0, 1), &Rgb([10, 20, 30]));
+        assert_eq!(testee.get_pixel(0, 2), &Rgb([40, 50, 60]));
+
+        let testee = rig.layout(StereoLayout::Anaglyph).composite(&left, &right);
+        assert_eq!(testee.dimensions(), (4, 2));
+        assert_eq!(testee.get_pixel(1, 1), &Rgb([10, 50, 60]));
+    }
+}
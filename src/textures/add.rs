@@ -0,0 +1,89 @@
+use alloc::boxed::Box;
+
+use super::Texture;
+use crate::hitable::{HitRecord, UvDerivatives};
+use crate::vec::{Point3, Vec3};
+
+/// A texture adding the color channels of two textures, to layer details over a texture.
+///
+/// Its alpha is the product of the alphas of the textures.
+#[derive(Debug)]
+pub struct Add {
+    a: Box<dyn Texture>,
+    b: Box<dyn Texture>,
+}
+
+impl Add {
+    /// Constructs a new `Add` of the textures.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Add, Fbm, Monochrome, Scale};
+    ///
+    /// let texture = Add::new(
+    ///     Monochrome::from_rgb(0.4, 0.4, 0.4),
+    ///     Scale::new(Fbm::new(7), 0.2),
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new<A: 'static + Texture, B: 'static + Texture>(a: A, b: B) -> Self {
+        Self {
+            a: Box::new(a),
+            b: Box::new(b),
+        }
+    }
+}
+
+impl Texture for Add {
+    fn value(&self, texture_coordinates: (f32, f32), p: &Point3) -> Vec3 {
+        self.a.value(texture_coordinates, p) + self.b.value(texture_coordinates, p)
+    }
+
+    fn filtered_value(
+        &self,
+        texture_coordinates: (f32, f32),
+        p: &Point3,
+        derivatives: &UvDerivatives,
+    ) -> Vec3 {
+        self.a.filtered_value(texture_coordinates, p, derivatives)
+            + self.b.filtered_value(texture_coordinates, p, derivatives)
+    }
+
+    fn value_from_hit(&self, record: &HitRecord<'_>) -> Vec3 {
+        self.a.value_from_hit(record) + self.b.value_from_hit(record)
+    }
+
+    fn alpha(&self, texture_coordinates: (f32, f32), p: &Point3) -> f32 {
+        self.a.alpha(texture_coordinates, p) * self.b.alpha(texture_coordinates, p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::textures::{Filter, Image, Monochrome};
+    use std::vec;
+
+    #[test]
+    fn add_sums_values() {
+        // Black and opaque on the left, white and transparent on the right
+        let image =
+            Image::from_rgba(2, 1, vec![0, 0, 0, 255, 255, 255, 255, 0]).filter(Filter::Trilinear);
+        let testee = Add::new(image, Monochrome::from_rgb(0.25, 0.25, 0.25));
+        let footprint = UvDerivatives {
+            dudx: 1.,
+            dvdx: 0.,
+            dudy: 0.,
+            dvdy: 1.,
+        };
+
+        let left = (0.25, 0.5);
+        assert!((testee.value(left, &Point3::zero()).x - 0.25).abs() < 1e-6);
+        // The footprint covers the whole image
+        let filtered = testee.filtered_value(left, &Point3::zero(), &footprint);
+        assert!((filtered.x - 0.75).abs() < 1e-2);
+        assert!((testee.alpha(left, &Point3::zero()) - 1.).abs() < 1e-6);
+        assert!(testee.alpha((0.75, 0.5), &Point3::zero()) < 1e-6);
+    }
+}
@@ -10,6 +10,8 @@ use core_maths::*;
 pub struct Checker {
     even: Box<dyn Texture>,
     odd: Box<dyn Texture>,
+    /// Whether the tiles follow the texture coordinates instead of the position in space.
+    texture_space: bool,
 }
 
 impl Checker {
@@ -23,6 +25,7 @@ impl Checker {
         Self {
             even: Box::new(even),
             odd: Box::new(odd),
+            texture_space: false,
         }
     }
 
@@ -31,11 +34,45 @@ impl Checker {
     pub fn from_colors(even: Color3, odd: Color3) -> Self {
         Self::new(Monochrome::new(even), Monochrome::new(odd))
     }
+
+    /// Consumes the `Checker` and returns self with square tiles of size 1 in texture space,
+    /// which follow the surface instead of the position in space.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Checker, Texture};
+    /// use crab_rt::vec::{Color3, Point3};
+    ///
+    /// let checker =
+    ///     Checker::from_colors(Color3::new(0., 0., 0.), Color3::new(1., 1., 1.)).texture_space();
+    /// assert_eq!(
+    ///     checker.value((0.5, 0.5), &Point3::zero()),
+    ///     Color3::new(0., 0., 0.)
+    /// );
+    /// assert_eq!(
+    ///     checker.value((1.5, 0.5), &Point3::zero()),
+    ///     Color3::new(1., 1., 1.)
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn texture_space(self) -> Self {
+        Self {
+            texture_space: true,
+            ..self
+        }
+    }
 }
 
 impl Texture for Checker {
     fn value(&self, texture_coordinates: (f32, f32), p: &Point3) -> Vec3 {
-        if f32::sin(10. * p.x) * f32::sin(10. * p.y) * f32::sin(10. * p.z) < 0. {
+        let odd = if self.texture_space {
+            (texture_coordinates.0.floor() + texture_coordinates.1.floor()).rem_euclid(2.) >= 1.
+        } else {
+            f32::sin(10. * p.x) * f32::sin(10. * p.y) * f32::sin(10. * p.z) < 0.
+        };
+
+        if odd {
             self.odd.value(texture_coordinates, p)
         } else {
             self.even.value(texture_coordinates, p)
@@ -0,0 +1,149 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use super::Texture;
+use crate::hitable::{HitRecord, UvDerivatives};
+use crate::vec::{Color3, Point3, Vec3};
+
+/// A texture mapping the values of a scalar texture, the mean of its color channels, to colors
+/// interpolated between stops.
+#[derive(Debug)]
+pub struct ColorRamp {
+    input: Box<dyn Texture>,
+    /// Positions and colors of the stops, sorted by position.
+    stops: Vec<(f32, Color3)>,
+}
+
+impl ColorRamp {
+    /// Constructs a new `ColorRamp` mapping the values of `input` to the colors of `stops`,
+    /// given as positions and colors. Values outside of the stops take the color of the closest
+    /// stop.
+    ///
+    /// # Panics
+    /// Panics if `stops` is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{ColorRamp, Fbm, Texture};
+    /// use crab_rt::vec::{Color3, Point3};
+    ///
+    /// let clouds = ColorRamp::new(
+    ///     Fbm::new(7),
+    ///     vec![
+    ///         (0.4, Color3::new(0.3, 0.5, 0.9)),
+    ///         (0.6, Color3::new(1., 1., 1.)),
+    ///     ],
+    /// );
+    /// assert_eq!(clouds.color(0.5), Color3::new(0.65, 0.75, 0.95));
+    /// ```
+    #[must_use]
+    pub fn new<T: 'static + Texture>(input: T, mut stops: Vec<(f32, Color3)>) -> Self {
+        assert!(!stops.is_empty(), "a color ramp needs at least one stop");
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        Self {
+            input: Box::new(input),
+            stops,
+        }
+    }
+
+    /// Returns the color of the ramp at position `t`.
+    #[must_use]
+    pub fn color(&self, t: f32) -> Color3 {
+        let next = self.stops.partition_point(|stop| stop.0 <= t);
+        if next == 0 {
+            return self.stops[0].1;
+        }
+        if next == self.stops.len() {
+            return self.stops[next - 1].1;
+        }
+
+        let (start, end) = (self.stops[next - 1], self.stops[next]);
+        let s = (t - start.0) / (end.0 - start.0);
+        (1. - s) * start.1 + s * end.1
+    }
+
+    /// Returns the color of the ramp at the mean of the color channels of a value of the input.
+    #[inline]
+    fn color_of(&self, value: Vec3) -> Color3 {
+        self.color((value.x + value.y + value.z) / 3.)
+    }
+}
+
+impl Texture for ColorRamp {
+    #[inline]
+    fn value(&self, texture_coordinates: (f32, f32), p: &Point3) -> Vec3 {
+        self.color_of(self.input.value(texture_coordinates, p))
+    }
+
+    #[inline]
+    fn filtered_value(
+        &self,
+        texture_coordinates: (f32, f32),
+        p: &Point3,
+        derivatives: &UvDerivatives,
+    ) -> Vec3 {
+        self.color_of(
+            self.input
+                .filtered_value(texture_coordinates, p, derivatives),
+        )
+    }
+
+    #[inline]
+    fn value_from_hit(&self, record: &HitRecord<'_>) -> Vec3 {
+        self.color_of(self.input.value_from_hit(record))
+    }
+
+    #[inline]
+    fn alpha(&self, texture_coordinates: (f32, f32), p: &Point3) -> f32 {
+        self.input.alpha(texture_coordinates, p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::materials::Lambertian;
+    use crate::textures::{Checker, Monochrome, Triplanar};
+    use std::vec;
+
+    #[test]
+    fn color_ramp_interpolates_stops() {
+        let testee = ColorRamp::new(
+            Monochrome::from_rgb(0., 0., 0.),
+            vec![
+                (1., Color3::new(0., 0., 1.)),
+                (0., Color3::new(1., 0., 0.)),
+                (0.5, Color3::new(0., 1., 0.)),
+            ],
+        );
+
+        assert_eq!(testee.color(-1.), Color3::new(1., 0., 0.));
+        assert_eq!(testee.color(0.25), Color3::new(0.5, 0.5, 0.));
+        assert_eq!(testee.color(0.5), Color3::new(0., 1., 0.));
+        assert_eq!(testee.color(0.75), Color3::new(0., 0.5, 0.5));
+        assert_eq!(testee.color(2.), Color3::new(0., 0., 1.));
+        assert_eq!(
+            testee.value((0., 0.), &Point3::zero()),
+            Color3::new(1., 0., 0.)
+        );
+    }
+
+    #[test]
+    fn color_ramp_maps_projected_input() {
+        let material = Lambertian::default();
+        let testee = ColorRamp::new(
+            Triplanar::new(
+                Checker::from_colors(Color3::new(0., 0., 0.), Color3::new(1., 1., 1.))
+                    .texture_space(),
+            ),
+            vec![(0., Color3::new(1., 0., 0.)), (1., Color3::new(0., 0., 1.))],
+        );
+
+        // The y projection reads the texture at (x, z), outside of the cell of (x, y)
+        let p = Point3::new(0.5, 0.5, 1.5);
+        let record = HitRecord::new(1., p, Vec3::new(0., 1., 0.), (0., 0.), &material);
+        assert_eq!(testee.value_from_hit(&record), Color3::new(0., 0., 1.));
+        assert_eq!(testee.value((0., 0.), &p), Color3::new(1., 0., 0.));
+    }
+}
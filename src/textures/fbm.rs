@@ -0,0 +1,131 @@
+use super::Texture;
+use crate::perlin::Perlin;
+use crate::vec::{Point3, Vec3};
+
+/// A grey texture of fractional Brownian motion (fBm), the sum of layers of Perlin noise of
+/// increasing frequency and decreasing amplitude, in range [0, 1].
+#[derive(Debug)]
+pub struct Fbm {
+    noise: Perlin,
+    scale: f32,
+    octaves: usize,
+    /// Frequency multiplier between two layers.
+    lacunarity: f32,
+    /// Amplitude multiplier between two layers.
+    gain: f32,
+}
+
+impl Fbm {
+    /// Constructs a new `Fbm` texture generated from `seed`, with 6 octaves, a lacunarity of 2
+    /// and a gain of 0.5.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Fbm, Texture};
+    /// use crab_rt::vec::Point3;
+    ///
+    /// let p = Point3::new(0.3, 1.7, -2.1);
+    /// assert_eq!(
+    ///     Fbm::new(7).value((0., 0.), &p),
+    ///     Fbm::new(7).value((0., 0.), &p)
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self {
+            noise: Perlin::with_seed(seed),
+            scale: 1.,
+            octaves: 6,
+            lacunarity: 2.,
+            gain: 0.5,
+        }
+    }
+
+    /// Consumes the `Fbm` and returns self with the frequency of the first layer multiplied by
+    /// `scale`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::Fbm;
+    ///
+    /// let texture = Fbm::new(7).scale(4.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn scale(self, scale: f32) -> Self {
+        Self { scale, ..self }
+    }
+
+    /// Consumes the `Fbm` and returns self with the given number of layers.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::Fbm;
+    ///
+    /// let texture = Fbm::new(7).octaves(3);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn octaves(self, octaves: usize) -> Self {
+        Self { octaves, ..self }
+    }
+
+    /// Consumes the `Fbm` and returns self with the frequency multiplied by `lacunarity` at
+    /// each layer.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::Fbm;
+    ///
+    /// let texture = Fbm::new(7).lacunarity(3.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn lacunarity(self, lacunarity: f32) -> Self {
+        Self { lacunarity, ..self }
+    }
+
+    /// Consumes the `Fbm` and returns self with the amplitude multiplied by `gain` at each
+    /// layer, lower gains giving smoother textures.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::Fbm;
+    ///
+    /// let texture = Fbm::new(7).gain(0.7);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn gain(self, gain: f32) -> Self {
+        Self { gain, ..self }
+    }
+}
+
+impl Texture for Fbm {
+    #[inline]
+    fn value(&self, _texture_coordinates: (f32, f32), p: &Point3) -> Vec3 {
+        let value = self
+            .noise
+            .fbm(&(self.scale * p), self.octaves, self.lacunarity, self.gain);
+        Vec3::new(value, value, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fbm_stays_in_unit_range() {
+        let testee = Fbm::new(42).scale(3.).gain(0.8);
+
+        for n in 0..1000_u16 {
+            let t = f32::from(n) * 0.0173;
+            let value = testee.value((0., 0.), &Point3::new(t, -2. * t, 0.5 * t));
+            assert!((0. ..=1.).contains(&value.x));
+            assert_eq!(value.x, value.y);
+            assert_eq!(value.x, value.z);
+        }
+    }
+}
@@ -1,20 +1,146 @@
 use super::Texture;
-use crate::vec::{Point3, Vec3};
+use crate::hitable::UvDerivatives;
+use crate::utils::{gamma_decode, index_to_f32};
+use crate::vec::{Color3, Point3, Vec3};
 use alloc::vec::Vec;
+use core::iter::zip;
 
 #[cfg(feature = "std")]
 use anyhow::Result;
+#[cfg(feature = "std")]
+use image::ColorType;
+
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
+/// How an image is sampled between the centers of its pixels.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    /// The color of the closest pixel.
+    #[default]
+    Nearest,
+    /// Linear interpolation of the 4 closest pixels.
+    Bilinear,
+    /// Catmull-Rom interpolation of the 16 closest pixels, sharper than bilinear filtering.
+    Bicubic,
+    /// Linear interpolation between the bilinear filtering of the two mipmap levels matching
+    /// the footprint of a pixel, which removes aliasing at a distance.
+    Trilinear,
+    /// Elliptically weighted average of the pixels in the footprint of a pixel, which removes
+    /// aliasing at a distance and keeps surfaces seen at grazing angles sharper than trilinear
+    /// filtering.
+    Ewa,
+}
+
+/// Maximum ratio between the axes of the footprint of a pixel in the elliptically weighted
+/// average filter, which bounds the number of pixels to average.
+const MAX_ANISOTROPY: f32 = 8.;
+
+/// How an image is extended outside of the texture coordinates range [0, 1].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Wrap {
+    /// The image is tiled.
+    Repeat,
+    /// The pixels at the edges of the image are extended.
+    #[default]
+    Clamp,
+    /// The image is tiled, flipping every other tile.
+    Mirror,
+}
 
-// For now the image only support RGB
+impl Wrap {
+    /// Maps a pixel index to the range [0, size).
+    #[inline]
+    fn index(self, i: isize, size: usize) -> usize {
+        let size = size.cast_signed();
+        let i = match self {
+            Self::Repeat => i.rem_euclid(size),
+            Self::Clamp => i.clamp(0, size - 1),
+            Self::Mirror => {
+                let i = i.rem_euclid(2 * size);
+                if i < size {
+                    i
+                } else {
+                    2 * size - 1 - i
+                }
+            }
+        };
+        i.unsigned_abs()
+    }
+}
+
+/// How the color channels of an image are encoded.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSpace {
+    /// Gamma encoded colors, as stored by most 8-bit images such as photographs and albedo
+    /// textures, decoded to linear light before rendering.
+    #[default]
+    Srgb,
+    /// Linear light colors, as stored by high dynamic range images and by data textures such as
+    /// normal or height maps.
+    Linear,
+}
+
+impl ColorSpace {
+    /// Returns the linear light value of an encoded channel.
+    #[inline]
+    fn decode(self, x: f32) -> f32 {
+        match self {
+            Self::Srgb => gamma_decode(x),
+            Self::Linear => x,
+        }
+    }
+}
+
+/// The channels of the pixels of an image.
+#[derive(Debug)]
+enum Data {
+    /// 8-bit channels in range [0, 255].
+    Bytes(Vec<u8>),
+    /// Floating point channels, from high bit depth or high dynamic range images.
+    Floats(Vec<f32>),
+}
+
+impl Data {
+    /// Returns the value of the channel at the given index, in range [0, 1] for 8-bit channels.
+    #[inline]
+    fn channel(&self, index: usize) -> f32 {
+        match self {
+            Self::Bytes(data) => f32::from(data[index]) / 255.,
+            Self::Floats(data) => data[index],
+        }
+    }
+}
+
+/// A downsampled level of the mipmap pyramid of an image.
+#[derive(Debug)]
+struct MipLevel {
+    width: usize,
+    height: usize,
+    colors: Vec<Color3>,
+}
+
+/// An image texture with RGB or RGBA pixels, returning linear light colors.
 #[derive(Debug)]
 pub struct Image {
     width: usize,
     height: usize,
+    /// Number of channels of the pixels, 3 for RGB and 4 for RGBA.
+    channels: usize,
 
-    data: Vec<u8>,
+    data: Data,
+    color_space: ColorSpace,
+
+    filter: Filter,
+    wrap: Wrap,
+    /// Levels of the mipmap pyramid after the image itself, each one half the size of the
+    /// previous one, built for the filters using them.
+    mipmaps: Vec<MipLevel>,
 }
 
 impl Image {
+    /// Constructs a new `Image` from 8-bit RGB pixels in the sRGB color space.
+    ///
     /// # Panics
     /// Panics if the data length is not equal to `width * height * 3`.
     #[inline]
@@ -25,46 +151,743 @@ impl Image {
         Self {
             width,
             height,
-            data,
+            channels: 3,
+            data: Data::Bytes(data),
+            color_space: ColorSpace::default(),
+            filter: Filter::default(),
+            wrap: Wrap::default(),
+            mipmaps: Vec::new(),
+        }
+    }
+
+    /// Constructs a new `Image` from 8-bit RGBA pixels in the sRGB color space, whose alpha
+    /// channel gives the opacity.
+    ///
+    /// # Panics
+    /// Panics if the data length is not equal to `width * height * 4`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::Image;
+    ///
+    /// let image = Image::from_rgba(1, 1, vec![255, 0, 0, 128]);
+    /// assert!(image.has_alpha());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn from_rgba(width: usize, height: usize, data: Vec<u8>) -> Self {
+        assert_eq!(data.len(), width * height * 4);
+
+        Self {
+            width,
+            height,
+            channels: 4,
+            data: Data::Bytes(data),
+            color_space: ColorSpace::default(),
+            filter: Filter::default(),
+            wrap: Wrap::default(),
+            mipmaps: Vec::new(),
+        }
+    }
+
+    /// Constructs a new `Image` from floating point RGB pixels in the linear color space, whose
+    /// values may exceed 1 for high dynamic range images.
+    ///
+    /// # Panics
+    /// Panics if the data length is not equal to `width * height * 3`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::Image;
+    /// use crab_rt::vec::Color3;
+    ///
+    /// let image = Image::from_rgb_f32(1, 1, vec![4., 2., 1.]);
+    /// assert_eq!(image.pixel(0, 0), Color3::new(4., 2., 1.));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn from_rgb_f32(width: usize, height: usize, data: Vec<f32>) -> Self {
+        assert_eq!(data.len(), width * height * 3);
+
+        Self {
+            width,
+            height,
+            channels: 3,
+            data: Data::Floats(data),
+            color_space: ColorSpace::Linear,
+            filter: Filter::default(),
+            wrap: Wrap::default(),
+            mipmaps: Vec::new(),
         }
     }
 
+    /// Constructs a new `Image` from floating point RGBA pixels in the linear color space, whose
+    /// alpha channel gives the opacity.
+    ///
+    /// # Panics
+    /// Panics if the data length is not equal to `width * height * 4`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::Image;
+    ///
+    /// let image = Image::from_rgba_f32(1, 1, vec![4., 2., 1., 0.5]);
+    /// assert_eq!(image.pixel_alpha(0, 0), 0.5);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn from_rgba_f32(width: usize, height: usize, data: Vec<f32>) -> Self {
+        assert_eq!(data.len(), width * height * 4);
+
+        Self {
+            width,
+            height,
+            channels: 4,
+            data: Data::Floats(data),
+            color_space: ColorSpace::Linear,
+            filter: Filter::default(),
+            wrap: Wrap::default(),
+            mipmaps: Vec::new(),
+        }
+    }
+
+    /// Loads an image, keeping its alpha channel if it has one.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or decoded.
     #[cfg(feature = "std")]
     pub fn load(filename: &str) -> Result<Self> {
-        let image_buffer = image::open(filename)?.into_rgb8();
-        let width = image_buffer.width();
-        let height = image_buffer.height();
+        let image = image::open(filename)?;
+        let width = image.width() as usize;
+        let height = image.height() as usize;
+        let color = image.color();
+        let has_alpha = color.has_alpha();
+
+        let image = match color {
+            // High dynamic range images, such as Radiance HDR files, store linear light
+            ColorType::Rgb32F | ColorType::Rgba32F if has_alpha => {
+                Self::from_rgba_f32(width, height, image.into_rgba32f().into_raw())
+            }
+            ColorType::Rgb32F | ColorType::Rgba32F => {
+                Self::from_rgb_f32(width, height, image.into_rgb32f().into_raw())
+            }
+            // High bit depth images, such as 16-bit PNG files, are kept as floats
+            _ if color.bytes_per_pixel() > color.channel_count() && has_alpha => {
+                Self::from_rgba_f32(width, height, image.into_rgba32f().into_raw())
+                    .color_space(ColorSpace::Srgb)
+            }
+            _ if color.bytes_per_pixel() > color.channel_count() => {
+                Self::from_rgb_f32(width, height, image.into_rgb32f().into_raw())
+                    .color_space(ColorSpace::Srgb)
+            }
+            _ if has_alpha => Self::from_rgba(width, height, image.into_rgba8().into_raw()),
+            _ => Self::new(width, height, image.into_rgb8().into_raw()),
+        };
+
+        Ok(image)
+    }
+}
+
+impl Image {
+    /// Consumes the `Image` and returns self with the given filter.
+    ///
+    /// The trilinear and elliptically weighted average filters build a mipmap pyramid of the
+    /// image and need the derivatives of the texture coordinates, otherwise they fall back to
+    /// bilinear filtering.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Filter, Image};
+    ///
+    /// let image = Image::new(1, 1, vec![0, 0, 0]).filter(Filter::Bilinear);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn filter(self, filter: Filter) -> Self {
+        let mipmaps = if matches!(filter, Filter::Trilinear | Filter::Ewa) {
+            self.build_mipmaps()
+        } else {
+            Vec::new()
+        };
+
+        Self {
+            filter,
+            mipmaps,
+            ..self
+        }
+    }
+
+    /// Consumes the `Image` and returns self with its channels decoded from the given color
+    /// space.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{ColorSpace, Image};
+    ///
+    /// // Normal maps store directions rather than colors
+    /// let normals = Image::new(1, 1, vec![128, 128, 255]).color_space(ColorSpace::Linear);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn color_space(self, color_space: ColorSpace) -> Self {
+        let filter = self.filter;
+        Self {
+            color_space,
+            ..self
+        }
+        .filter(filter)
+    }
+
+    /// Consumes the `Image` and returns self with the given wrap mode.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Image, Wrap};
+    ///
+    /// let image = Image::new(1, 1, vec![0, 0, 0]).wrap(Wrap::Repeat);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn wrap(self, wrap: Wrap) -> Self {
+        Self { wrap, ..self }
+    }
+
+    /// Returns the width of the image in pixels.
+    #[inline]
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height of the image in pixels.
+    #[inline]
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns whether the image has an alpha channel.
+    #[inline]
+    #[must_use]
+    pub const fn has_alpha(&self) -> bool {
+        self.channels == 4
+    }
+
+    /// Returns the linear light color of the pixel at column `i` and row `j`, starting from the
+    /// top left corner of the image.
+    ///
+    /// # Panics
+    /// Panics if `i >= width` or `j >= height`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::Image;
+    /// use crab_rt::vec::Color3;
+    ///
+    /// let image = Image::new(2, 1, vec![0, 0, 0, 255, 255, 255]);
+    /// assert_eq!(image.pixel(1, 0), Color3::new(1., 1., 1.));
+    ///
+    /// // sRGB mid grey is darker in linear light
+    /// let image = Image::new(1, 1, vec![128, 128, 128]);
+    /// assert!(image.pixel(0, 0).x < 0.25);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn pixel(&self, i: usize, j: usize) -> Color3 {
+        assert!(i < self.width && j < self.height);
+
+        let pixel = (i + j * self.width) * self.channels;
+        let channel = |c| self.color_space.decode(self.data.channel(pixel + c));
+        Color3::new(channel(0), channel(1), channel(2))
+    }
+
+    /// Returns the stored color of the pixel at column `i` and row `j`, starting from the top
+    /// left corner of the image, without decoding it to linear light.
+    ///
+    /// # Panics
+    /// Panics if `i >= width` or `j >= height`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::Image;
+    ///
+    /// let image = Image::new(1, 1, vec![51, 51, 51]);
+    /// assert_eq!(image.raw_pixel(0, 0).x, 0.2);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn raw_pixel(&self, i: usize, j: usize) -> Color3 {
+        assert!(i < self.width && j < self.height);
+
+        let pixel = (i + j * self.width) * self.channels;
+        let channel = |c| self.data.channel(pixel + c);
+        Color3::new(channel(0), channel(1), channel(2))
+    }
+
+    /// Returns the alpha of the pixel at column `i` and row `j`, starting from the top left
+    /// corner of the image, or 1 if the image has no alpha channel.
+    ///
+    /// # Panics
+    /// Panics if `i >= width` or `j >= height`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::Image;
+    ///
+    /// let image = Image::from_rgba(2, 1, vec![0, 0, 0, 0, 255, 255, 255, 255]);
+    /// assert_eq!(image.pixel_alpha(0, 0), 0.);
+    /// assert_eq!(image.pixel_alpha(1, 0), 1.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn pixel_alpha(&self, i: usize, j: usize) -> f32 {
+        assert!(i < self.width && j < self.height);
+
+        if self.has_alpha() {
+            // The alpha channel is always linear
+            self.data.channel((i + j * self.width) * 4 + 3)
+        } else {
+            1.
+        }
+    }
+
+    /// Returns the mipmap pyramid of the image, averaging blocks of 2x2 pixels of each level.
+    fn build_mipmaps(&self) -> Vec<MipLevel> {
+        let mut mipmaps: Vec<MipLevel> = Vec::new();
+        let (mut width, mut height) = (self.width, self.height);
+
+        while width > 1 || height > 1 {
+            let previous = |i: usize, j: usize| {
+                let (i, j) = (i.min(width - 1), j.min(height - 1));
+                mipmaps
+                    .last()
+                    .map_or_else(|| self.pixel(i, j), |level| level.colors[i + j * width])
+            };
+
+            let level_width = width.div_ceil(2);
+            let level_height = height.div_ceil(2);
+            let colors = (0..level_height)
+                .flat_map(|j| (0..level_width).map(move |i| (i, j)))
+                .map(|(i, j)| {
+                    0.25 * (previous(2 * i, 2 * j)
+                        + previous(2 * i + 1, 2 * j)
+                        + previous(2 * i, 2 * j + 1)
+                        + previous(2 * i + 1, 2 * j + 1))
+                })
+                .collect();
+
+            mipmaps.push(MipLevel {
+                width: level_width,
+                height: level_height,
+                colors,
+            });
+            (width, height) = (level_width, level_height);
+        }
+
+        mipmaps
+    }
+
+    /// Returns the size of the given level of the mipmap pyramid, the level 0 being the image.
+    #[inline]
+    fn level_size(&self, level: usize) -> (usize, usize) {
+        match level {
+            0 => (self.width, self.height),
+            _ => (
+                self.mipmaps[level - 1].width,
+                self.mipmaps[level - 1].height,
+            ),
+        }
+    }
+
+    /// Returns the color of the pixel at column `i` and row `j` of the given level of the mipmap
+    /// pyramid.
+    #[inline]
+    fn level_pixel(&self, level: usize, i: usize, j: usize) -> Color3 {
+        if level == 0 {
+            self.pixel(i, j)
+        } else {
+            let mipmap = &self.mipmaps[level - 1];
+            mipmap.colors[i + j * mipmap.width]
+        }
+    }
+
+    /// Returns a closure giving the value of the pixel at the given column and row starting
+    /// from the bottom left corner of an image of the given size, following the wrap mode.
+    ///
+    /// `fetch` returns the value of the pixel at the given column and row, starting from the top
+    /// left corner of the image.
+    #[inline]
+    fn texel<'a, F: 'a + Fn(usize, usize) -> Vec3>(
+        &'a self,
+        (width, height): (usize, usize),
+        fetch: F,
+    ) -> impl Fn(isize, isize) -> Vec3 + 'a {
+        move |i, j| {
+            fetch(
+                self.wrap.index(i, width),
+                height - 1 - self.wrap.index(j, height),
+            )
+        }
+    }
+
+    /// Samples the pixels of an image of the given size with the filter and the wrap mode of the
+    /// image at the given texture coordinates.
+    ///
+    /// The filters using mipmaps fall back to bilinear filtering.
+    fn sample<F: Fn(usize, usize) -> Vec3>(
+        &self,
+        size: (usize, usize),
+        texture_coordinates: (f32, f32),
+        fetch: F,
+    ) -> Vec3 {
+        let texel = self.texel(size, fetch);
+
+        let x = texture_coordinates.0 * index_to_f32(size.0);
+        let y = texture_coordinates.1 * index_to_f32(size.1);
+
+        // Coordinates of the closest pixel center below and to the left, and of the point
+        // relatively to it
+        let centered = || {
+            let (x, y) = (x - 0.5, y - 0.5);
+            let (i, j) = (x.floor(), y.floor());
+            ((pixel_index(i), pixel_index(j)), (x - i, y - j))
+        };
+
+        match self.filter {
+            Filter::Nearest => texel(pixel_index(x.floor()), pixel_index(y.floor())),
+            Filter::Bilinear | Filter::Trilinear | Filter::Ewa => {
+                let ((i, j), (dx, dy)) = centered();
+                (1. - dy) * ((1. - dx) * texel(i, j) + dx * texel(i + 1, j))
+                    + dy * ((1. - dx) * texel(i, j + 1) + dx * texel(i + 1, j + 1))
+            }
+            Filter::Bicubic => {
+                let ((i, j), (dx, dy)) = centered();
+                let (wx, wy) = (catmull_rom_weights(dx), catmull_rom_weights(dy));
+                let color = zip(wy, j - 1..)
+                    .map(|(wy, j)| {
+                        wy * zip(wx, i - 1..)
+                            .map(|(wx, i)| wx * texel(i, j))
+                            .sum::<Vec3>()
+                    })
+                    .sum::<Vec3>();
+
+                // The interpolation can overshoot the range of the pixels
+                let color = color.max(&Vec3::zero());
+                match self.data {
+                    Data::Bytes(_) => color.min(&Vec3::new(1., 1., 1.)),
+                    Data::Floats(_) => color,
+                }
+            }
+        }
+    }
+
+    /// Samples the given level of the mipmap pyramid with bilinear filtering.
+    #[inline]
+    fn bilinear(&self, level: usize, texture_coordinates: (f32, f32)) -> Vec3 {
+        self.sample(self.level_size(level), texture_coordinates, |i, j| {
+            self.level_pixel(level, i, j)
+        })
+    }
+
+    /// Samples the image at the mipmap level matching a footprint of the given width in pixels
+    /// of the image, interpolating between the two closest levels with `filter`.
+    fn sample_footprint<F: Fn(usize) -> Vec3>(&self, width: f32, filter: F) -> Vec3 {
+        let max_level = self.mipmaps.len();
+        let level = if width.is_finite() {
+            width.max(1.).log2().min(index_to_f32(max_level))
+        } else {
+            index_to_f32(max_level)
+        };
+
+        // The level is in range [0, max_level]
+        #[allow(clippy::cast_possible_truncation)]
+        #[allow(clippy::cast_sign_loss)]
+        let lower = level.floor() as usize;
+        if lower == max_level {
+            return filter(lower);
+        }
+
+        let t = level - level.floor();
+        (1. - t) * filter(lower) + t * filter(lower + 1)
+    }
+
+    /// Samples the image with linear interpolation between two bilinearly filtered mipmap
+    /// levels.
+    fn trilinear(&self, texture_coordinates: (f32, f32), derivatives: &UvDerivatives) -> Vec3 {
+        let (width, height) = (index_to_f32(self.width), index_to_f32(self.height));
+        let footprint = f32::max(
+            f32::hypot(derivatives.dudx * width, derivatives.dvdx * height),
+            f32::hypot(derivatives.dudy * width, derivatives.dvdy * height),
+        );
+
+        self.sample_footprint(footprint, |level| self.bilinear(level, texture_coordinates))
+    }
+
+    /// Samples the image with an elliptically weighted average of the pixels in the footprint.
+    fn ewa(&self, texture_coordinates: (f32, f32), derivatives: &UvDerivatives) -> Vec3 {
+        let mut major = (derivatives.dudx, derivatives.dvdx);
+        let mut minor = (derivatives.dudy, derivatives.dvdy);
+        let length = |axis: (f32, f32)| f32::hypot(axis.0, axis.1);
+        if length(major) < length(minor) {
+            (major, minor) = (minor, major);
+        }
+
+        // Lengthens the minor axis of very eccentric ellipses
+        let (major_length, minor_length) = (length(major), length(minor));
+        if minor_length * MAX_ANISOTROPY < major_length && minor_length > 0. {
+            let scale = major_length / (minor_length * MAX_ANISOTROPY);
+            minor = (minor.0 * scale, minor.1 * scale);
+        }
+        let minor_length = length(minor);
+        if minor_length == 0. {
+            return self.bilinear(0, texture_coordinates);
+        }
 
-        Ok(Self::new(
-            width as usize,
-            height as usize,
-            image_buffer.into_raw(),
-        ))
+        let footprint = minor_length * index_to_f32(self.width.max(self.height));
+        self.sample_footprint(footprint, |level| {
+            self.ewa_level(level, texture_coordinates, major, minor)
+        })
     }
+
+    /// Returns the elliptically weighted average of the pixels of a level of the mipmap pyramid
+    /// inside the ellipse of the given axes in texture space.
+    #[allow(clippy::many_single_char_names)]
+    fn ewa_level(
+        &self,
+        level: usize,
+        texture_coordinates: (f32, f32),
+        major: (f32, f32),
+        minor: (f32, f32),
+    ) -> Vec3 {
+        let size = self.level_size(level);
+        let texel = self.texel(size, |i, j| self.level_pixel(level, i, j));
+        let (width, height) = (index_to_f32(size.0), index_to_f32(size.1));
+
+        // Center and axes of the ellipse in pixels relatively to the pixel centers
+        let s = texture_coordinates.0.mul_add(width, -0.5);
+        let t = texture_coordinates.1.mul_add(height, -0.5);
+        let (du0, dv0) = (major.0 * width, major.1 * height);
+        let (du1, dv1) = (minor.0 * width, minor.1 * height);
+
+        // Coefficients of the implicit equation a * s^2 + b * s * t + c * t^2 < 1 of the ellipse
+        let a = dv0.mul_add(dv0, dv1 * dv1) + 1.;
+        let b = -2. * du0.mul_add(dv0, du1 * dv1);
+        let c = du0.mul_add(du0, du1 * du1) + 1.;
+        let inverse_f = 1. / (a * c - b * b * 0.25);
+        let (a, b, c) = (a * inverse_f, b * inverse_f, c * inverse_f);
+
+        // Bounding box of the ellipse
+        let determinant = (4. * a).mul_add(c, -b * b);
+        let inverse_determinant = 1. / determinant;
+        let s_extent = 2. * inverse_determinant * f32::sqrt(determinant * c);
+        let t_extent = 2. * inverse_determinant * f32::sqrt(determinant * a);
+
+        let mut sum = Vec3::zero();
+        let mut weights = 0.;
+        for j in pixel_index((t - t_extent).ceil())..=pixel_index((t + t_extent).floor()) {
+            let dt = pixel_to_f32(j) - t;
+            for i in pixel_index((s - s_extent).ceil())..=pixel_index((s + s_extent).floor()) {
+                let ds = pixel_to_f32(i) - s;
+                let r2 = (a * ds).mul_add(ds, (b * ds).mul_add(dt, c * dt * dt));
+                if r2 < 1. {
+                    // Gaussian falling to zero at the border of the ellipse
+                    let weight = f32::exp(-2. * r2) - f32::exp(-2.);
+                    sum += weight * texel(i, j);
+                    weights += weight;
+                }
+            }
+        }
+
+        if weights > 0. {
+            sum / weights
+        } else {
+            self.bilinear(level, texture_coordinates)
+        }
+    }
+}
+
+/// Converts a whole pixel coordinate to a pixel index.
+///
+/// The coordinates of the pixels around the image fit in an `isize`, and the ones far outside of
+/// it saturate.
+#[allow(clippy::cast_possible_truncation)]
+#[inline]
+const fn pixel_index(x: f32) -> isize {
+    x as isize
+}
+
+/// Converts a pixel index to a coordinate.
+///
+/// The pixels around the image are far fewer than the 2^24 integers a `f32` represents exactly.
+#[allow(clippy::cast_precision_loss)]
+#[inline]
+const fn pixel_to_f32(i: isize) -> f32 {
+    i as f32
+}
+
+/// Returns the weights of the 4 pixels around a point at `t` between the second and third ones
+/// for a Catmull-Rom spline.
+#[inline]
+fn catmull_rom_weights(t: f32) -> [f32; 4] {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    [
+        0.5 * (-t3 + 2. * t2 - t),
+        1.5f32.mul_add(t3, (-2.5f32).mul_add(t2, 1.)),
+        0.5 * (-3. * t3 + 4. * t2 + t),
+        0.5 * (t3 - t2),
+    ]
 }
 
 impl Texture for Image {
     fn value(&self, texture_coordinates: (f32, f32), _p: &Point3) -> Vec3 {
-        debug_assert!(0. <= texture_coordinates.0 && texture_coordinates.0 <= 1.);
-        debug_assert!(0. <= texture_coordinates.1 && texture_coordinates.1 <= 1.);
+        self.sample(self.level_size(0), texture_coordinates, |i, j| {
+            self.pixel(i, j)
+        })
+    }
+
+    fn filtered_value(
+        &self,
+        texture_coordinates: (f32, f32),
+        p: &Point3,
+        derivatives: &UvDerivatives,
+    ) -> Vec3 {
+        match self.filter {
+            Filter::Trilinear => self.trilinear(texture_coordinates, derivatives),
+            Filter::Ewa => self.ewa(texture_coordinates, derivatives),
+            _ => self.value(texture_coordinates, p),
+        }
+    }
+
+    fn alpha(&self, texture_coordinates: (f32, f32), _p: &Point3) -> f32 {
+        if !self.has_alpha() {
+            return 1.;
+        }
+
+        self.sample(self.level_size(0), texture_coordinates, |i, j| {
+            let alpha = self.pixel_alpha(i, j);
+            Vec3::new(alpha, alpha, alpha)
+        })
+        .x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec;
+
+    /// A 2x1 image, black on the left and white on the right.
+    fn black_and_white() -> Image {
+        Image::new(2, 1, vec![0, 0, 0, 255, 255, 255])
+    }
+
+    #[test]
+    fn image_decodes_color_space() {
+        let srgb = Image::new(1, 1, vec![128, 128, 128]);
+        let linear = Image::new(1, 1, vec![128, 128, 128]).color_space(ColorSpace::Linear);
+
+        assert!((srgb.pixel(0, 0).x - gamma_decode(128. / 255.)).abs() < 1e-6);
+        assert!((linear.pixel(0, 0).x - 128. / 255.).abs() < 1e-6);
+    }
+
+    #[test]
+    fn image_keeps_high_dynamic_range() {
+        let testee =
+            Image::from_rgb_f32(2, 1, vec![0., 0., 0., 8., 8., 8.]).filter(Filter::Bicubic);
+
+        assert!((testee.value((0.75, 0.5), &Point3::zero()).x - 8.).abs() < 1e-5);
+        assert!((testee.value((0.5, 0.5), &Point3::zero()).x - 4.).abs() < 1e-5);
+    }
+
+    #[test]
+    fn image_nearest_filter() {
+        let testee = black_and_white();
+
+        assert_eq!(testee.value((0.4, 0.5), &Point3::zero()).x, 0.);
+        assert_eq!(testee.value((0.6, 0.5), &Point3::zero()).x, 1.);
+    }
+
+    #[test]
+    fn image_bilinear_filter() {
+        let testee = black_and_white().filter(Filter::Bilinear);
+
+        assert!((testee.value((0.5, 0.5), &Point3::zero()).x - 0.5).abs() < 1e-6);
+        assert_eq!(testee.value((0.25, 0.5), &Point3::zero()).x, 0.);
+        assert_eq!(testee.value((0.75, 0.5), &Point3::zero()).x, 1.);
+    }
 
-        let mut i = (texture_coordinates.0 * self.width as f32) as usize;
-        let mut j = (texture_coordinates.1 * self.height as f32) as usize;
+    #[test]
+    fn image_bicubic_filter_interpolates_pixels() {
+        let testee = black_and_white().filter(Filter::Bicubic);
 
-        if i >= self.width {
-            i = self.width - 1;
+        assert!(testee.value((0.25, 0.5), &Point3::zero()).x.abs() < 1e-6);
+        assert!((testee.value((0.75, 0.5), &Point3::zero()).x - 1.).abs() < 1e-6);
+        assert!((testee.value((0.5, 0.5), &Point3::zero()).x - 0.5).abs() < 1e-6);
+    }
+
+    /// A 4x4 image of alternating black and white columns.
+    fn stripes() -> Image {
+        let data = (0..16)
+            .flat_map(|i| if i % 2 == 0 { [0; 3] } else { [255; 3] })
+            .collect();
+        Image::new(4, 4, data)
+    }
+
+    #[test]
+    fn image_mipmaps_average_pixels() {
+        let testee = stripes().filter(Filter::Trilinear);
+
+        assert_eq!(testee.mipmaps.len(), 2);
+        assert_eq!(testee.level_size(1), (2, 2));
+        assert_eq!(testee.level_size(2), (1, 1));
+        assert_eq!(testee.level_pixel(2, 0, 0), Vec3::new(0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn image_filters_large_footprints() {
+        // A footprint as large as the image averages all its pixels
+        let derivatives = UvDerivatives {
+            dudx: 1.,
+            dvdx: 0.,
+            dudy: 0.,
+            dvdy: 1.,
+        };
+
+        for filter in [Filter::Trilinear, Filter::Ewa] {
+            let testee = stripes().filter(filter);
+            let color = testee.filtered_value((0.375, 0.5), &Point3::zero(), &derivatives);
+            assert!((color.x - 0.5).abs() < 1e-2);
         }
-        if j >= self.height {
-            j = self.height - 1;
+    }
+
+    #[test]
+    fn image_filters_small_footprints() {
+        // A footprint smaller than a pixel keeps the details
+        let derivatives = UvDerivatives {
+            dudx: 0.01,
+            dvdx: 0.,
+            dudy: 0.,
+            dvdy: 0.01,
+        };
+
+        for filter in [Filter::Trilinear, Filter::Ewa] {
+            let testee = stripes().filter(filter);
+            let color = testee.filtered_value((0.375, 0.5), &Point3::zero(), &derivatives);
+            assert!((color.x - 1.).abs() < 1e-2);
         }
-        j = self.height - 1 - j;
+    }
+
+    #[test]
+    fn image_wrap_modes() {
+        let repeat = black_and_white().wrap(Wrap::Repeat);
+        let clamp = black_and_white().wrap(Wrap::Clamp);
+        let mirror = black_and_white().wrap(Wrap::Mirror);
 
-        let color_scale = 1. / 255.;
-        let pixel = i * 3 + j * 3 * self.width;
-        Vec3::new(
-            color_scale * f32::from(self.data[pixel]),
-            color_scale * f32::from(self.data[pixel + 1]),
-            color_scale * f32::from(self.data[pixel + 2]),
-        )
+        assert_eq!(repeat.value((1.25, 0.5), &Point3::zero()).x, 0.);
+        assert_eq!(clamp.value((1.25, 0.5), &Point3::zero()).x, 1.);
+        assert_eq!(mirror.value((1.25, 0.5), &Point3::zero()).x, 1.);
+        assert_eq!(mirror.value((1.75, 0.5), &Point3::zero()).x, 0.);
+        assert_eq!(repeat.value((-0.25, 0.5), &Point3::zero()).x, 1.);
     }
 }
@@ -0,0 +1,89 @@
+use alloc::boxed::Box;
+
+use super::Texture;
+use crate::hitable::{HitRecord, UvDerivatives};
+use crate::vec::{Point3, Vec3};
+
+/// A texture subtracting the color channels of a texture from 1, turning black into white.
+#[derive(Debug)]
+pub struct Invert {
+    texture: Box<dyn Texture>,
+}
+
+impl Invert {
+    /// Constructs a new `Invert` of the texture.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Invert, Monochrome, Texture};
+    /// use crab_rt::vec::{Color3, Point3};
+    ///
+    /// let texture = Invert::new(Monochrome::from_rgb(0., 0.25, 1.));
+    /// assert_eq!(
+    ///     texture.value((0., 0.), &Point3::zero()),
+    ///     Color3::new(1., 0.75, 0.)
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new<T: 'static + Texture>(texture: T) -> Self {
+        Self {
+            texture: Box::new(texture),
+        }
+    }
+}
+
+impl Texture for Invert {
+    fn value(&self, texture_coordinates: (f32, f32), p: &Point3) -> Vec3 {
+        Vec3::new(1., 1., 1.) - self.texture.value(texture_coordinates, p)
+    }
+
+    fn filtered_value(
+        &self,
+        texture_coordinates: (f32, f32),
+        p: &Point3,
+        derivatives: &UvDerivatives,
+    ) -> Vec3 {
+        Vec3::new(1., 1., 1.)
+            - self
+                .texture
+                .filtered_value(texture_coordinates, p, derivatives)
+    }
+
+    fn value_from_hit(&self, record: &HitRecord<'_>) -> Vec3 {
+        Vec3::new(1., 1., 1.) - self.texture.value_from_hit(record)
+    }
+
+    fn alpha(&self, texture_coordinates: (f32, f32), p: &Point3) -> f32 {
+        self.texture.alpha(texture_coordinates, p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::textures::{Filter, Image};
+    use std::vec;
+
+    #[test]
+    fn invert_inverts_values() {
+        // Black and opaque on the left, white and transparent on the right
+        let image =
+            Image::from_rgba(2, 1, vec![0, 0, 0, 255, 255, 255, 255, 0]).filter(Filter::Trilinear);
+        let testee = Invert::new(image);
+        let footprint = UvDerivatives {
+            dudx: 1.,
+            dvdx: 0.,
+            dudy: 0.,
+            dvdy: 1.,
+        };
+
+        let left = (0.25, 0.5);
+        assert!((testee.value(left, &Point3::zero()).x - 1.).abs() < 1e-6);
+        // The footprint covers the whole image
+        let filtered = testee.filtered_value(left, &Point3::zero(), &footprint);
+        assert!((filtered.x - 0.5).abs() < 1e-2);
+        assert!((testee.alpha(left, &Point3::zero()) - 1.).abs() < 1e-6);
+        assert!(testee.alpha((0.75, 0.5), &Point3::zero()) < 1e-6);
+    }
+}
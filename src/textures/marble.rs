@@ -0,0 +1,104 @@
+use super::Texture;
+use crate::perlin::Perlin;
+use crate::vec::{Point3, Vec3};
+
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
+/// A grey texture of marble veins in range [0, 1], stripes along the z axis distorted by
+/// turbulence.
+///
+/// Wrap it in a [`ColorRamp`](super::ColorRamp) to color the veins.
+#[derive(Debug)]
+pub struct Marble {
+    noise: Perlin,
+    /// Frequency of the stripes.
+    scale: f32,
+    /// Distortion of the stripes.
+    turbulence: f32,
+}
+
+impl Marble {
+    /// Constructs a new `Marble` texture generated from `seed`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{ColorRamp, Marble};
+    /// use crab_rt::vec::Color3;
+    ///
+    /// let marble = ColorRamp::new(
+    ///     Marble::new(7).scale(4.),
+    ///     vec![
+    ///         (0., Color3::new(0.2, 0.25, 0.3)),
+    ///         (0.3, Color3::new(0.8, 0.8, 0.8)),
+    ///         (1., Color3::new(0.95, 0.95, 0.9)),
+    ///     ],
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self {
+            noise: Perlin::with_seed(seed),
+            scale: 1.,
+            turbulence: 10.,
+        }
+    }
+
+    /// Consumes the `Marble` and returns self with the frequency of the stripes multiplied by
+    /// `scale`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::Marble;
+    ///
+    /// let texture = Marble::new(7).scale(4.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn scale(self, scale: f32) -> Self {
+        Self { scale, ..self }
+    }
+
+    /// Consumes the `Marble` and returns self with the stripes distorted by `turbulence`. The
+    /// default is 10.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::Marble;
+    ///
+    /// let texture = Marble::new(7).turbulence(4.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn turbulence(self, turbulence: f32) -> Self {
+        Self { turbulence, ..self }
+    }
+}
+
+impl Texture for Marble {
+    #[inline]
+    fn value(&self, _texture_coordinates: (f32, f32), p: &Point3) -> Vec3 {
+        let phase = self
+            .scale
+            .mul_add(p.z, self.turbulence * self.noise.turbulence(p));
+        let value = f32::sin(phase).mul_add(0.5, 0.5);
+        Vec3::new(value, value, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marble_stripes_repeat_along_z() {
+        let testee = Marble::new(42).scale(2.).turbulence(0.);
+        let value = |z: f32| testee.value((0., 0.), &Point3::new(0.3, -1.2, z)).x;
+
+        for z in [0.1, 0.6, 1.7] {
+            assert!((0. ..=1.).contains(&value(z)));
+            assert!((value(z) - value(z + core::f32::consts::PI)).abs() < 1e-4);
+        }
+    }
+}
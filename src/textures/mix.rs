@@ -0,0 +1,150 @@
+use alloc::boxed::Box;
+
+use super::{Monochrome, Texture};
+use crate::hitable::{HitRecord, UvDerivatives};
+use crate::vec::{Point3, Vec3};
+
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
+/// A texture blending two textures, by a constant factor or by the mean of the color channels
+/// of a mask texture, 0 giving the first texture and 1 the second one.
+///
+/// The alphas of the textures are blended by the same factor.
+#[derive(Debug)]
+pub struct Mix {
+    a: Box<dyn Texture>,
+    b: Box<dyn Texture>,
+    mask: Box<dyn Texture>,
+}
+
+impl Mix {
+    /// Constructs a new `Mix` of the textures by a constant `factor`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Mix, Monochrome, Texture};
+    /// use crab_rt::vec::{Color3, Point3};
+    ///
+    /// let texture = Mix::new(
+    ///     Monochrome::from_rgb(1., 0., 0.),
+    ///     Monochrome::from_rgb(0., 0., 1.),
+    ///     0.25,
+    /// );
+    /// assert_eq!(
+    ///     texture.value((0., 0.), &Point3::zero()),
+    ///     Color3::new(0.75, 0., 0.25)
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new<A: 'static + Texture, B: 'static + Texture>(a: A, b: B, factor: f32) -> Self {
+        Self::with_mask(a, b, Monochrome::from_rgb(factor, factor, factor))
+    }
+
+    /// Constructs a new `Mix` of the textures by the mean of the color channels of `mask`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Fbm, Mix, Monochrome};
+    ///
+    /// let texture = Mix::with_mask(
+    ///     Monochrome::from_rgb(0.2, 0.4, 0.1),
+    ///     Monochrome::from_rgb(0.5, 0.4, 0.3),
+    ///     Fbm::new(7).scale(4.),
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_mask<A, B, M>(a: A, b: B, mask: M) -> Self
+    where
+        A: 'static + Texture,
+        B: 'static + Texture,
+        M: 'static + Texture,
+    {
+        Self {
+            a: Box::new(a),
+            b: Box::new(b),
+            mask: Box::new(mask),
+        }
+    }
+
+    /// Returns the blending factor of a color of the mask, the mean of its color channels.
+    #[inline]
+    fn factor(mask: Vec3) -> f32 {
+        (mask.x + mask.y + mask.z) / 3.
+    }
+
+    /// Returns the blend of the two colors by the mean of the color channels of the mask.
+    #[inline]
+    fn blend(a: Vec3, b: Vec3, mask: Vec3) -> Vec3 {
+        let factor = Self::factor(mask);
+        (1. - factor) * a + factor * b
+    }
+}
+
+impl Texture for Mix {
+    fn value(&self, texture_coordinates: (f32, f32), p: &Point3) -> Vec3 {
+        Self::blend(
+            self.a.value(texture_coordinates, p),
+            self.b.value(texture_coordinates, p),
+            self.mask.value(texture_coordinates, p),
+        )
+    }
+
+    fn filtered_value(
+        &self,
+        texture_coordinates: (f32, f32),
+        p: &Point3,
+        derivatives: &UvDerivatives,
+    ) -> Vec3 {
+        Self::blend(
+            self.a.filtered_value(texture_coordinates, p, derivatives),
+            self.b.filtered_value(texture_coordinates, p, derivatives),
+            self.mask
+                .filtered_value(texture_coordinates, p, derivatives),
+        )
+    }
+
+    fn value_from_hit(&self, record: &HitRecord<'_>) -> Vec3 {
+        Self::blend(
+            self.a.value_from_hit(record),
+            self.b.value_from_hit(record),
+            self.mask.value_from_hit(record),
+        )
+    }
+
+    fn alpha(&self, texture_coordinates: (f32, f32), p: &Point3) -> f32 {
+        let factor = Self::factor(self.mask.value(texture_coordinates, p));
+        (1. - factor).mul_add(
+            self.a.alpha(texture_coordinates, p),
+            factor * self.b.alpha(texture_coordinates, p),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::textures::{Image, Invert, Multiply, OpacityMask, Remap, Scale};
+    use std::vec;
+
+    #[test]
+    fn combinators_keep_alpha_of_nested_images() {
+        let image = || Image::from_rgba(2, 1, vec![255, 0, 0, 0, 0, 0, 255, 255]);
+        let testee = OpacityMask::alpha(Scale::new(
+            Remap::new(
+                Invert::new(Multiply::new(image(), Monochrome::from_rgb(0.5, 0.5, 0.5))),
+                (0., 1.),
+                (0., 2.),
+            ),
+            0.5,
+        ));
+        assert_eq!(testee.opacity((0.25, 0.5), &Point3::zero()), 0.);
+        assert_eq!(testee.opacity((0.75, 0.5), &Point3::zero()), 1.);
+
+        let testee = Mix::new(image(), Monochrome::from_rgb(1., 1., 1.), 0.25);
+        assert!((testee.alpha((0.25, 0.5), &Point3::zero()) - 0.25).abs() < 1e-6);
+        assert!((testee.alpha((0.75, 0.5), &Point3::zero()) - 1.).abs() < 1e-6);
+    }
+}
@@ -1,11 +1,39 @@
+pub mod add;
 pub mod checker;
+pub mod color_ramp;
+pub mod fbm;
 pub mod image;
+pub mod invert;
+pub mod marble;
+pub mod mix;
 pub mod monochrome;
+pub mod multiply;
 pub mod noise;
+pub mod opacity_mask;
+pub mod remap;
+pub mod scale;
 pub mod texture;
+pub mod triplanar;
+pub mod uv_transform;
+pub mod voronoi;
+pub mod wood;
 
-pub use self::image::Image;
+pub use self::image::{ColorSpace, Filter, Image, Wrap};
+pub use add::Add;
 pub use checker::Checker;
+pub use color_ramp::ColorRamp;
+pub use fbm::Fbm;
+pub use invert::Invert;
+pub use marble::Marble;
+pub use mix::Mix;
 pub use monochrome::Monochrome;
+pub use multiply::Multiply;
 pub use noise::Noise;
+pub use opacity_mask::OpacityMask;
+pub use remap::Remap;
+pub use scale::Scale;
 pub use texture::Texture;
+pub use triplanar::Triplanar;
+pub use uv_transform::UvTransform;
+pub use voronoi::{Feature, Voronoi};
+pub use wood::Wood;
@@ -0,0 +1,86 @@
+use alloc::boxed::Box;
+
+use super::Texture;
+use crate::hitable::{HitRecord, UvDerivatives};
+use crate::vec::{Point3, Vec3};
+
+/// A texture multiplying the color channels of two textures, to tint or darken a texture.
+///
+/// Its alpha is the product of the alphas of the textures.
+#[derive(Debug)]
+pub struct Multiply {
+    a: Box<dyn Texture>,
+    b: Box<dyn Texture>,
+}
+
+impl Multiply {
+    /// Constructs a new `Multiply` of the textures.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Monochrome, Multiply, Texture, Voronoi};
+    ///
+    /// let texture = Multiply::new(Monochrome::from_rgb(0.8, 0.5, 0.3), Voronoi::new(7));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new<A: 'static + Texture, B: 'static + Texture>(a: A, b: B) -> Self {
+        Self {
+            a: Box::new(a),
+            b: Box::new(b),
+        }
+    }
+}
+
+impl Texture for Multiply {
+    fn value(&self, texture_coordinates: (f32, f32), p: &Point3) -> Vec3 {
+        self.a.value(texture_coordinates, p) * self.b.value(texture_coordinates, p)
+    }
+
+    fn filtered_value(
+        &self,
+        texture_coordinates: (f32, f32),
+        p: &Point3,
+        derivatives: &UvDerivatives,
+    ) -> Vec3 {
+        self.a.filtered_value(texture_coordinates, p, derivatives)
+            * self.b.filtered_value(texture_coordinates, p, derivatives)
+    }
+
+    fn value_from_hit(&self, record: &HitRecord<'_>) -> Vec3 {
+        self.a.value_from_hit(record) * self.b.value_from_hit(record)
+    }
+
+    fn alpha(&self, texture_coordinates: (f32, f32), p: &Point3) -> f32 {
+        self.a.alpha(texture_coordinates, p) * self.b.alpha(texture_coordinates, p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::textures::{Filter, Image, Monochrome};
+    use std::vec;
+
+    #[test]
+    fn multiply_multiplies_values() {
+        // Black and opaque on the left, white and transparent on the right
+        let image =
+            Image::from_rgba(2, 1, vec![0, 0, 0, 255, 255, 255, 255, 0]).filter(Filter::Trilinear);
+        let testee = Multiply::new(image, Monochrome::from_rgb(0.5, 0.5, 0.5));
+        let footprint = UvDerivatives {
+            dudx: 1.,
+            dvdx: 0.,
+            dudy: 0.,
+            dvdy: 1.,
+        };
+
+        let left = (0.25, 0.5);
+        assert!((testee.value((0.75, 0.5), &Point3::zero()).x - 0.5).abs() < 1e-6);
+        // The footprint covers the whole image
+        let filtered = testee.filtered_value(left, &Point3::zero(), &footprint);
+        assert!((filtered.x - 0.25).abs() < 1e-2);
+        assert!((testee.alpha(left, &Point3::zero()) - 1.).abs() < 1e-6);
+        assert!(testee.alpha((0.75, 0.5), &Point3::zero()) < 1e-6);
+    }
+}
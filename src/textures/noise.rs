@@ -20,6 +20,24 @@ impl Noise {
             scale,
         }
     }
+
+    /// Constructs a new `Noise` texture generated from `seed`, so that the same seed always
+    /// gives the same texture.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::Noise;
+    ///
+    /// let texture = Noise::with_seed(4., 7);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_seed(scale: f32, seed: u64) -> Self {
+        Self {
+            noise: Perlin::with_seed(seed),
+            scale,
+        }
+    }
 }
 
 impl Texture for Noise {
@@ -0,0 +1,184 @@
+use alloc::boxed::Box;
+use rand::Rng;
+
+use super::Texture;
+use crate::utils::rng;
+use crate::vec::Point3;
+
+const DEFAULT_THRESHOLD: f32 = 0.5;
+
+/// How the opacity of a texture is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Channel {
+    /// The alpha channel of the texture.
+    Alpha,
+    /// The mean of the color channels of the texture.
+    Luminance,
+}
+
+/// How the opacity decides whether a point is opaque.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    /// Points whose opacity is below the threshold are transparent.
+    Threshold(f32),
+    /// Points are opaque with a probability equal to their opacity.
+    Stochastic,
+}
+
+/// A mask deciding from a texture which points of a surface are opaque, used to cut out
+/// transparent regions of foliage or fences modeled as textured quads.
+#[derive(Debug)]
+pub struct OpacityMask {
+    texture: Box<dyn Texture>,
+    channel: Channel,
+    mode: Mode,
+}
+
+impl OpacityMask {
+    /// Constructs a new `OpacityMask` from the alpha channel of the texture.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Image, OpacityMask};
+    ///
+    /// let mask = OpacityMask::alpha(Image::from_rgba(1, 1, vec![0, 255, 0, 255]));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn alpha<T: 'static + Texture>(texture: T) -> Self {
+        Self {
+            texture: Box::new(texture),
+            channel: Channel::Alpha,
+            mode: Mode::Threshold(DEFAULT_THRESHOLD),
+        }
+    }
+
+    /// Constructs a new `OpacityMask` from the mean of the color channels of the texture, black
+    /// being transparent and white opaque.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Checker, OpacityMask};
+    /// use crab_rt::vec::Color3;
+    ///
+    /// let mask = OpacityMask::luminance(Checker::from_colors(
+    ///     Color3::new(0., 0., 0.),
+    ///     Color3::new(1., 1., 1.),
+    /// ));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn luminance<T: 'static + Texture>(texture: T) -> Self {
+        Self {
+            channel: Channel::Luminance,
+            ..Self::alpha(texture)
+        }
+    }
+
+    /// Consumes the `OpacityMask` and returns self where points whose opacity is below
+    /// `threshold` are transparent. This is the default with a threshold of 0.5.
+    ///
+    /// # Panics
+    /// Panics if `threshold` is not in range [0, 1].
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Image, OpacityMask};
+    ///
+    /// let mask = OpacityMask::alpha(Image::from_rgba(1, 1, vec![0, 255, 0, 255])).threshold(0.1);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn threshold(self, threshold: f32) -> Self {
+        assert!(
+            (0. ..=1.).contains(&threshold),
+            "threshold should be in range [0, 1]"
+        );
+
+        Self {
+            mode: Mode::Threshold(threshold),
+            ..self
+        }
+    }
+
+    /// Consumes the `OpacityMask` and returns self where points are opaque with a probability
+    /// equal to their opacity, which renders semi-transparent regions on average.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Image, OpacityMask};
+    ///
+    /// let mask = OpacityMask::alpha(Image::from_rgba(1, 1, vec![0, 255, 0, 128])).stochastic();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn stochastic(self) -> Self {
+        Self {
+            mode: Mode::Stochastic,
+            ..self
+        }
+    }
+
+    /// Returns the opacity of the point in range [0, 1].
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Image, OpacityMask};
+    /// use crab_rt::vec::Point3;
+    ///
+    /// let mask = OpacityMask::alpha(Image::from_rgba(1, 1, vec![0, 255, 0, 51]));
+    /// assert_eq!(mask.opacity((0.5, 0.5), &Point3::zero()), 0.2);
+    /// ```
+    #[must_use]
+    pub fn opacity(&self, texture_coordinates: (f32, f32), p: &Point3) -> f32 {
+        match self.channel {
+            Channel::Alpha => self.texture.alpha(texture_coordinates, p),
+            Channel::Luminance => {
+                let color = self.texture.value(texture_coordinates, p);
+                (color.x + color.y + color.z) / 3.
+            }
+        }
+    }
+
+    /// Returns the probability of the point being opaque, which is its opacity with a stochastic
+    /// mask and either 0 or 1 with a threshold.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Image, OpacityMask};
+    /// use crab_rt::vec::Point3;
+    ///
+    /// let mask = OpacityMask::alpha(Image::from_rgba(1, 1, vec![0, 255, 0, 51]));
+    /// assert_eq!(mask.coverage((0.5, 0.5), &Point3::zero()), 0.);
+    /// assert_eq!(mask.stochastic().coverage((0.5, 0.5), &Point3::zero()), 0.2);
+    /// ```
+    #[must_use]
+    pub fn coverage(&self, texture_coordinates: (f32, f32), p: &Point3) -> f32 {
+        let opacity = self.opacity(texture_coordinates, p);
+        match self.mode {
+            Mode::Threshold(threshold) if opacity >= threshold => 1.,
+            Mode::Threshold(_) => 0.,
+            Mode::Stochastic => opacity,
+        }
+    }
+
+    /// Returns whether the point is opaque.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Image, OpacityMask};
+    /// use crab_rt::vec::Point3;
+    ///
+    /// let mask = OpacityMask::alpha(Image::from_rgba(2, 1, vec![0, 0, 0, 0, 0, 0, 0, 255]));
+    /// assert!(!mask.is_opaque((0.25, 0.5), &Point3::zero()));
+    /// assert!(mask.is_opaque((0.75, 0.5), &Point3::zero()));
+    /// ```
+    #[must_use]
+    pub fn is_opaque(&self, texture_coordinates: (f32, f32), p: &Point3) -> bool {
+        let opacity = self.opacity(texture_coordinates, p);
+        match self.mode {
+            Mode::Threshold(threshold) => opacity >= threshold,
+            Mode::Stochastic => rng().gen::<f32>() < opacity,
+        }
+    }
+}
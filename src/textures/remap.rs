@@ -0,0 +1,148 @@
+use alloc::boxed::Box;
+
+use super::Texture;
+use crate::hitable::{HitRecord, UvDerivatives};
+use crate::vec::{Point3, Vec3};
+
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
+/// A texture linearly mapping the color channels of a texture from one range to another, to
+/// adjust the contrast of a texture or convert it to a range of heights or roughnesses.
+#[derive(Debug)]
+pub struct Remap {
+    texture: Box<dyn Texture>,
+    from: (f32, f32),
+    to: (f32, f32),
+    /// Whether the results are clamped to the target range.
+    clamp: bool,
+}
+
+impl Remap {
+    /// Constructs a new `Remap` of the texture mapping the range `from` to the range `to`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Monochrome, Remap, Texture};
+    /// use crab_rt::vec::{Color3, Point3};
+    ///
+    /// let texture = Remap::new(
+    ///     Monochrome::from_rgb(0.25, 0.5, 0.75),
+    ///     (0.25, 0.75),
+    ///     (0., 2.),
+    /// );
+    /// assert_eq!(
+    ///     texture.value((0., 0.), &Point3::zero()),
+    ///     Color3::new(0., 1., 2.)
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new<T: 'static + Texture>(texture: T, from: (f32, f32), to: (f32, f32)) -> Self {
+        Self {
+            texture: Box::new(texture),
+            from,
+            to,
+            clamp: false,
+        }
+    }
+
+    /// Consumes the `Remap` and returns self with the results clamped to the target range.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Monochrome, Remap, Texture};
+    /// use crab_rt::vec::{Color3, Point3};
+    ///
+    /// let texture = Remap::new(Monochrome::from_rgb(0., 0.5, 1.), (0.25, 0.75), (0., 1.)).clamp();
+    /// assert_eq!(
+    ///     texture.value((0., 0.), &Point3::zero()),
+    ///     Color3::new(0., 0.5, 1.)
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn clamp(self) -> Self {
+        Self {
+            clamp: true,
+            ..self
+        }
+    }
+
+    /// Returns the remapped value of a color channel.
+    #[inline]
+    fn remap(&self, x: f32) -> f32 {
+        let t = (x - self.from.0) / (self.from.1 - self.from.0);
+        let y = t.mul_add(self.to.1 - self.to.0, self.to.0);
+        if self.clamp {
+            y.clamp(self.to.0.min(self.to.1), self.to.0.max(self.to.1))
+        } else {
+            y
+        }
+    }
+
+    /// Returns the remapped color.
+    #[inline]
+    fn remap_color(&self, color: Vec3) -> Vec3 {
+        Vec3::new(
+            self.remap(color.x),
+            self.remap(color.y),
+            self.remap(color.z),
+        )
+    }
+}
+
+impl Texture for Remap {
+    fn value(&self, texture_coordinates: (f32, f32), p: &Point3) -> Vec3 {
+        self.remap_color(self.texture.value(texture_coordinates, p))
+    }
+
+    fn filtered_value(
+        &self,
+        texture_coordinates: (f32, f32),
+        p: &Point3,
+        derivatives: &UvDerivatives,
+    ) -> Vec3 {
+        self.remap_color(
+            self.texture
+                .filtered_value(texture_coordinates, p, derivatives),
+        )
+    }
+
+    fn value_from_hit(&self, record: &HitRecord<'_>) -> Vec3 {
+        self.remap_color(self.texture.value_from_hit(record))
+    }
+
+    fn alpha(&self, texture_coordinates: (f32, f32), p: &Point3) -> f32 {
+        self.texture.alpha(texture_coordinates, p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::textures::{Filter, Image};
+    use std::vec;
+
+    #[test]
+    fn remap_maps_values_to_range() {
+        // Black and opaque on the left, white and transparent on the right
+        let image =
+            Image::from_rgba(2, 1, vec![0, 0, 0, 255, 255, 255, 255, 0]).filter(Filter::Trilinear);
+        let testee = Remap::new(image, (0., 1.), (0.5, 1.));
+        let footprint = UvDerivatives {
+            dudx: 1.,
+            dvdx: 0.,
+            dudy: 0.,
+            dvdy: 1.,
+        };
+
+        let left = (0.25, 0.5);
+        assert!((testee.value(left, &Point3::zero()).x - 0.5).abs() < 1e-6);
+        // The footprint covers the whole image
+        let filtered = testee.filtered_value(left, &Point3::zero(), &footprint);
+        assert!((filtered.x - 0.75).abs() < 1e-2);
+        assert!((testee.alpha(left, &Point3::zero()) - 1.).abs() < 1e-6);
+        assert!(testee.alpha((0.75, 0.5), &Point3::zero()) < 1e-6);
+    }
+}
@@ -0,0 +1,91 @@
+use alloc::boxed::Box;
+
+use super::Texture;
+use crate::hitable::{HitRecord, UvDerivatives};
+use crate::vec::{Point3, Vec3};
+
+/// A texture multiplying the color channels of a texture by a constant factor.
+#[derive(Debug)]
+pub struct Scale {
+    texture: Box<dyn Texture>,
+    factor: f32,
+}
+
+impl Scale {
+    /// Constructs a new `Scale` of the texture by `factor`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Monochrome, Scale, Texture};
+    /// use crab_rt::vec::{Color3, Point3};
+    ///
+    /// let texture = Scale::new(Monochrome::from_rgb(0.5, 0.25, 1.), 2.);
+    /// assert_eq!(
+    ///     texture.value((0., 0.), &Point3::zero()),
+    ///     Color3::new(1., 0.5, 2.)
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new<T: 'static + Texture>(texture: T, factor: f32) -> Self {
+        Self {
+            texture: Box::new(texture),
+            factor,
+        }
+    }
+}
+
+impl Texture for Scale {
+    fn value(&self, texture_coordinates: (f32, f32), p: &Point3) -> Vec3 {
+        self.factor * self.texture.value(texture_coordinates, p)
+    }
+
+    fn filtered_value(
+        &self,
+        texture_coordinates: (f32, f32),
+        p: &Point3,
+        derivatives: &UvDerivatives,
+    ) -> Vec3 {
+        self.factor
+            * self
+                .texture
+                .filtered_value(texture_coordinates, p, derivatives)
+    }
+
+    fn value_from_hit(&self, record: &HitRecord<'_>) -> Vec3 {
+        self.factor * self.texture.value_from_hit(record)
+    }
+
+    fn alpha(&self, texture_coordinates: (f32, f32), p: &Point3) -> f32 {
+        self.texture.alpha(texture_coordinates, p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::textures::{Filter, Image};
+    use std::vec;
+
+    #[test]
+    fn scale_scales_values() {
+        // Black and opaque on the left, white and transparent on the right
+        let image =
+            Image::from_rgba(2, 1, vec![0, 0, 0, 255, 255, 255, 255, 0]).filter(Filter::Trilinear);
+        let testee = Scale::new(image, 2.);
+        let footprint = UvDerivatives {
+            dudx: 1.,
+            dvdx: 0.,
+            dudy: 0.,
+            dvdy: 1.,
+        };
+
+        let left = (0.25, 0.5);
+        assert!((testee.value(left, &Point3::zero()).x - 0.).abs() < 1e-6);
+        // The footprint covers the whole image
+        let filtered = testee.filtered_value(left, &Point3::zero(), &footprint);
+        assert!((filtered.x - 1.).abs() < 1e-2);
+        assert!((testee.alpha(left, &Point3::zero()) - 1.).abs() < 1e-6);
+        assert!(testee.alpha((0.75, 0.5), &Point3::zero()) < 1e-6);
+    }
+}
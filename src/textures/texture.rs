@@ -1,15 +1,91 @@
+use alloc::boxed::Box;
 use core::fmt::Debug;
 
-use crate::hitable::HitRecord;
+use crate::hitable::{HitRecord, UvDerivatives};
 use crate::vec::{Point3, Vec3};
 
 pub trait Texture: Debug + Send + Sync {
     #[must_use]
     fn value(&self, texture_coordinates: (f32, f32), p: &Point3) -> Vec3;
 
+    /// Returns the color averaged over the footprint of a pixel around the texture coordinates,
+    /// given by the derivatives of the texture coordinates along the image axes.
+    ///
+    /// Textures that do not alias return their value at the texture coordinates.
+    #[allow(unused_variables)]
+    #[inline]
+    #[must_use]
+    fn filtered_value(
+        &self,
+        texture_coordinates: (f32, f32),
+        p: &Point3,
+        derivatives: &UvDerivatives,
+    ) -> Vec3 {
+        self.value(texture_coordinates, p)
+    }
+
     #[inline(always)]
     #[must_use]
     fn value_from_hit(&self, record: &HitRecord<'_>) -> Vec3 {
-        self.value(record.texture_coordinates(), record.hit_point())
+        record.uv_derivatives().map_or_else(
+            || self.value(record.texture_coordinates(), record.hit_point()),
+            |derivatives| {
+                self.filtered_value(
+                    record.texture_coordinates(),
+                    record.hit_point(),
+                    derivatives,
+                )
+            },
+        )
+    }
+
+    /// Returns the opacity of the texture in range [0, 1], textures without alpha channel being
+    /// fully opaque.
+    #[allow(unused_variables)]
+    #[inline]
+    #[must_use]
+    fn alpha(&self, texture_coordinates: (f32, f32), p: &Point3) -> f32 {
+        1.
+    }
+}
+
+/// Boxed textures are textures, so that texture networks built at runtime can be nested into
+/// the texture combinators.
+///
+/// # Examples
+/// ```
+/// use crab_rt::textures::{Fbm, Invert, Mix, Monochrome, Texture};
+///
+/// let layers: Vec<Box<dyn Texture>> = vec![
+///     Box::new(Monochrome::from_rgb(0.8, 0.2, 0.1)),
+///     Box::new(Invert::new(Fbm::new(7))),
+/// ];
+/// let mut layers = layers.into_iter();
+/// let texture = Mix::new(layers.next().unwrap(), layers.next().unwrap(), 0.5);
+/// ```
+impl<T: Texture + ?Sized> Texture for Box<T> {
+    #[inline]
+    fn value(&self, texture_coordinates: (f32, f32), p: &Point3) -> Vec3 {
+        (**self).value(texture_coordinates, p)
+    }
+
+    #[inline]
+    fn filtered_value(
+        &self,
+        texture_coordinates: (f32, f32),
+        p: &Point3,
+        derivatives: &UvDerivatives,
+    ) -> Vec3 {
+        (**self).filtered_value(texture_coordinates, p, derivatives)
+    }
+
+    #[inline]
+    fn value_from_hit(&self, record: &HitRecord<'_>) -> Vec3 {
+        (**self).value_from_hit(record)
+    }
+
+    #[inline]
+    fn alpha(&self, texture_coordinates: (f32, f32), p: &Point3) -> f32 {
+        (**self).alpha(texture_coordinates, p)
     }
 }
@@ -0,0 +1,210 @@
+use alloc::boxed::Box;
+
+use super::Texture;
+use crate::hitable::{HitRecord, UvDerivatives};
+use crate::vec::{Point3, Vec3};
+
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
+/// A texture projecting a texture along the x, y and z axes and blending the projections by the
+/// normal of the surface.
+///
+/// It textures objects without texture coordinates or with stretched ones, such as heightfields
+/// or implicit surfaces.
+///
+/// The projections along x, y and z use the coordinates (z, y), (x, z) and (x, y) of the hit
+/// point as texture coordinates. Without the normal of a hit, the texture is projected along z.
+#[derive(Debug)]
+pub struct Triplanar {
+    texture: Box<dyn Texture>,
+    /// Texture coordinates per unit of distance.
+    scale: f32,
+    /// Exponent of the normal components in the blending weights, higher values giving sharper
+    /// transitions between the projections.
+    sharpness: f32,
+}
+
+impl Triplanar {
+    /// Constructs a new `Triplanar` projection of the texture, with a scale of 1 and a sharpness
+    /// of 4.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Checker, Triplanar};
+    /// use crab_rt::vec::Color3;
+    ///
+    /// let texture = Triplanar::new(
+    ///     Checker::from_colors(Color3::new(0., 0., 0.), Color3::new(1., 1., 1.)).texture_space(),
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new<T: 'static + Texture>(texture: T) -> Self {
+        Self {
+            texture: Box::new(texture),
+            scale: 1.,
+            sharpness: 4.,
+        }
+    }
+
+    /// Consumes the `Triplanar` and returns self with `scale` texture coordinates per unit of
+    /// distance.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Fbm, Triplanar};
+    ///
+    /// let texture = Triplanar::new(Fbm::new(7)).scale(0.5);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn scale(self, scale: f32) -> Self {
+        Self { scale, ..self }
+    }
+
+    /// Consumes the `Triplanar` and returns self with the given sharpness of the transitions
+    /// between the projections.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Fbm, Triplanar};
+    ///
+    /// let texture = Triplanar::new(Fbm::new(7)).sharpness(16.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn sharpness(self, sharpness: f32) -> Self {
+        Self { sharpness, ..self }
+    }
+
+    /// Returns the scaled derivatives of the hit point along the image axes, recovered from the
+    /// derivatives of its texture coordinates when the record has them.
+    #[allow(clippy::similar_names)]
+    fn position_derivatives(&self, record: &HitRecord<'_>) -> Option<(Vec3, Vec3)> {
+        let derivatives = record.uv_derivatives()?;
+        let (dpdu, dpdv) = (record.dpdu(), record.dpdv());
+
+        Some((
+            self.scale * (derivatives.dudx * dpdu + derivatives.dvdx * dpdv),
+            self.scale * (derivatives.dudy * dpdu + derivatives.dvdy * dpdv),
+        ))
+    }
+}
+
+impl Texture for Triplanar {
+    fn value(&self, _texture_coordinates: (f32, f32), p: &Point3) -> Vec3 {
+        self.texture.value((self.scale * p.x, self.scale * p.y), p)
+    }
+
+    fn value_from_hit(&self, record: &HitRecord<'_>) -> Vec3 {
+        let p = record.hit_point();
+        let normal = record.normal();
+
+        let weight = |n: f32| n.abs().powf(self.sharpness);
+        let weights = Vec3::new(weight(normal.x), weight(normal.y), weight(normal.z));
+        let weights = weights / (weights.x + weights.y + weights.z);
+
+        // Filters each projection over the footprint of the pixel projected along its axis
+        let derivatives = self.position_derivatives(record);
+        let project = |texture_coordinates, u: fn(&Vec3) -> f32, v: fn(&Vec3) -> f32| {
+            derivatives.map_or_else(
+                || self.texture.value(texture_coordinates, p),
+                |(dpdx, dpdy)| {
+                    let derivatives = UvDerivatives {
+                        dudx: u(&dpdx),
+                        dvdx: v(&dpdx),
+                        dudy: u(&dpdy),
+                        dvdy: v(&dpdy),
+                    };
+                    self.texture
+                        .filtered_value(texture_coordinates, p, &derivatives)
+                },
+            )
+        };
+
+        let (x, y, z) = (self.scale * p.x, self.scale * p.y, self.scale * p.z);
+        weights.x * project((z, y), |d| d.z, |d| d.y)
+            + weights.y * project((x, z), |d| d.x, |d| d.z)
+            + weights.z * project((x, y), |d| d.x, |d| d.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::materials::Lambertian;
+    use crate::ray::{Ray, RayDifferentials};
+
+    /// A texture returning its texture coordinates.
+    #[derive(Debug)]
+    struct Coordinates;
+
+    impl Texture for Coordinates {
+        fn value(&self, texture_coordinates: (f32, f32), _p: &Point3) -> Vec3 {
+            Vec3::new(texture_coordinates.0, texture_coordinates.1, 0.)
+        }
+    }
+
+    /// A texture returning the derivatives of its texture coordinates along x.
+    #[derive(Debug)]
+    struct Footprint;
+
+    impl Texture for Footprint {
+        fn value(&self, _texture_coordinates: (f32, f32), _p: &Point3) -> Vec3 {
+            Vec3::zero()
+        }
+
+        fn filtered_value(
+            &self,
+            _texture_coordinates: (f32, f32),
+            _p: &Point3,
+            derivatives: &UvDerivatives,
+        ) -> Vec3 {
+            Vec3::new(derivatives.dudx, derivatives.dvdx, 0.)
+        }
+    }
+
+    #[test]
+    fn triplanar_projects_along_normal() {
+        let material = Lambertian::default();
+        let testee = Triplanar::new(Coordinates);
+        let p = Point3::new(1., 2., 3.);
+
+        let record = HitRecord::new(1., p, Vec3::new(0., 1., 0.), (0., 0.), &material);
+        assert_eq!(testee.value_from_hit(&record), Vec3::new(1., 3., 0.));
+
+        // Diagonal normals blend the projections evenly
+        let normal = Vec3::new(1., 1., 1.).unit();
+        let record = HitRecord::new(1., p, normal, (0., 0.), &material);
+        let expected = (Vec3::new(3., 2., 0.) + Vec3::new(1., 3., 0.) + Vec3::new(1., 2., 0.)) / 3.;
+        assert!((testee.value_from_hit(&record) - expected).length() < 1e-5);
+    }
+
+    #[test]
+    fn triplanar_filters_projected_footprint() {
+        let material = Lambertian::default();
+        let testee = Triplanar::new(Footprint).scale(2.);
+        let ray = Ray::new(Point3::new(0., 1., 0.), Vec3::new(0., -1., 0.), 0.).with_differentials(
+            RayDifferentials {
+                rx_origin: Point3::new(0.1, 1., 0.),
+                rx_direction: Vec3::new(0., -1., 0.),
+                ry_origin: Point3::new(0., 1., 0.1),
+                ry_direction: Vec3::new(0., -1., 0.),
+            },
+        );
+
+        let mut record = HitRecord::new(
+            1.,
+            Point3::zero(),
+            Vec3::new(0., 1., 0.),
+            (0., 0.),
+            &material,
+        );
+        record.set_partial_derivatives(Vec3::new(4., 0., 0.), Vec3::new(0., 0., 4.));
+        record.compute_uv_derivatives(&ray);
+
+        // The footprint along x is projected on the xz plane and scaled
+        assert!((testee.value_from_hit(&record) - Vec3::new(0.2, 0., 0.)).length() < 1e-5);
+    }
+}
@@ -0,0 +1,197 @@
+use alloc::boxed::Box;
+
+use super::Texture;
+use crate::hitable::UvDerivatives;
+use crate::vec::{Point3, Vec3};
+
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
+/// A texture wrapper scaling, rotating and offsetting the texture coordinates of a texture,
+/// in this order.
+#[derive(Debug)]
+pub struct UvTransform {
+    texture: Box<dyn Texture>,
+    scale: (f32, f32),
+    /// Sine and cosine of the rotation angle.
+    rotation: (f32, f32),
+    offset: (f32, f32),
+}
+
+impl UvTransform {
+    /// Constructs a new `UvTransform` of the texture with the identity transform.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Checker, UvTransform};
+    /// use crab_rt::vec::Color3;
+    ///
+    /// // Tiles a checker 10 times in each direction
+    /// let texture = UvTransform::new(
+    ///     Checker::from_colors(Color3::new(0., 0., 0.), Color3::new(1., 1., 1.)).texture_space(),
+    /// )
+    /// .scale(10., 10.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new<T: 'static + Texture>(texture: T) -> Self {
+        Self {
+            texture: Box::new(texture),
+            scale: (1., 1.),
+            rotation: (0., 1.),
+            offset: (0., 0.),
+        }
+    }
+
+    /// Consumes the `UvTransform` and returns self with the texture coordinates scaled by the
+    /// given factors, which tiles the texture when it repeats.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Image, UvTransform, Wrap};
+    ///
+    /// let texture =
+    ///     UvTransform::new(Image::new(1, 1, vec![0, 0, 0]).wrap(Wrap::Repeat)).scale(4., 2.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn scale(self, u: f32, v: f32) -> Self {
+        Self {
+            scale: (u, v),
+            ..self
+        }
+    }
+
+    /// Consumes the `UvTransform` and returns self with the texture coordinates rotated by the
+    /// given angle in degrees.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Image, UvTransform};
+    ///
+    /// let texture = UvTransform::new(Image::new(1, 1, vec![0, 0, 0])).rotation(45.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn rotation(self, angle: f32) -> Self {
+        let theta = angle.to_radians();
+        Self {
+            rotation: (theta.sin(), theta.cos()),
+            ..self
+        }
+    }
+
+    /// Consumes the `UvTransform` and returns self with the given offset added to the texture
+    /// coordinates.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Image, UvTransform};
+    ///
+    /// let texture = UvTransform::new(Image::new(1, 1, vec![0, 0, 0])).offset(0.5, 0.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn offset(self, u: f32, v: f32) -> Self {
+        Self {
+            offset: (u, v),
+            ..self
+        }
+    }
+
+    /// Returns the transformed texture coordinates.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Image, UvTransform};
+    ///
+    /// let texture = UvTransform::new(Image::new(1, 1, vec![0, 0, 0]))
+    ///     .scale(2., 2.)
+    ///     .offset(0.5, 0.);
+    /// assert_eq!(texture.transform((0.5, 0.25)), (1.5, 0.5));
+    /// ```
+    #[must_use]
+    pub fn transform(&self, texture_coordinates: (f32, f32)) -> (f32, f32) {
+        let (u, v) = self.scale_and_rotate(texture_coordinates);
+
+        (u + self.offset.0, v + self.offset.1)
+    }
+
+    /// Applies the linear part of the transform, without the offset.
+    fn scale_and_rotate(&self, (u, v): (f32, f32)) -> (f32, f32) {
+        let u = u * self.scale.0;
+        let v = v * self.scale.1;
+        let (sin, cos) = self.rotation;
+
+        (cos.mul_add(u, -sin * v), sin.mul_add(u, cos * v))
+    }
+
+    /// Returns the derivatives of the transformed texture coordinates.
+    #[allow(clippy::similar_names)]
+    fn transform_derivatives(&self, derivatives: &UvDerivatives) -> UvDerivatives {
+        // The derivatives only go through the linear part of the transform
+        let (dudx, dvdx) = self.scale_and_rotate((derivatives.dudx, derivatives.dvdx));
+        let (dudy, dvdy) = self.scale_and_rotate((derivatives.dudy, derivatives.dvdy));
+
+        UvDerivatives {
+            dudx,
+            dvdx,
+            dudy,
+            dvdy,
+        }
+    }
+}
+
+impl Texture for UvTransform {
+    fn value(&self, texture_coordinates: (f32, f32), p: &Point3) -> Vec3 {
+        self.texture.value(self.transform(texture_coordinates), p)
+    }
+
+    fn filtered_value(
+        &self,
+        texture_coordinates: (f32, f32),
+        p: &Point3,
+        derivatives: &UvDerivatives,
+    ) -> Vec3 {
+        self.texture.filtered_value(
+            self.transform(texture_coordinates),
+            p,
+            &self.transform_derivatives(derivatives),
+        )
+    }
+
+    fn alpha(&self, texture_coordinates: (f32, f32), p: &Point3) -> f32 {
+        self.texture.alpha(self.transform(texture_coordinates), p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::textures::Checker;
+    use crate::vec::Color3;
+
+    fn checker() -> Checker {
+        Checker::from_colors(Color3::new(0., 0., 0.), Color3::new(1., 1., 1.)).texture_space()
+    }
+
+    #[test]
+    fn uv_transform_tiles_checker() {
+        // Squares of a quarter, turned by a quarter turn and shifted by half a square
+        let testee = UvTransform::new(checker())
+            .scale(4., 4.)
+            .rotation(90.)
+            .offset(0.5, 0.);
+        let color = |u, v| testee.value((u, v), &Point3::zero()).x;
+
+        assert_eq!(color(0.1, 0.1), 0.);
+        assert_eq!(color(0.35, 0.1), 1.);
+        assert_eq!(color(0.6, 0.1), 0.);
+        assert_eq!(color(0.1, 0.35), 1.);
+        assert_eq!(color(0.1, 0.6), 0.);
+
+        // The offset moves the squares by half a square along u after the rotation
+        let testee = UvTransform::new(checker()).scale(4., 4.).rotation(90.);
+        assert_eq!(testee.value((0.1, 0.1), &Point3::zero()).x, 1.);
+    }
+}
@@ -0,0 +1,108 @@
+use super::Texture;
+use crate::vec::{Point3, Vec3};
+use crate::worley::Worley;
+
+/// Which distance of Worley noise a [`Voronoi`] texture shows.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    /// The distance to the closest feature point, dark at the center of the cells.
+    #[default]
+    Closest,
+    /// The distance to the second closest feature point.
+    SecondClosest,
+    /// The difference between the two closest distances, dark at the borders of the cells.
+    Border,
+}
+
+/// A grey texture of Worley cellular noise, clamped to range [0, 1].
+#[derive(Debug)]
+pub struct Voronoi {
+    noise: Worley,
+    scale: f32,
+    feature: Feature,
+}
+
+impl Voronoi {
+    /// Constructs a new `Voronoi` texture generated from `seed`, with cells of size 1 showing
+    /// the distance to the closest feature point.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::Voronoi;
+    ///
+    /// let texture = Voronoi::new(7);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new(seed: u64) -> Self {
+        Self {
+            noise: Worley::new(seed),
+            scale: 1.,
+            feature: Feature::Closest,
+        }
+    }
+
+    /// Consumes the `Voronoi` and returns self with the size of the cells divided by `scale`.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::Voronoi;
+    ///
+    /// let texture = Voronoi::new(7).scale(8.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn scale(self, scale: f32) -> Self {
+        Self { scale, ..self }
+    }
+
+    /// Consumes the `Voronoi` and returns self showing the given feature.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{Feature, Voronoi};
+    ///
+    /// let cracks = Voronoi::new(7).feature(Feature::Border);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn feature(self, feature: Feature) -> Self {
+        Self { feature, ..self }
+    }
+}
+
+impl Texture for Voronoi {
+    fn value(&self, _texture_coordinates: (f32, f32), p: &Point3) -> Vec3 {
+        let (f1, f2) = self.noise.distances(&(self.scale * p));
+        let value = match self.feature {
+            Feature::Closest => f1,
+            Feature::SecondClosest => f2,
+            Feature::Border => f2 - f1,
+        }
+        .min(1.);
+
+        Vec3::new(value, value, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn voronoi_border_is_zero_between_cells() {
+        let testee = Voronoi::new(42).feature(Feature::Border);
+
+        // The closest feature point changes along any segment longer than 2 sqrt(3), where the
+        // distances to the two closest points are equal
+        let borders = (0..4000_u16)
+            .map(|n| {
+                let p = Point3::new(f32::from(n) * 0.001, 0.3, 0.7);
+                testee.value((0., 0.), &p).x
+            })
+            .collect::<Vec<_>>();
+        assert!(borders.iter().copied().fold(f32::INFINITY, f32::min) < 0.01);
+        assert!(borders.iter().copied().fold(0., f32::max) > 0.1);
+    }
+}
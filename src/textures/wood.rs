@@ -0,0 +1,103 @@
+use super::Texture;
+use crate::perlin::Perlin;
+use crate::vec::{Point3, Vec3};
+
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
+/// A grey texture of wood growth rings in range [0, 1], concentric around the y axis and
+/// distorted by noise.
+///
+/// Wrap it in a [`ColorRamp`](super::ColorRamp) to color the rings.
+#[derive(Debug)]
+pub struct Wood {
+    noise: Perlin,
+    /// Number of rings per unit of distance to the axis.
+    rings: f32,
+    /// Distortion of the rings, in rings.
+    turbulence: f32,
+}
+
+impl Wood {
+    /// Constructs a new `Wood` texture generated from `seed`, with 10 rings per unit.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::{ColorRamp, Wood};
+    /// use crab_rt::vec::Color3;
+    ///
+    /// let wood = ColorRamp::new(
+    ///     Wood::new(7),
+    ///     vec![
+    ///         (0., Color3::new(0.75, 0.5, 0.3)),
+    ///         (1., Color3::new(0.45, 0.25, 0.1)),
+    ///     ],
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self {
+            noise: Perlin::with_seed(seed),
+            rings: 10.,
+            turbulence: 1.,
+        }
+    }
+
+    /// Consumes the `Wood` and returns self with the given number of rings per unit of
+    /// distance to the axis.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::Wood;
+    ///
+    /// let texture = Wood::new(7).rings(25.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn rings(self, rings: f32) -> Self {
+        Self { rings, ..self }
+    }
+
+    /// Consumes the `Wood` and returns self with the rings distorted by `turbulence` rings. The
+    /// default is 1.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::textures::Wood;
+    ///
+    /// let texture = Wood::new(7).turbulence(0.3);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn turbulence(self, turbulence: f32) -> Self {
+        Self { turbulence, ..self }
+    }
+}
+
+impl Texture for Wood {
+    #[inline]
+    fn value(&self, _texture_coordinates: (f32, f32), p: &Point3) -> Vec3 {
+        let distance = f32::hypot(p.x, p.z);
+        let noise = self.noise.fbm(&(self.rings * p), 4, 2., 0.5);
+        let ring = self.rings.mul_add(distance, self.turbulence * noise);
+        let value = ring - ring.floor();
+        Vec3::new(value, value, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wood_rings_repeat_with_radius() {
+        let testee = Wood::new(42).rings(10.).turbulence(0.);
+        let value = |radius: f32| testee.value((0., 0.), &Point3::new(radius, 0.3, 0.)).x;
+
+        for radius in [0.25, 0.52, 1.13] {
+            assert!((value(radius) - value(radius + 0.1)).abs() < 1e-4);
+            assert!((value(radius) - value(radius + 0.05)).abs() > 0.4);
+        }
+    }
+}
@@ -136,6 +136,30 @@ pub fn random_in_unit_disk() -> Vec3 {
     p
 }
 
+/// Returns two unit vectors forming with the unit vector `n` an orthonormal basis.
+///
+/// # Examples
+/// ```
+/// use crab_rt::utils::orthonormal_basis;
+/// use crab_rt::vec::Vec3;
+///
+/// let n = Vec3::new(0., 0., 1.);
+/// let (s, t) = orthonormal_basis(&n);
+/// assert!(s.dot(&n).abs() < 1e-6 && t.dot(&n).abs() < 1e-6 && s.dot(&t).abs() < 1e-6);
+/// ```
+#[must_use]
+pub fn orthonormal_basis(n: &Vec3) -> (Vec3, Vec3) {
+    // Building an Orthonormal Basis, Revisited (Duff et al. 2017)
+    let sign = 1f32.copysign(n.z);
+    let a = -1. / (sign + n.z);
+    let b = n.x * n.y * a;
+
+    (
+        Vec3::new((sign * n.x * n.x).mul_add(a, 1.), sign * b, -sign * n.x),
+        Vec3::new(b, n.y.mul_add(n.y * a, sign), -n.y),
+    )
+}
+
 /// Computes the outcoming reflection vector with the given incoming vector and normal.
 /// We now that the angle between the incoming vector and the normal is equal to the angle
 /// between the outcoming vector and the normal.
@@ -196,6 +220,16 @@ pub fn gamma_decode(x: f32) -> f32 {
     x.powf(GAMMA)
 }
 
+/// Converts an index or a count of samples to `f32`.
+///
+/// Grids of samples are far smaller than the 2^24 integers a `f32` represents exactly.
+#[allow(clippy::cast_precision_loss)]
+#[inline]
+#[must_use]
+pub(crate) const fn index_to_f32(i: usize) -> f32 {
+    i as f32
+}
+
 /// Return the `num_views` [`PartialRowViewMut`] of the given slice.
 ///
 /// # Panics
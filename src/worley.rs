@@ -0,0 +1,108 @@
+use crate::vec::{Point3, Vec3};
+
+#[cfg(not(feature = "std"))]
+use core_maths::*;
+
+/// Worley cellular noise, built from one random feature point per unit cell of space.
+///
+/// The distances to the closest feature points draw a Voronoi diagram, used for cells, stones,
+/// scales or cracks.
+#[derive(Debug, Clone, Copy)]
+pub struct Worley {
+    seed: u64,
+}
+
+impl Worley {
+    /// Constructs a new `Worley` noise generated from `seed`, so that the same seed always gives
+    /// the same noise.
+    ///
+    /// # Examples
+    /// ```
+    /// use crab_rt::vec::Point3;
+    /// use crab_rt::worley::Worley;
+    ///
+    /// let p = Point3::new(0.3, 1.7, -2.1);
+    /// assert_eq!(Worley::new(7).distances(&p), Worley::new(7).distances(&p));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    /// Returns the distances from `p` to the closest and second closest feature points.
+    #[must_use]
+    pub fn distances(self, p: &Point3) -> (f32, f32) {
+        // The floors are whole numbers, which only saturate far away from the origin
+        #[allow(clippy::cast_possible_truncation)]
+        let cell = (p.x.floor() as i64, p.y.floor() as i64, p.z.floor() as i64);
+
+        let mut closest = (f32::INFINITY, f32::INFINITY);
+        for i in cell.0 - 1..=cell.0 + 1 {
+            for j in cell.1 - 1..=cell.1 + 1 {
+                for k in cell.2 - 1..=cell.2 + 1 {
+                    let distance = (self.feature_point(i, j, k) - p).length();
+                    if distance < closest.0 {
+                        closest = (distance, closest.0);
+                    } else if distance < closest.1 {
+                        closest.1 = distance;
+                    }
+                }
+            }
+        }
+
+        closest
+    }
+
+    /// Returns the feature point of the cell at the given integer coordinates.
+    fn feature_point(self, i: i64, j: i64, k: i64) -> Point3 {
+        let mut hash = self.seed;
+        for coordinate in [i, j, k] {
+            hash = splitmix64(hash ^ coordinate.cast_unsigned());
+        }
+
+        // Uses 21 bits of the hash for each coordinate of the point in the cell, which a f32
+        // represents exactly
+        #[allow(clippy::cast_precision_loss)]
+        let offset = |shift: u32| ((hash >> shift) & 0x1f_ffff) as f32 / 2_097_152.;
+        // The cells are as precise as the points that fall in them
+        #[allow(clippy::cast_precision_loss)]
+        let cell = Point3::new(i as f32, j as f32, k as f32);
+        cell + Vec3::new(offset(0), offset(21), offset(42))
+    }
+}
+
+/// The `SplitMix64` mixing function, scrambling the bits of `x`.
+#[inline]
+const fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn worley_distances_are_ordered() {
+        let testee = Worley::new(42);
+
+        for n in 0..100_u8 {
+            let t = f32::from(n) * 0.173;
+            let (f1, f2) = testee.distances(&Point3::new(t, 2. * t, -t));
+            assert!(f1 <= f2);
+            // A feature point lies in the cell of the point
+            assert!(f1 <= f32::sqrt(3.));
+        }
+    }
+
+    #[test]
+    fn worley_is_zero_at_feature_points() {
+        let testee = Worley::new(42);
+        let feature_point = testee.feature_point(3, -2, 5);
+
+        assert!(testee.distances(&feature_point).0 < 1e-5);
+    }
+}